@@ -0,0 +1,69 @@
+//! Compares the batch motor application with calling the scalar
+//! apply_to_* once per element. Run with `cargo bench --bench batch`.
+
+use ppga::*;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const N: usize = 100_000;
+const RUNS: u32 = 20;
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    f();
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        f();
+    }
+    start.elapsed() / RUNS
+}
+
+fn report(name: &str, scalar: Duration, batch: Duration) {
+    println!(
+        "{:<8} scalar {:>10.3?}   batch {:>10.3?}   speedup {:.2}x",
+        name,
+        scalar,
+        batch,
+        scalar.as_secs_f64() / batch.as_secs_f64()
+    );
+}
+
+fn main() {
+    let r = Rotor::new(1.3, &[0.0, 0.6, 0.8]);
+    let m = Translator::new(&[1., -2., 0.5]).mul_rotor(&r);
+
+    let mut points: Vec<Point> = (0..N).map(|_| Point::random()).collect();
+    let scalar = time(|| {
+        for p in points.iter_mut() {
+            *p = black_box(&m).apply_to_point(p);
+        }
+    });
+    let batch = time(|| black_box(&m).apply_to_points_mut(&mut points));
+    report("points", scalar, batch);
+
+    let mut planes: Vec<Plane> = (0..N).map(|_| Plane::random()).collect();
+    let scalar = time(|| {
+        for p in planes.iter_mut() {
+            *p = black_box(&m).apply_to_plane(p);
+        }
+    });
+    let batch = time(|| black_box(&m).apply_to_planes_mut(&mut planes));
+    report("planes", scalar, batch);
+
+    let mut lines: Vec<Line> = (0..N).map(|_| Line::random()).collect();
+    let scalar = time(|| {
+        for l in lines.iter_mut() {
+            *l = black_box(&m).apply_to_line(l);
+        }
+    });
+    let batch = time(|| black_box(&m).apply_to_lines_mut(&mut lines));
+    report("lines", scalar, batch);
+
+    let mut xs: Vec<[f32; 3]> = points.iter().map(|p| p.eucl()).collect();
+    let scalar = time(|| {
+        for x in xs.iter_mut() {
+            *x = black_box(&m).apply_to_point(&Point::new(x)).eucl();
+        }
+    });
+    let batch = time(|| black_box(&m).apply_to_eucl_mut(&mut xs));
+    report("eucl", scalar, batch);
+}
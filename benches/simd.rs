@@ -0,0 +1,101 @@
+//! Compares the opt-in SIMD kernels with the scalar generated expressions
+//! the Motor methods use, and the bulk point path with them.
+//! Run with `cargo bench --bench simd`.
+
+use ppga::*;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const N: usize = 100_000;
+const RUNS: u32 = 20;
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    f();
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        f();
+    }
+    start.elapsed() / RUNS
+}
+
+fn report(name: &str, backend: &str, scalar: Duration, simd: Duration) {
+    println!(
+        "{:<8} scalar {:>10.3?}   {} {:>10.3?}   speedup {:.2}x",
+        name,
+        scalar,
+        backend,
+        simd,
+        scalar.as_secs_f64() / simd.as_secs_f64()
+    );
+}
+
+fn main() {
+    let motors: Vec<Motor> = (0..N).map(|_| Motor::random().normalize()).collect();
+    let points: Vec<Point> = (0..N).map(|_| Point::random()).collect();
+    let planes: Vec<Plane> = (0..N).map(|_| Plane::random()).collect();
+    let lines: Vec<Line> = (0..N).map(|_| Line::random()).collect();
+    let m = motors[0];
+
+    let scalar = time(|| {
+        let mut acc = Motor::one();
+        for b in motors.iter() {
+            acc = black_box(products::geometric_motor_motor(&acc, b));
+        }
+    });
+    let simd = time(|| {
+        let mut acc = Motor::one();
+        for b in motors.iter() {
+            acc = black_box(simd::mul(&acc, b));
+        }
+    });
+    report("mul", simd::BACKEND, scalar, simd);
+
+    let scalar = time(|| {
+        for p in points.iter() {
+            black_box(products::sandwich_motor_point(&m, p));
+        }
+    });
+    let simd = time(|| {
+        for p in points.iter() {
+            black_box(simd::apply_to_point(&m, p));
+        }
+    });
+    report("points", simd::BACKEND, scalar, simd);
+
+    let scalar = time(|| {
+        for p in planes.iter() {
+            black_box(products::sandwich_motor_plane(&m, p));
+        }
+    });
+    let simd = time(|| {
+        for p in planes.iter() {
+            black_box(simd::apply_to_plane(&m, p));
+        }
+    });
+    report("planes", simd::BACKEND, scalar, simd);
+
+    let scalar = time(|| {
+        for l in lines.iter() {
+            black_box(products::sandwich_motor_line(&m, l));
+        }
+    });
+    let simd = time(|| {
+        for l in lines.iter() {
+            black_box(simd::apply_to_line(&m, l));
+        }
+    });
+    report("lines", simd::BACKEND, scalar, simd);
+
+    let mut moved = points.clone();
+    let scalar = time(|| {
+        for p in moved.iter_mut() {
+            *p = products::sandwich_motor_point(&m, p);
+        }
+        black_box(&moved);
+    });
+    let simd = time(|| {
+        m.apply_to_points_mut(&mut moved);
+        black_box(&moved);
+    });
+    report("batch", simd::batch_backend(), scalar, simd);
+}
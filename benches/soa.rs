@@ -0,0 +1,80 @@
+//! Compares the structure-of-arrays kernels with loops over the structs.
+//! Run with `cargo bench --bench soa`.
+
+use ppga::soa::*;
+use ppga::*;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const N: usize = 100_000;
+const RUNS: u32 = 20;
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    f();
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        f();
+    }
+    start.elapsed() / RUNS
+}
+
+fn report(name: &str, aos: Duration, soa: Duration) {
+    println!(
+        "{:<10} aos {:>10.3?}   soa {:>10.3?}   speedup {:.2}x",
+        name,
+        aos,
+        soa,
+        aos.as_secs_f64() / soa.as_secs_f64()
+    );
+}
+
+fn main() {
+    let motors: Vec<Motor> = (0..N).map(|_| Motor::random().normalize()).collect();
+    let points: Vec<Point> = (0..N).map(|_| Point::random()).collect();
+    let lines: Vec<Line> = (0..N).map(|_| Line::random()).collect();
+    let motors_soa = MotorSoA::from(&motors[..]);
+    let points_soa = PointSoA::from(&points[..]);
+    let lines_soa = LineSoA::from(&lines[..]);
+
+    let aos = time(|| {
+        let r: Vec<Motor> = motors.iter().map(|m| m.mul(m)).collect();
+        black_box(r);
+    });
+    let soa = time(|| {
+        black_box(motors_soa.mul(&motors_soa));
+    });
+    report("mul", aos, soa);
+
+    let aos = time(|| {
+        let r: Vec<Point> = motors
+            .iter()
+            .zip(points.iter())
+            .map(|(m, p)| m.apply_to_point(p))
+            .collect();
+        black_box(r);
+    });
+    let soa = time(|| {
+        black_box(motors_soa.apply_to_points(&points_soa));
+    });
+    report("points", aos, soa);
+
+    let aos = time(|| {
+        let r: Vec<Motor> = motors.iter().map(|m| m.normalize()).collect();
+        black_box(r);
+    });
+    let soa = time(|| {
+        let mut r = motors_soa.clone();
+        r.normalize();
+        black_box(r);
+    });
+    report("normalize", aos, soa);
+
+    let aos = time(|| {
+        let r: Vec<Motor> = lines.iter().map(|l| l.exp()).collect();
+        black_box(r);
+    });
+    let soa = time(|| {
+        black_box(lines_soa.exp());
+    });
+    report("exp", aos, soa);
+}
@@ -0,0 +1,40 @@
+use std::{env, fs, path::Path};
+
+#[allow(dead_code)]
+#[path = "src/codegen.rs"]
+mod codegen;
+#[allow(dead_code)]
+#[path = "src/error.rs"]
+mod error;
+#[allow(dead_code)]
+#[path = "src/shader/transpile.rs"]
+mod transpile;
+
+fn main() {
+    println!("cargo:rerun-if-changed=glsl/ppga.glsl");
+    println!("cargo:rerun-if-changed=src/shader/transpile.rs");
+    println!("cargo:rerun-if-changed=src/error.rs");
+    println!("cargo:rerun-if-changed=src/codegen.rs");
+
+    let out = env::var("OUT_DIR").unwrap();
+    let out = Path::new(&out);
+
+    fs::write(
+        out.join("products.rs"),
+        codegen::generate(codegen::PRODUCTS),
+    )
+    .unwrap();
+
+    let src = fs::read_to_string("glsl/ppga.glsl").unwrap();
+    let lib = transpile::parse(&src).unwrap_or_else(|e| panic!("glsl/ppga.glsl: {}", e));
+    fs::write(
+        out.join("ppga.wgsl"),
+        transpile::emit(&lib, transpile::Language::Wgsl),
+    )
+    .unwrap();
+    fs::write(
+        out.join("ppga.hlsl"),
+        transpile::emit(&lib, transpile::Language::Hlsl),
+    )
+    .unwrap();
+}
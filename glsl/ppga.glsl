@@ -19,6 +19,13 @@ struct ppga_motor
     vec4 p2;
 };
 
+// eucl -> (e23, e31, e12), vanish -> (e01, e02, e03)
+struct ppga_line
+{
+    vec3 eucl;
+    vec3 vanish;
+};
+
 vec3 ppga_apply_rotor_to_direction(ppga_rotor r, vec3 d) {
     vec4 mask1 = vec4(1, 1, -1, -1);
     vec4 mask2 = vec4(1, 1, 1, -1);
@@ -46,39 +53,34 @@ vec3 ppga_apply_motor_to_origin(ppga_motor m) {
     return 2.0 * res;
 }
 
-// Not properly tested yet
+// Line::exp, with the series around 0 below u = 0.01
 ppga_motor ppga_exp(vec3 eucl, vec3 vanish) {
-    float bdb = dot(eucl, eucl);
-    if (bdb < 0.01)
-        return ppga_motor(vec4(1, eucl), vec4(0, vanish));
-    float u = sqrt(bdb);
-    float v = -dot(eucl, vanish); // meet operation
-    float cu = cos(u);
-    float su = sin(u);
-    float uinv = 1 / u;
-    float suu = su * uinv;
-    float cuvu = -cu * v * uinv;
-    float suvu2 = -su * v / bdb;
-
-    return ppga_motor(vec4(cu,
-                           eucl.x * cuvu + eucl.x * suvu2 + vanish.x * suu,
-                           eucl.y * cuvu + eucl.y * suvu2 + vanish.y * suu,
-                           eucl.z * cuvu + eucl.z * suvu2 + vanish.z * suu),
-                      vec4(-su * v,
-                           eucl.x * suu,
-                           eucl.y * suu,
-                           eucl.z * suu));
+    float u2 = dot(eucl, eucl);
+    float u = sqrt(u2);
+    float ev = dot(eucl, vanish);
+    float cu = 1.0 - 0.5 * u2;
+    float a = 1.0 - u2 / 6.0;
+    float b = -1.0 / 3.0;
+    if (u >= 0.01) {
+        cu = cos(u);
+        a = sin(u) / u;
+        b = (cu - a) / u2;
+    }
+    return ppga_motor(vec4(cu, eucl * a), vec4(a * ev, vanish * a + eucl * (b * ev)));
 }
 
+// Line::cayley_exp scaled by (1 + dot(eucl, eucl)) / 2
 ppga_motor ppga_cayley_exp(vec3 eucl, vec3 vanish) {
     return ppga_motor(vec4(0.5 - 0.5 * dot(eucl, eucl), -eucl),
                       vec4(dot(eucl, vanish), -vanish));
 }
 
+// Same for a line through the origin
 ppga_rotor ppga_cayley_exp(vec3 eucl) {
     return ppga_rotor(vec4(0.5 - 0.5 * dot(eucl, eucl), -eucl));
 }
 
+// Line::outer_exp
 ppga_motor ppga_outer_exp(vec3 eucl, vec3 vanish) {
     vec4 p1 = vec4(1.0, eucl);
     float normalizer = 1 / length(p1);
@@ -92,8 +94,119 @@ ppga_rotor ppga_outer_exp(vec3 eucl) {
     return ppga_rotor(p1 * normalizer);
 }
 
+// Rotor::qtangent_exp
 ppga_rotor ppga_qtangent_exp(vec3 eucl) {
     float w = sqrt(1 - dot(eucl, eucl)); 
     return ppga_rotor(vec4(w, eucl));
 }
-#endif
\ No newline at end of file
+
+// Motor::mul
+ppga_motor ppga_motor_mul(ppga_motor a, ppga_motor b) {
+    float s1 = a.p1.x;
+    float s2 = b.p1.x;
+    float p1 = a.p2.x;
+    float p2 = b.p2.x;
+    vec3 e1 = a.p1.yzw;
+    vec3 e2 = b.p1.yzw;
+    vec3 v1 = a.p2.yzw;
+    vec3 v2 = b.p2.yzw;
+    return ppga_motor(vec4(s1 * s2 - dot(e1, e2), s1 * e2 + s2 * e1 - cross(e1, e2)),
+                      vec4(s1 * p2 + s2 * p1 + dot(e1, v2) + dot(v1, e2),
+                           s1 * v2 + s2 * v1 - p2 * e1 - p1 * e2 - cross(e1, v2) - cross(v1, e2)));
+}
+
+// Rotor::mul
+ppga_rotor ppga_rotor_mul(ppga_rotor a, ppga_rotor b) {
+    float s1 = a.p1.x;
+    float s2 = b.p1.x;
+    vec3 e1 = a.p1.yzw;
+    vec3 e2 = b.p1.yzw;
+    return ppga_rotor(vec4(s1 * s2 - dot(e1, e2), s1 * e2 + s2 * e1 - cross(e1, e2)));
+}
+
+ppga_motor ppga_motor_reverse(ppga_motor m) {
+    return ppga_motor(vec4(m.p1.x, -m.p1.yzw), vec4(m.p2.x, -m.p2.yzw));
+}
+
+// Motor::normalize
+ppga_motor ppga_motor_normalize(ppga_motor m) {
+    float normalizer = 1.0 / length(m.p1);
+    return ppga_motor(m.p1 * normalizer, m.p2 * normalizer);
+}
+
+ppga_rotor ppga_rotor_normalize(ppga_rotor r) {
+    return ppga_rotor(r.p1 / length(r.p1));
+}
+
+// Point::normalize, p3 -> (e123, e032, e013, e021)
+vec4 ppga_point_normalize(vec4 p) {
+    return p / p.x;
+}
+
+// Plane::normalize, p0 -> (e0, e1, e2, e3)
+vec4 ppga_plane_normalize(vec4 p) {
+    return p / length(p.yzw);
+}
+
+// Rotation part of every sandwich, for a normalized motor the same as
+// ppga_apply_rotor_to_direction:
+//     (s^2 - e.e) x + 2 (e.x) e + 2 s (x cross e)
+vec3 ppga_rotate(ppga_motor m, vec3 x) {
+    float s = m.p1.x;
+    vec3 e = m.p1.yzw;
+    return (s * s - dot(e, e)) * x + 2.0 * dot(e, x) * e + 2.0 * s * cross(x, e);
+}
+
+// Motor::apply_to_point, p3 -> (e123, e032, e013, e021)
+vec4 ppga_apply_motor_to_point(ppga_motor m, vec4 p) {
+    float s = m.p1.x;
+    float ps = m.p2.x;
+    vec3 e = m.p1.yzw;
+    vec3 v = m.p2.yzw;
+    float w = p.x;
+    vec3 t = cross(e, v) - s * v - ps * e;
+    return vec4(w * (s * s + dot(e, e)), ppga_rotate(m, p.yzw) + 2.0 * w * t);
+}
+
+// Motor::apply_to_plane, p0 -> (e0, e1, e2, e3)
+vec4 ppga_apply_motor_to_plane(ppga_motor m, vec4 p) {
+    float s = m.p1.x;
+    float ps = m.p2.x;
+    vec3 e = m.p1.yzw;
+    vec3 v = m.p2.yzw;
+    vec3 n = p.yzw;
+    float d = p.x * (s * s + dot(e, e)) + 2.0 * (ps * dot(e, n) + s * dot(v, n) + dot(e, cross(v, n)));
+    return vec4(d, ppga_rotate(m, n));
+}
+
+// Motor::apply_to_line
+ppga_line ppga_apply_motor_to_line(ppga_motor m, ppga_line l) {
+    float s = m.p1.x;
+    float ps = m.p2.x;
+    vec3 e = m.p1.yzw;
+    vec3 v = m.p2.yzw;
+    vec3 le = l.eucl;
+    vec3 t = v * dot(e, le) + e * dot(v, le) - le * dot(e, v);
+    t -= ps * s * le + ps * cross(le, e) - s * cross(le, v);
+    return ppga_line(ppga_rotate(m, le), ppga_rotate(m, l.vanish) + 2.0 * t);
+}
+
+// Motor::ln
+ppga_line ppga_ln(ppga_motor m) {
+    float s1 = m.p1.x;
+    float p = m.p2.x;
+    vec3 be = m.p1.yzw;
+    vec3 bv = m.p2.yzw;
+    float s2sq = dot(be, be);
+    float s2 = sqrt(s2sq);
+    // Series around s2 = 0, for s1 < 0 the log of -m.
+    float r = 1.0 / s1 - s2sq / (3.0 * s1 * s1 * s1);
+    float c = -1.0 / (3.0 * s1 * s1);
+    if (s2 >= 0.01 * abs(s1)) {
+        float u = atan(s2, s1);
+        r = u / s2;
+        c = (u * s1 - s2) / (s2sq * s2);
+    }
+    return ppga_line(be * r, bv * r - be * (c * p));
+}
+#endif
@@ -0,0 +1,283 @@
+//! Batch application of a motor to many elements.
+//!
+//! The sandwich product is linear in the element it is applied to, so for a
+//! fixed motor it is just a matrix. Motor::matrix computes these matrices
+//! once (by applying the motor to the basis elements), after which every
+//! element costs a single small matrix-vector product instead of the full
+//! generated expression.
+//!
+//! The par module has parallel versions of everything here, behind the
+//! rayon feature.
+
+use super::{Line, Motor, Plane, Point};
+
+/// A motor precomputed into matrices acting on the coordinates
+/// of points (trivector), planes (vector) and lines ([e_bivector, v_bivector]).
+#[derive(Debug, Copy, Clone)]
+pub struct MotorMatrix {
+    pub point: [[f32; 4]; 4],
+    pub plane: [[f32; 4]; 4],
+    pub line: [[f32; 6]; 6],
+    /// The familiar 3x4 matrix [R | t] acting on euclidean coordinates.
+    /// Only valid for a normalized motor.
+    pub eucl: [[f32; 4]; 3],
+}
+
+impl MotorMatrix {
+    pub fn new(m: &Motor) -> Self {
+        let mut point = [[0.0; 4]; 4];
+        let mut plane = [[0.0; 4]; 4];
+        let mut line = [[0.0; 6]; 6];
+        for col in 0..4 {
+            let mut b = [0.0; 4];
+            b[col] = 1.0;
+            let p = m.apply_to_point(&Point { trivector: b }).trivector;
+            let v = m.apply_to_plane(&Plane { vector: b }).vector;
+            for row in 0..4 {
+                point[row][col] = p[row];
+                plane[row][col] = v[row];
+            }
+        }
+        for col in 0..6 {
+            let mut b = [0.0; 6];
+            b[col] = 1.0;
+            let l = m.apply_to_line(&Line::from_tangent(&b)).tangent();
+            for row in 0..6 {
+                line[row][col] = l[row];
+            }
+        }
+        // With trivector = [1, -x, -y, -z], x' = -(M (1, -x)) so the
+        // rotation block keeps its sign and the translation flips.
+        let mut eucl = [[0.0; 4]; 3];
+        for row in 0..3 {
+            let p = point[row + 1];
+            eucl[row] = [p[1], p[2], p[3], -p[0]];
+        }
+        Self {
+            point,
+            plane,
+            line,
+            eucl,
+        }
+    }
+
+    pub fn apply_to_point(&self, p: &Point) -> Point {
+        Point {
+            trivector: mul4(&self.point, &p.trivector),
+        }
+    }
+
+    pub fn apply_to_plane(&self, p: &Plane) -> Plane {
+        Plane {
+            vector: mul4(&self.plane, &p.vector),
+        }
+    }
+
+    pub fn apply_to_line(&self, l: &Line) -> Line {
+        let t = l.tangent();
+        let m = &self.line;
+        let mut r = [0.0; 6];
+        for (row, r) in r.iter_mut().enumerate() {
+            *r = m[row][0] * t[0]
+                + m[row][1] * t[1]
+                + m[row][2] * t[2]
+                + m[row][3] * t[3]
+                + m[row][4] * t[4]
+                + m[row][5] * t[5];
+        }
+        Line::from_tangent(&r)
+    }
+
+    /// Moves euclidean coordinates, assumes a normalized motor.
+    pub fn apply_to_eucl(&self, x: &[f32; 3]) -> [f32; 3] {
+        let e = &self.eucl;
+        let mut r = [0.0; 3];
+        for (row, r) in r.iter_mut().enumerate() {
+            *r = e[row][0] * x[0] + e[row][1] * x[1] + e[row][2] * x[2] + e[row][3];
+        }
+        r
+    }
+}
+
+fn mul4(m: &[[f32; 4]; 4], x: &[f32; 4]) -> [f32; 4] {
+    let mut r = [0.0; 4];
+    for (row, r) in r.iter_mut().enumerate() {
+        *r = m[row][0] * x[0] + m[row][1] * x[1] + m[row][2] * x[2] + m[row][3] * x[3];
+    }
+    r
+}
+
+impl Motor {
+    pub fn matrix(&self) -> MotorMatrix {
+        MotorMatrix::new(self)
+    }
+
+    pub fn apply_to_points(&self, ps: &[Point]) -> Vec<Point> {
+        let m = self.matrix();
+        ps.iter().map(|p| m.apply_to_point(p)).collect()
+    }
+    pub fn apply_to_points_mut(&self, ps: &mut [Point]) {
+        super::simd::mul4_points(&self.matrix().point, ps);
+    }
+
+    pub fn apply_to_planes(&self, ps: &[Plane]) -> Vec<Plane> {
+        let m = self.matrix();
+        ps.iter().map(|p| m.apply_to_plane(p)).collect()
+    }
+    pub fn apply_to_planes_mut(&self, ps: &mut [Plane]) {
+        let m = self.matrix();
+        for p in ps.iter_mut() {
+            p.vector = mul4(&m.plane, &p.vector);
+        }
+    }
+
+    pub fn apply_to_lines(&self, ls: &[Line]) -> Vec<Line> {
+        let m = self.matrix();
+        ls.iter().map(|l| m.apply_to_line(l)).collect()
+    }
+    pub fn apply_to_lines_mut(&self, ls: &mut [Line]) {
+        let m = self.matrix();
+        for l in ls.iter_mut() {
+            *l = m.apply_to_line(l);
+        }
+    }
+
+    /// Moves raw euclidean coordinates. Assumes a normalized motor.
+    pub fn apply_to_eucl(&self, xs: &[[f32; 3]]) -> Vec<[f32; 3]> {
+        let m = self.matrix();
+        xs.iter().map(|x| m.apply_to_eucl(x)).collect()
+    }
+    pub fn apply_to_eucl_mut(&self, xs: &mut [[f32; 3]]) {
+        let m = self.matrix();
+        for x in xs.iter_mut() {
+            *x = m.apply_to_eucl(x);
+        }
+    }
+}
+
+/// Block size of chain. Fixed, so that the serial and parallel versions
+/// multiply in the same order and give identical results.
+pub const CHAIN_BLOCK: usize = 256;
+
+/// Prefix products r[i] = ms[0] * ... * ms[i], e.g., to turn the local
+/// motors of a kinematic chain into global ones. Every block of CHAIN_BLOCK
+/// motors is accumulated on its own and then moved by the product of all
+/// blocks before it.
+pub fn chain(ms: &[Motor]) -> Vec<Motor> {
+    let mut r = ms.to_vec();
+    r.chunks_mut(CHAIN_BLOCK).for_each(prefix);
+    let offsets = block_offsets(&r);
+    for (block, offset) in r.chunks_mut(CHAIN_BLOCK).skip(1).zip(offsets.iter()) {
+        apply_offset(block, offset);
+    }
+    r
+}
+
+pub(crate) fn prefix(block: &mut [Motor]) {
+    for i in 1..block.len() {
+        block[i] = block[i - 1].mul(&block[i]);
+    }
+}
+
+/// Products of all blocks before block 1, 2, ...
+pub(crate) fn block_offsets(r: &[Motor]) -> Vec<Motor> {
+    let mut offsets: Vec<Motor> = Vec::new();
+    for block in r.chunks(CHAIN_BLOCK) {
+        let last = block[block.len() - 1];
+        let offset = match offsets.last() {
+            Some(o) => o.mul(&last),
+            None => last,
+        };
+        offsets.push(offset);
+    }
+    offsets.pop();
+    offsets
+}
+
+pub(crate) fn apply_offset(block: &mut [Motor], offset: &Motor) {
+    for m in block.iter_mut() {
+        *m = offset.mul(m);
+    }
+}
+
+pub fn exp(ls: &[Line]) -> Vec<Motor> {
+    ls.iter().map(|l| l.exp()).collect()
+}
+
+pub fn ln(ms: &[Motor]) -> Vec<Line> {
+    ms.iter().map(|m| m.ln()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn test_motor() -> Motor {
+        let r = Rotor::new(1.3, &na::Vec3::new(0.4, -1., 0.3).normalize().into());
+        Translator::new(&[1., -2., 0.5]).mul_rotor(&r)
+    }
+
+    #[test]
+    fn same_as_scalar() {
+        let m = test_motor();
+        let points: Vec<Point> = (0..100).map(|_| Point::random()).collect();
+        let planes: Vec<Plane> = (0..100).map(|_| Plane::random()).collect();
+        let lines: Vec<Line> = (0..100).map(|_| Line::random()).collect();
+
+        for (p, p_) in points.iter().zip(m.apply_to_points(&points).iter()) {
+            assert_eq!(m.apply_to_point(p), *p_);
+        }
+        for (p, p_) in planes.iter().zip(m.apply_to_planes(&planes).iter()) {
+            assert_eq!(m.apply_to_plane(p), *p_);
+        }
+        for (l, l_) in lines.iter().zip(m.apply_to_lines(&lines).iter()) {
+            let a = m.apply_to_line(l).tangent();
+            let b = l_.tangent();
+            assert!(a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 1e-4));
+        }
+
+        let mut moved = lines.clone();
+        m.apply_to_lines_mut(&mut moved);
+        for (l, l_) in lines.iter().zip(moved.iter()) {
+            let a = m.apply_to_line(l).tangent();
+            let b = l_.tangent();
+            assert!(a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 1e-4));
+        }
+    }
+
+    #[test]
+    fn eucl() {
+        let m = test_motor();
+        let mut xs: Vec<[f32; 3]> = (0..100).map(|_| na::Vec3::new_random().into()).collect();
+        let moved = m.apply_to_eucl(&xs);
+        m.apply_to_eucl_mut(&mut xs);
+        for (x, x_) in xs.iter().zip(moved.iter()) {
+            assert_eq!(x, x_);
+        }
+        let points: Vec<Point> = (0..100).map(|_| Point::random()).collect();
+        let mut points_ = points
+            .iter()
+            .map(|p| Point::new(&p.eucl()))
+            .collect::<Vec<_>>();
+        m.apply_to_points_mut(&mut points_);
+        for (p, p_) in points.iter().zip(points_.iter()) {
+            let x = m.matrix().apply_to_eucl(&p.eucl());
+            assert_eq!(Point::new(&x), *p_);
+            assert_eq!(m.apply_to_point(p), *p_);
+        }
+    }
+
+    #[test]
+    fn chain() {
+        let ms: Vec<Motor> = (0..1000)
+            .map(|_| Line::random().mul_scalar(0.01).exp().normalize())
+            .collect();
+        let r = batch::chain(&ms);
+        let mut acc = Motor::one();
+        for (m, r) in ms.iter().zip(r.iter()) {
+            acc = acc.mul(m);
+            assert_eq!(acc, *r);
+        }
+        assert!(batch::chain(&[]).is_empty());
+    }
+}
@@ -0,0 +1,405 @@
+//! Bounding volumes for broad-phase collision and culling: axis aligned
+//! boxes, spheres and oriented boxes, all movable by motors.
+//!
+//! Moving a sphere or an oriented box is exact. An axis aligned box can
+//! only stay axis aligned by growing, so Aabb::apply is conservative and
+//! Obb::from(&aabb).apply(m) is the exact alternative.
+
+use super::{Applicable, Motor, Point, Rotor};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+#[derive(Debug)]
+pub struct Sphere {
+    pub center: Point,
+    pub radius: f32,
+}
+
+/// A box with half extents half along the axes of the rotor, i.e.,
+/// the rotor applied to x, y and z, around center.
+#[derive(Debug)]
+pub struct Obb {
+    pub center: Point,
+    pub rotor: Rotor,
+    pub half: [f32; 3],
+}
+
+/// Whether two volumes share a point.
+pub trait Overlaps<T> {
+    fn overlaps(&self, other: &T) -> bool;
+}
+
+impl Aabb {
+    pub fn new(min: &[f32; 3], max: &[f32; 3]) -> Self {
+        Self {
+            min: *min,
+            max: *max,
+        }
+    }
+
+    /// None for no points.
+    pub fn from_points(points: &[Point]) -> Option<Self> {
+        let first = points.first()?.normalize().eucl();
+        let mut b = Self::new(&first, &first);
+        for p in points.iter() {
+            b = b.merge_point(&p.normalize().eucl());
+        }
+        Some(b)
+    }
+
+    pub fn center(&self) -> [f32; 3] {
+        (self.min_() + self.max_()).scale(0.5).into()
+    }
+    pub fn half(&self) -> [f32; 3] {
+        (self.max_() - self.min_()).scale(0.5).into()
+    }
+
+    pub fn contains(&self, p: &Point) -> bool {
+        let x = p.normalize().eucl();
+        (0..3).all(|i| self.min[i] <= x[i] && x[i] <= self.max[i])
+    }
+
+    pub fn merge(&self, other: &Self) -> Self {
+        self.merge_point(&other.min).merge_point(&other.max)
+    }
+
+    /// Larger by d on every side.
+    pub fn grow(&self, d: f32) -> Self {
+        let d = na::Vec3::repeat(d);
+        Self::new(&(self.min_() - d).into(), &(self.max_() + d).into())
+    }
+
+    fn merge_point(&self, x: &[f32; 3]) -> Self {
+        let x = na::Vec3::from(*x);
+        Self::new(&self.min_().inf(&x).into(), &self.max_().sup(&x).into())
+    }
+
+    /// The point of the box closest to x.
+    fn closest(&self, x: &na::Vec3) -> na::Vec3 {
+        na::Vec3::from_fn(|i, _| x[i].max(self.min[i]).min(self.max[i]))
+    }
+
+    fn min_(&self) -> na::Vec3 {
+        na::Vec3::from(self.min)
+    }
+    fn max_(&self) -> na::Vec3 {
+        na::Vec3::from(self.max)
+    }
+}
+
+/// Conservative, the box around the moved box.
+impl Applicable for Aabb {
+    fn apply(&self, m: &Motor) -> Self {
+        Aabb::from(&Obb::from(self).apply(m))
+    }
+}
+
+impl Sphere {
+    pub fn new(center: &[f32; 3], radius: f32) -> Self {
+        Self {
+            center: Point::new(center),
+            radius,
+        }
+    }
+
+    /// Around the center of the bounding box, not the smallest one.
+    pub fn from_points(points: &[Point]) -> Option<Self> {
+        let c = na::Vec3::from(Aabb::from_points(points)?.center());
+        let r = points
+            .iter()
+            .map(|p| (na::Vec3::from(p.normalize().eucl()) - c).norm())
+            .fold(0.0, f32::max);
+        Some(Self::new(&c.into(), r))
+    }
+
+    pub fn contains(&self, p: &Point) -> bool {
+        (na::Vec3::from(p.normalize().eucl()) - self.c()).norm() <= self.radius
+    }
+
+    /// The smallest sphere around both.
+    pub fn merge(&self, other: &Self) -> Self {
+        let (c1, c2) = (self.c(), other.c());
+        let d = (c2 - c1).norm();
+        if d + other.radius <= self.radius {
+            return Self::new(&c1.into(), self.radius);
+        }
+        if d + self.radius <= other.radius {
+            return Self::new(&c2.into(), other.radius);
+        }
+        let r = 0.5 * (d + self.radius + other.radius);
+        let c = c1 + (c2 - c1) * ((r - self.radius) / d);
+        Self::new(&c.into(), r)
+    }
+
+    fn c(&self) -> na::Vec3 {
+        na::Vec3::from(self.center.normalize().eucl())
+    }
+}
+
+impl Applicable for Sphere {
+    fn apply(&self, m: &Motor) -> Self {
+        Self {
+            center: m.apply_to_point(&self.center),
+            radius: self.radius,
+        }
+    }
+}
+
+impl Obb {
+    pub fn new(center: &[f32; 3], rotor: &Rotor, half: &[f32; 3]) -> Self {
+        Self {
+            center: Point::new(center),
+            rotor: rotor.normalize(),
+            half: *half,
+        }
+    }
+
+    /// The columns are the axes of the box.
+    pub fn rotation(&self) -> na::Matrix3<f32> {
+        let e = Motor::from(&self.rotor).matrix().eucl;
+        na::Matrix3::from_fn(|i, j| e[i][j])
+    }
+
+    pub fn corners(&self) -> [[f32; 3]; 8] {
+        let r = self.rotation();
+        let c = self.c();
+        let mut corners = [[0.0; 3]; 8];
+        for (i, corner) in corners.iter_mut().enumerate() {
+            let s = na::Vec3::from_fn(|k, _| if i >> k & 1 == 0 { -1.0 } else { 1.0 });
+            *corner = (c + r * s.component_mul(&na::Vec3::from(self.half))).into();
+        }
+        corners
+    }
+
+    pub fn contains(&self, p: &Point) -> bool {
+        let x = self.local(&na::Vec3::from(p.normalize().eucl()));
+        (0..3).all(|i| x[i].abs() <= self.half[i])
+    }
+
+    /// Around both, with the axes of self. Not the smallest one.
+    pub fn merge(&self, other: &Self) -> Self {
+        let r = self.rotation();
+        let local = |x: &[f32; 3]| r.transpose() * na::Vec3::from(*x);
+        let mut b = Aabb::new(
+            &local(&self.corners()[0]).into(),
+            &local(&self.corners()[0]).into(),
+        );
+        for x in self.corners().iter().chain(other.corners().iter()) {
+            b = b.merge_point(&local(x).into());
+        }
+        Self::new(
+            &(r * na::Vec3::from(b.center())).into(),
+            &self.rotor,
+            &b.half(),
+        )
+    }
+
+    fn c(&self) -> na::Vec3 {
+        na::Vec3::from(self.center.normalize().eucl())
+    }
+    /// World to box coordinates.
+    fn local(&self, x: &na::Vec3) -> na::Vec3 {
+        self.rotation().transpose() * (x - self.c())
+    }
+}
+
+impl Applicable for Obb {
+    fn apply(&self, m: &Motor) -> Self {
+        let r = m.mul(&Motor::from(&self.rotor)).into_rotor_unchecked();
+        Self {
+            center: m.apply_to_point(&self.center),
+            rotor: r.normalize(),
+            half: self.half,
+        }
+    }
+}
+
+impl From<&Aabb> for Obb {
+    fn from(b: &Aabb) -> Self {
+        Self::new(&b.center(), &Rotor::new(0.0, &[1., 0., 0.]), &b.half())
+    }
+}
+
+impl From<&Obb> for Aabb {
+    fn from(b: &Obb) -> Self {
+        let r = b.rotation().abs();
+        let h = r * na::Vec3::from(b.half);
+        let c = b.c();
+        Self::new(&(c - h).into(), &(c + h).into())
+    }
+}
+
+impl From<&Sphere> for Aabb {
+    fn from(s: &Sphere) -> Self {
+        let c = s.c();
+        let h = na::Vec3::repeat(s.radius);
+        Self::new(&(c - h).into(), &(c + h).into())
+    }
+}
+
+impl From<&Aabb> for Sphere {
+    fn from(b: &Aabb) -> Self {
+        Self::new(&b.center(), na::Vec3::from(b.half()).norm())
+    }
+}
+
+impl Overlaps<Aabb> for Aabb {
+    fn overlaps(&self, other: &Aabb) -> bool {
+        (0..3).all(|i| self.min[i] <= other.max[i] && other.min[i] <= self.max[i])
+    }
+}
+
+impl Overlaps<Sphere> for Aabb {
+    fn overlaps(&self, s: &Sphere) -> bool {
+        let c = s.c();
+        (self.closest(&c) - c).norm() <= s.radius
+    }
+}
+
+impl Overlaps<Obb> for Aabb {
+    fn overlaps(&self, b: &Obb) -> bool {
+        Obb::from(self).overlaps(b)
+    }
+}
+
+impl Overlaps<Sphere> for Sphere {
+    fn overlaps(&self, other: &Sphere) -> bool {
+        (self.c() - other.c()).norm() <= self.radius + other.radius
+    }
+}
+
+impl Overlaps<Aabb> for Sphere {
+    fn overlaps(&self, b: &Aabb) -> bool {
+        b.overlaps(self)
+    }
+}
+
+impl Overlaps<Obb> for Sphere {
+    fn overlaps(&self, b: &Obb) -> bool {
+        b.overlaps(self)
+    }
+}
+
+/// Separating axis test on the face normals of both and their crosses.
+impl Overlaps<Obb> for Obb {
+    fn overlaps(&self, other: &Obb) -> bool {
+        let (ra, rb) = (self.rotation(), other.rotation());
+        let (ha, hb) = (na::Vec3::from(self.half), na::Vec3::from(other.half));
+        let d = other.c() - self.c();
+        let radius = |r: &na::Matrix3<f32>, h: &na::Vec3, l: &na::Vec3| {
+            (0..3).map(|i| h[i] * r.column(i).dot(l).abs()).sum::<f32>()
+        };
+        let mut axes: Vec<na::Vec3> = Vec::with_capacity(15);
+        for i in 0..3 {
+            axes.push(ra.column(i).into());
+            axes.push(rb.column(i).into());
+            for j in 0..3 {
+                axes.push(ra.column(i).cross(&rb.column(j)));
+            }
+        }
+        axes.iter()
+            .filter(|l| l.norm() > 1e-6)
+            .all(|l| d.dot(l).abs() <= radius(&ra, &ha, l) + radius(&rb, &hb, l))
+    }
+}
+
+impl Overlaps<Aabb> for Obb {
+    fn overlaps(&self, b: &Aabb) -> bool {
+        b.overlaps(self)
+    }
+}
+
+impl Overlaps<Sphere> for Obb {
+    fn overlaps(&self, s: &Sphere) -> bool {
+        let h = na::Vec3::from(self.half);
+        let local = Aabb::new(&(-h).into(), &h.into());
+        local.overlaps(&Sphere::new(&self.local(&s.c()).into(), s.radius))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bounds::*;
+    use crate::*;
+
+    fn test_motor() -> Motor {
+        let r = Rotor::new(0.8, &na::Vec3::new(0.3, -1., 0.5).normalize().into());
+        Translator::new(&[1., -2., 0.5]).mul_rotor(&r)
+    }
+
+    #[test]
+    fn aabb() {
+        let a = Aabb::new(&[0., 0., 0.], &[1., 1., 1.]);
+        let b = Aabb::new(&[0.5, 0.5, 0.5], &[2., 2., 2.]);
+        let c = Aabb::new(&[1.5, 0., 0.], &[2., 1., 1.]);
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&c));
+        assert_eq!(a.merge(&c), Aabb::new(&[0., 0., 0.], &[2., 1., 1.]));
+        let points = [Point::new(&[1., -1., 2.]), Point::new(&[-3., 0., 1.])];
+        assert_eq!(
+            Aabb::from_points(&points).unwrap(),
+            Aabb::new(&[-3., -1., 1.], &[1., 0., 2.])
+        );
+    }
+
+    #[test]
+    fn apply() {
+        let m = test_motor();
+        let a = Aabb::new(&[-1., 0., 2.], &[1., 0.5, 3.]);
+        // The moved corners lie on the moved volumes, up to rounding.
+        let moved = a.apply(&m).grow(1e-4);
+        let obb = Obb::from(&a).apply(&m);
+        let mut s = Sphere::from(&a).apply(&m);
+        s.radius += 1e-4;
+        for x in Obb::from(&a).corners().iter() {
+            let p = m.apply_to_point(&Point::new(x));
+            assert!(moved.contains(&p));
+            assert!(s.contains(&p));
+            assert!(obb
+                .corners()
+                .iter()
+                .any(|c| p.is_similar_to(1e-4, &Point::new(c))));
+        }
+        let inside = m.apply_to_point(&Point::new(&[0.5, 0.25, 2.5]));
+        assert!(obb.contains(&inside));
+        assert!(!obb.contains(&m.apply_to_point(&Point::new(&[1.5, 0.25, 2.5]))));
+    }
+
+    #[test]
+    fn spheres() {
+        let a = Sphere::new(&[0., 0., 0.], 1.);
+        let b = Sphere::new(&[1.5, 0., 0.], 1.);
+        let c = Sphere::new(&[3., 0., 0.], 0.5);
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&c));
+        let m = a.merge(&c);
+        assert!((m.radius - 2.25).abs() < 1e-5);
+        assert!(m.contains(&Point::new(&[-1., 0., 0.])));
+        assert!(m.contains(&Point::new(&[3.5, 0., 0.])));
+        assert!(!Aabb::new(&[1.2, -1., -1.], &[2., 1., 1.]).overlaps(&a));
+        assert!(Aabb::new(&[0.9, -1., -1.], &[2., 1., 1.]).overlaps(&a));
+    }
+
+    #[test]
+    fn obb() {
+        let r = Rotor::new(std::f32::consts::FRAC_PI_4, &[0., 0., 1.]);
+        let o = Obb::new(&[0., 0., 0.], &r, &[1., 1., 1.]);
+        // The diamond reaches sqrt(2) along x.
+        assert!(o.overlaps(&Aabb::new(&[1.3, -0.1, -0.1], &[2., 0.1, 0.1])));
+        assert!(!o.overlaps(&Aabb::new(&[1.5, -0.1, -0.1], &[2., 0.1, 0.1])));
+        assert!(!o.overlaps(&Aabb::new(&[1.1, 1.1, -1.], &[2., 2., 1.])));
+        assert!(o.overlaps(&Sphere::new(&[1.6, 0., 0.], 0.3)));
+        assert!(!o.overlaps(&Sphere::new(&[1.2, 1.2, 0.], 0.3)));
+        let moved =
+            o.apply(&Translator::new(&[5., 0., 0.]).mul_rotor(&Rotor::new(0., &[1., 0., 0.])));
+        assert!(!o.overlaps(&moved));
+        let merged = o.merge(&moved);
+        assert!(merged.contains(&Point::new(&[5., 0., 0.])));
+        assert!(merged.contains(&Point::new(&[0., 0., 0.])));
+        assert!(Aabb::from(&o).contains(&Point::new(&[1.4, 0., 0.])));
+    }
+}
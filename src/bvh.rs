@@ -0,0 +1,528 @@
+//! BioVision BVH motion capture files.
+//!
+//! The hierarchy gives every joint an offset from its parent and the
+//! channels that animate it, and every frame of the motion has one value
+//! per channel. Rotations are in degrees and apply in the order of their
+//! channels, so ZXY gives the local motor T * Rz * Rx * Ry. Position
+//! channels replace the components of the offset they name.
+//!
+//! End sites become joints named after their parent with an _end suffix,
+//! without channels. Joints without channels and children are written
+//! back as end sites.
+
+use super::skeleton::{AnimationClip, Skeleton};
+use super::{Motor, ParseError, Rotor, Translator};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Xposition,
+    Yposition,
+    Zposition,
+    Xrotation,
+    Yrotation,
+    Zrotation,
+}
+
+impl Channel {
+    /// The axis, 0 for x.
+    pub fn axis(&self) -> usize {
+        match self {
+            Channel::Xposition | Channel::Xrotation => 0,
+            Channel::Yposition | Channel::Yrotation => 1,
+            Channel::Zposition | Channel::Zrotation => 2,
+        }
+    }
+
+    pub fn is_rotation(&self) -> bool {
+        matches!(
+            self,
+            Channel::Xrotation | Channel::Yrotation | Channel::Zrotation
+        )
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        Some(match s.to_ascii_lowercase().as_str() {
+            "xposition" => Channel::Xposition,
+            "yposition" => Channel::Yposition,
+            "zposition" => Channel::Zposition,
+            "xrotation" => Channel::Xrotation,
+            "yrotation" => Channel::Yrotation,
+            "zrotation" => Channel::Zrotation,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Bvh {
+    /// The rest pose are the offsets.
+    pub skeleton: Skeleton,
+    /// Per joint, in the order of the file.
+    pub channels: Vec<Vec<Channel>>,
+    /// In seconds.
+    pub frame_time: f32,
+    /// The local motors of every joint, one pose per frame.
+    pub poses: Vec<Vec<Motor>>,
+}
+
+impl Bvh {
+    pub fn parse(src: &str) -> Result<Self, ParseError> {
+        let tokens = src
+            .lines()
+            .enumerate()
+            .flat_map(|(n, l)| l.split_whitespace().map(move |t| (t, n + 1)))
+            .collect();
+        let mut p = Parser {
+            tokens,
+            pos: 0,
+            bvh: Bvh {
+                skeleton: Skeleton::new(),
+                channels: Vec::new(),
+                frame_time: 0.0,
+                poses: Vec::new(),
+            },
+        };
+        p.expect("HIERARCHY")?;
+        while p.peek() == Some("ROOT") {
+            p.pos += 1;
+            p.joint(None)?;
+        }
+        if p.bvh.skeleton.is_empty() {
+            return p.error("no ROOT".to_string());
+        }
+        p.expect("MOTION")?;
+        p.expect("Frames:")?;
+        let frames: usize = p.number()?;
+        p.expect("Frame")?;
+        p.expect("Time:")?;
+        p.bvh.frame_time = p.number()?;
+        for _ in 0..frames {
+            let mut pose = Vec::with_capacity(p.bvh.skeleton.len());
+            for j in 0..p.bvh.skeleton.len() {
+                let mut values = Vec::with_capacity(p.bvh.channels[j].len());
+                for _ in 0..p.bvh.channels[j].len() {
+                    values.push(p.number()?);
+                }
+                pose.push(p.bvh.local(j, &values));
+            }
+            p.bvh.poses.push(pose);
+        }
+        if p.peek().is_some() {
+            return p.error("more values than frames".to_string());
+        }
+        Ok(p.bvh)
+    }
+
+    /// The local motor of the joint for its channel values.
+    pub fn local(&self, joint: usize, values: &[f32]) -> Motor {
+        let channels = &self.channels[joint];
+        assert_eq!(channels.len(), values.len());
+        let mut t = offset(&self.skeleton.joints[joint].local);
+        let mut r = Motor::one();
+        for (c, v) in channels.iter().zip(values.iter()) {
+            if c.is_rotation() {
+                let mut axis = [0.0; 3];
+                axis[c.axis()] = 1.0;
+                r = r.mul(&Motor::from(&Rotor::new(v.to_radians(), &axis)));
+            } else {
+                t[c.axis()] = *v;
+            }
+        }
+        Motor::from(&Translator::new(&t)).mul(&r)
+    }
+
+    /// The channel values of the joint for its local motor, the inverse
+    /// of local. Rotation channels that do not name three different axes
+    /// can only give back motors that turn around those axes.
+    pub fn values(&self, joint: usize, local: &Motor) -> Vec<f32> {
+        let channels = &self.channels[joint];
+        let e = local.matrix().eucl;
+        let mut order: Vec<usize> = channels
+            .iter()
+            .filter(|c| c.is_rotation())
+            .map(|c| c.axis())
+            .collect();
+        for axis in 0..3 {
+            if !order.contains(&axis) {
+                order.push(axis);
+            }
+        }
+        let angles = euler(&e, [order[0], order[1], order[2]]);
+        let mut rotation = 0;
+        channels
+            .iter()
+            .map(|c| {
+                if c.is_rotation() {
+                    rotation += 1;
+                    angles[rotation - 1].to_degrees()
+                } else {
+                    e[c.axis()][3]
+                }
+            })
+            .collect()
+    }
+
+    /// The motion as keys at every frame, for the joints with channels.
+    pub fn clip(&self) -> AnimationClip {
+        let mut clip = AnimationClip::new(self.skeleton.len());
+        for (i, pose) in self.poses.iter().enumerate() {
+            for (j, m) in pose.iter().enumerate() {
+                if !self.channels[j].is_empty() {
+                    clip.add_key(j, i as f32 * self.frame_time, m);
+                }
+            }
+        }
+        clip
+    }
+
+    fn write_joint(&self, f: &mut fmt::Formatter, j: usize, depth: usize) -> fmt::Result {
+        let indent = "\t".repeat(depth);
+        let joint = &self.skeleton.joints[j];
+        let children: Vec<usize> = (j + 1..self.skeleton.len())
+            .filter(|c| self.skeleton.joints[*c].parent == Some(j))
+            .collect();
+        let [x, y, z] = offset(&joint.local);
+        if joint.parent.is_some() && self.channels[j].is_empty() && children.is_empty() {
+            writeln!(f, "{}End Site", indent)?;
+            writeln!(f, "{}{{", indent)?;
+            writeln!(f, "{}\tOFFSET {} {} {}", indent, x, y, z)?;
+            return writeln!(f, "{}}}", indent);
+        }
+        let kind = if joint.parent.is_some() {
+            "JOINT"
+        } else {
+            "ROOT"
+        };
+        writeln!(f, "{}{} {}", indent, kind, joint.name)?;
+        writeln!(f, "{}{{", indent)?;
+        writeln!(f, "{}\tOFFSET {} {} {}", indent, x, y, z)?;
+        write!(f, "{}\tCHANNELS {}", indent, self.channels[j].len())?;
+        for c in self.channels[j].iter() {
+            write!(f, " {}", c)?;
+        }
+        writeln!(f)?;
+        for c in children {
+            self.write_joint(f, c, depth + 1)?;
+        }
+        writeln!(f, "{}}}", indent)
+    }
+}
+
+/// The file, with the values of every frame from the poses.
+impl fmt::Display for Bvh {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "HIERARCHY")?;
+        for (j, joint) in self.skeleton.joints.iter().enumerate() {
+            if joint.parent.is_none() {
+                self.write_joint(f, j, 0)?;
+            }
+        }
+        writeln!(f, "MOTION")?;
+        writeln!(f, "Frames: {}", self.poses.len())?;
+        writeln!(f, "Frame Time: {}", self.frame_time)?;
+        for pose in self.poses.iter() {
+            let values: Vec<String> = pose
+                .iter()
+                .enumerate()
+                .flat_map(|(j, m)| self.values(j, m))
+                .map(|v| v.to_string())
+                .collect();
+            writeln!(f, "{}", values.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<(&'a str, usize)>,
+    pos: usize,
+    bvh: Bvh,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).map(|t| t.0)
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, ParseError> {
+        let line = self
+            .tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map_or(0, |t| t.1);
+        Err(ParseError { line, message })
+    }
+
+    fn next(&mut self) -> Result<&'a str, ParseError> {
+        match self.peek() {
+            Some(t) => {
+                self.pos += 1;
+                Ok(t)
+            }
+            None => self.error("unexpected end of file".to_string()),
+        }
+    }
+
+    fn expect(&mut self, s: &str) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(t) if t == s => {
+                self.pos += 1;
+                Ok(())
+            }
+            t => self.error(format!("expected {}, found {:?}", s, t)),
+        }
+    }
+
+    /// A float, or a count when T is usize.
+    fn number<T: std::str::FromStr>(&mut self) -> Result<T, ParseError> {
+        let t = self.next()?;
+        match t.parse() {
+            Ok(x) => Ok(x),
+            Err(_) => {
+                self.pos -= 1;
+                self.error(format!("expected a number, found {}", t))
+            }
+        }
+    }
+
+    fn offset(&mut self) -> Result<Translator, ParseError> {
+        self.expect("OFFSET")?;
+        Ok(Translator::new(&[
+            self.number()?,
+            self.number()?,
+            self.number()?,
+        ]))
+    }
+
+    /// After ROOT or JOINT.
+    fn joint(&mut self, parent: Option<usize>) -> Result<(), ParseError> {
+        let name = self.next()?;
+        self.expect("{")?;
+        let offset = self.offset()?;
+        let j = self
+            .bvh
+            .skeleton
+            .add_joint(name, parent, &Motor::from(&offset));
+        self.expect("CHANNELS")?;
+        let n: usize = self.number()?;
+        if n > 6 {
+            self.pos -= 1;
+            return self.error(format!("{} channels, at most 6", n));
+        }
+        let mut channels = Vec::with_capacity(n);
+        for _ in 0..n {
+            let t = self.next()?;
+            match Channel::parse(t) {
+                Some(c) => channels.push(c),
+                None => {
+                    self.pos -= 1;
+                    return self.error(format!("unknown channel {}", t));
+                }
+            }
+        }
+        self.bvh.channels.push(channels);
+        loop {
+            match self.next()? {
+                "JOINT" => self.joint(Some(j))?,
+                "End" => {
+                    self.expect("Site")?;
+                    self.expect("{")?;
+                    let offset = self.offset()?;
+                    self.expect("}")?;
+                    let name = format!("{}_end", name);
+                    self.bvh
+                        .skeleton
+                        .add_joint(&name, Some(j), &Motor::from(&offset));
+                    self.bvh.channels.push(Vec::new());
+                }
+                "}" => return Ok(()),
+                t => {
+                    self.pos -= 1;
+                    return self.error(format!("expected JOINT, End Site or }}, found {}", t));
+                }
+            }
+        }
+    }
+}
+
+/// The translation of the motor.
+fn offset(m: &Motor) -> [f32; 3] {
+    let e = m.matrix().eucl;
+    [e[0][3], e[1][3], e[2][3]]
+}
+
+/// The angles of R = R_i(a) R_j(b) R_k(c) for the different axes i, j, k.
+fn euler(r: &[[f32; 4]; 3], [i, j, k]: [usize; 3]) -> [f32; 3] {
+    // Even orders, xyz and its cyclic shifts, have the sine of b at (i, k)
+    // and the others its negation.
+    let s = if j == (i + 1) % 3 { 1.0 } else { -1.0 };
+    let b = (s * r[i][k]).clamp(-1.0, 1.0).asin();
+    if r[i][k].abs() < 0.9999 {
+        let a = (-s * r[j][k]).atan2(r[k][k]);
+        let c = (-s * r[i][j]).atan2(r[i][i]);
+        [a, b, c]
+    } else {
+        // Gimbal lock, only a + c or a - c is known, so c is 0.
+        let a = (s * r[k][j]).atan2(r[j][j]);
+        [a, b, 0.0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bvh::*;
+    use crate::*;
+
+    const WALK: &str = "HIERARCHY
+ROOT Hips
+{
+    OFFSET 0.0 0.0 0.0
+    CHANNELS 6 Xposition Yposition Zposition Zrotation Xrotation Yrotation
+    JOINT Spine
+    {
+        OFFSET 0.0 10.0 0.0
+        CHANNELS 3 Xrotation Yrotation Zrotation
+        End Site
+        {
+            OFFSET 0.0 5.0 0.0
+        }
+    }
+    JOINT Leg
+    {
+        OFFSET 3.0 -1.0 0.0
+        CHANNELS 3 Yrotation Zrotation Xrotation
+        End Site
+        {
+            OFFSET 0.0 -8.0 0.0
+        }
+    }
+}
+MOTION
+Frames: 2
+Frame Time: 0.0333333
+0 20 0 0 0 0 0 0 0 0 0 0
+1 20.5 -2 90 10 -20 30 0 45 12 -80 5
+";
+
+    fn close(a: &[f32; 3], b: &[f32; 3]) -> bool {
+        a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 1e-3)
+    }
+
+    #[test]
+    fn parse() {
+        let bvh = Bvh::parse(WALK).unwrap();
+        let s = &bvh.skeleton;
+        assert_eq!(s.len(), 5);
+        assert_eq!(s.find("Spine_end"), Some(2));
+        assert_eq!(s.joints[4].parent, s.find("Leg"));
+        assert_eq!(bvh.channels[0][3], Channel::Zrotation);
+        assert!(bvh.channels[4].is_empty());
+        assert_eq!(bvh.poses.len(), 2);
+        assert_eq!(bvh.frame_time, 0.0333333);
+
+        // The position replaces the offset and the rest is the offset.
+        let world = s.world_from(&bvh.poses[0]);
+        let foot = world[4].apply_to_point(&Point::new(&[0., 0., 0.]));
+        assert_eq!(foot, Point::new(&[3., 11., 0.]));
+
+        // Turning the hips by 90 around z takes the spine from y to -x.
+        let world = s.world_from(&bvh.poses[1]);
+        let spine = world[1].apply_to_point(&Point::new(&[0., 0., 0.]));
+        let hips = Motor::from(&Translator::new(&[1., 20.5, -2.]))
+            .mul(&Motor::from(&Rotor::new(
+                std::f32::consts::FRAC_PI_2,
+                &[0., 0., 1.],
+            )))
+            .mul(&Motor::from(&Rotor::new(0.1745329, &[1., 0., 0.])))
+            .mul(&Motor::from(&Rotor::new(-0.3490658, &[0., 1., 0.])));
+        assert_eq!(world[0], hips);
+        let up = hips.apply_to_point(&Point::new(&[0., 10., 0.]));
+        assert_eq!(spine, up);
+        assert!(close(&spine.normalize().eucl(), &[-8.848, 20.5, -0.264]));
+    }
+
+    #[test]
+    fn orders() {
+        let axes = [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]];
+        let angles = [0.3, -1.1, 2.5];
+        for order in [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ]
+        .iter()
+        {
+            let r = order
+                .iter()
+                .zip(angles.iter())
+                .fold(Motor::one(), |m, (i, a)| {
+                    m.mul(&Motor::from(&Rotor::new(*a, &axes[*i])))
+                });
+            let e = euler(&r.matrix().eucl, *order);
+            assert!(close(&e, &angles), "{:?} {:?}", order, e);
+            // At gimbal lock the first angle takes all of the turn.
+            let lock = [0.3, std::f32::consts::FRAC_PI_2, 0.];
+            let r = order
+                .iter()
+                .zip(lock.iter())
+                .fold(Motor::one(), |m, (i, a)| {
+                    m.mul(&Motor::from(&Rotor::new(*a, &axes[*i])))
+                });
+            let e = euler(&r.matrix().eucl, *order);
+            assert!(close(&e, &lock), "{:?} {:?}", order, e);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut bvh = Bvh::parse(WALK).unwrap();
+        let out = bvh.to_string();
+        let again = Bvh::parse(&out).unwrap();
+        assert_eq!(again.channels, bvh.channels);
+        assert_eq!(again.skeleton.find("Leg_end"), Some(4));
+        for (a, b) in again.poses.iter().zip(bvh.poses.iter()) {
+            assert_eq!(a, b);
+        }
+
+        // Edited motions are written back too.
+        let r = Motor::from(&Rotor::new(0.5, &[0., 1., 0.]));
+        bvh.poses[1][3] = bvh.poses[1][3].mul(&r);
+        let edited = Bvh::parse(&bvh.to_string()).unwrap();
+        assert_eq!(edited.poses[1][3], bvh.poses[1][3]);
+
+        let clip = bvh.clip();
+        assert!((clip.duration() - 0.0333333).abs() < 1e-6);
+        assert!(clip.tracks[2].keys.is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        let e = Bvh::parse("HIERARCHY\nROOT Hips\n{\nOFFSET 0 0 0\nCHANNELS 1 Wrotation\n}")
+            .unwrap_err();
+        assert_eq!(e.line, 5);
+        let short = WALK.replace("1 20.5 -2 90 10 -20 30 0 45 12 -80 5", "1 2");
+        assert_eq!(
+            Bvh::parse(&short).unwrap_err().message,
+            "unexpected end of file"
+        );
+        assert!(Bvh::parse("MOTION").is_err());
+        let e = Bvh::parse("HIERARCHY\nROOT Hips\n{\nOFFSET 0 0 0\nCHANNELS 1e30 Xrotation\n}")
+            .unwrap_err();
+        assert_eq!(e.message, "expected a number, found 1e30");
+        let e = Bvh::parse("HIERARCHY\nROOT Hips\n{\nOFFSET 0 0 0\nCHANNELS 7 Xrotation\n}")
+            .unwrap_err();
+        assert_eq!((e.line, e.message.as_str()), (5, "7 channels, at most 6"));
+        let fractional = WALK.replace("Frames: 2", "Frames: 2.5");
+        assert!(Bvh::parse(&fractional).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! The pinhole camera. It looks along its local +z with x to the right and
+//! y down, like a Frustum, and the pose moves it into the world. Pixels
+//! are u = fx x/z + cx and v = fy y/z + cy, after radial distortion.
+
+use super::d2::Line2;
+use super::frustum::Frustum;
+use super::{join, Applicable, Line, Motor, Plane, Point};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    pub focal: [f32; 2],
+    pub principal: [f32; 2],
+    /// k1 and k2, scaling x/z and y/z by 1 + k1 r^2 + k2 r^4.
+    pub distortion: [f32; 2],
+    pub pose: Motor,
+}
+
+impl Camera {
+    pub fn new(pose: &Motor, focal: &[f32; 2], principal: &[f32; 2]) -> Self {
+        Self {
+            focal: *focal,
+            principal: *principal,
+            distortion: [0.0; 2],
+            pose: pose.normalize(),
+        }
+    }
+
+    pub fn with_distortion(&self, k1: f32, k2: f32) -> Self {
+        Self {
+            distortion: [k1, k2],
+            ..*self
+        }
+    }
+
+    pub fn center(&self) -> Point {
+        self.pose.apply_to_point(&Point::new(&[0., 0., 0.]))
+    }
+
+    /// The pixel of the point, None behind the camera.
+    pub fn project(&self, p: &Point) -> Option<[f32; 2]> {
+        let x = self.pose.reverse().apply_to_point(p).normalize().eucl();
+        if x[2] <= 0.0 {
+            return None;
+        }
+        Some(self.pixel(&self.distort(&[x[0] / x[2], x[1] / x[2]])))
+    }
+
+    /// The line from the camera center through the pixel, pointing away
+    /// from the camera.
+    pub fn back_project(&self, pixel: &[f32; 2]) -> Line {
+        let [x, y] = self.image_point(pixel);
+        let p = self.pose.apply_to_point(&Point::new(&[x, y, 1.]));
+        join::points(&self.center(), &p)
+    }
+
+    /// The image of the line, in pixels. Distortion bends lines, so this
+    /// is only exact without it. None for lines through the center.
+    pub fn project_line(&self, l: &Line) -> Option<Line2> {
+        let plane = join::line_to_point(l, &self.center());
+        if plane.norm() < 1e-4 * l.norm() {
+            return None;
+        }
+        // The plane passes through the center, so n.(x/z, y/z, 1) = 0.
+        let n = self.pose.reverse().apply_to_plane(&plane).vector;
+        let ([fx, fy], [cx, cy]) = (self.focal, self.principal);
+        let (a, b) = (n[1] / fx, n[2] / fy);
+        Some(Line2::new(a * cx + b * cy - n[3], &[a, b]).normalize())
+    }
+
+    /// The plane through the center and the image line, the inverse of
+    /// project_line.
+    pub fn back_project_line(&self, l: &Line2) -> Plane {
+        let [d, a, b] = l.vector;
+        let ([fx, fy], [cx, cy]) = (self.focal, self.principal);
+        let n = [a * fx, b * fy, a * cx + b * cy - d];
+        self.pose.apply_to_plane(&Plane::new(0., &n)).normalize()
+    }
+
+    /// The undistorted x/z and y/z in camera coordinates of the pixel.
+    pub fn image_point(&self, pixel: &[f32; 2]) -> [f32; 2] {
+        self.undistort(&self.normalized(pixel))
+    }
+
+    /// The frustum through the corners of a width by height image,
+    /// ignoring distortion.
+    pub fn frustum(&self, width: f32, height: f32, near: f32, far: f32) -> Frustum {
+        let [x0, y0] = self.normalized(&[0., 0.]);
+        let [x1, y1] = self.normalized(&[width, height]);
+        Frustum::new([
+            Plane::new(0., &[-1., 0., x0]),
+            Plane::new(0., &[1., 0., -x1]),
+            Plane::new(0., &[0., -1., y0]),
+            Plane::new(0., &[0., 1., -y1]),
+            Plane::new(-near, &[0., 0., -1.]),
+            Plane::new(far, &[0., 0., 1.]),
+        ])
+        .apply(&self.pose)
+    }
+
+    fn pixel(&self, x: &[f32; 2]) -> [f32; 2] {
+        [
+            self.focal[0] * x[0] + self.principal[0],
+            self.focal[1] * x[1] + self.principal[1],
+        ]
+    }
+    fn normalized(&self, pixel: &[f32; 2]) -> [f32; 2] {
+        [
+            (pixel[0] - self.principal[0]) / self.focal[0],
+            (pixel[1] - self.principal[1]) / self.focal[1],
+        ]
+    }
+
+    fn distort(&self, x: &[f32; 2]) -> [f32; 2] {
+        let r2 = x[0] * x[0] + x[1] * x[1];
+        let s = 1. + self.distortion[0] * r2 + self.distortion[1] * r2 * r2;
+        [x[0] * s, x[1] * s]
+    }
+    /// Fixed point iteration, fine for the small distortions of real lenses.
+    fn undistort(&self, x: &[f32; 2]) -> [f32; 2] {
+        let mut u = *x;
+        for _ in 0..20 {
+            let r2 = u[0] * u[0] + u[1] * u[1];
+            let s = 1. + self.distortion[0] * r2 + self.distortion[1] * r2 * r2;
+            u = [x[0] / s, x[1] / s];
+        }
+        u
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::camera::*;
+    use crate::*;
+
+    fn camera() -> Camera {
+        let r = Rotor::new(0.4, &na::Vec3::new(0.2, 1., 0.3).normalize().into());
+        let pose = Translator::new(&[1., 2., -3.]).mul_rotor(&r);
+        Camera::new(&pose, &[500., 480.], &[320., 240.]).with_distortion(-0.1, 0.01)
+    }
+
+    /// From the camera center along the back projected line.
+    fn ray(c: &Camera, l: &Line) -> ray::Ray {
+        ray::Ray::new(&c.center().normalize().eucl(), &l.e_bivector)
+    }
+
+    fn close(a: &[f32; 2], b: &[f32; 2]) -> bool {
+        (a[0] - b[0]).abs() < 1e-2 && (a[1] - b[1]).abs() < 1e-2
+    }
+
+    #[test]
+    fn project() {
+        let c = Camera::new(&Motor::one(), &[100., 100.], &[50., 40.]);
+        assert_eq!(c.project(&Point::new(&[1., -2., 4.])), Some([75., -10.]));
+        assert_eq!(c.project(&Point::new(&[1., -2., -4.])), None);
+        let l = c.back_project(&[75., -10.]);
+        assert_eq!(
+            l.normalize(),
+            Line::new(&[0., 0., 0.], &[1., -2., 4.]).normalize()
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let c = camera();
+        for pixel in [[320., 240.], [10., 20.], [600., 450.], [100., 400.]].iter() {
+            let l = c.back_project(pixel);
+            for t in [1., 5., 20.].iter() {
+                let p_ = c.project(&ray(&c, &l).at(*t)).unwrap();
+                assert!(close(&p_, pixel), "{:?} {:?}", p_, pixel);
+            }
+            let p = c.pose.apply_to_point(&Point::new(&[0.3, -0.2, 2.]));
+            let pixel = c.project(&p).unwrap();
+            let l = c.back_project(&pixel);
+            assert!(meet::lines(&l, &join::points(&c.center(), &p)).0.abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn lines() {
+        let c = Camera {
+            distortion: [0.0; 2],
+            ..camera()
+        };
+        let (a, b) = (
+            c.pose.apply_to_point(&Point::new(&[1., -1., 5.])),
+            c.pose.apply_to_point(&Point::new(&[-2., 0.5, 8.])),
+        );
+        let image = c.project_line(&join::points(&a, &b)).unwrap();
+        for p in [&a, &b].iter() {
+            let [u, v] = c.project(p).unwrap();
+            let n = image.vector;
+            assert!((n[1] * u + n[2] * v - n[0]).abs() < 1e-2);
+        }
+        let plane = c.back_project_line(&image);
+        assert!(plane.distance(&a).abs() < 1e-4 && plane.distance(&b).abs() < 1e-4);
+        let through = join::points(&c.center(), &a);
+        assert!(c.project_line(&through).is_none());
+    }
+
+    #[test]
+    fn frustum() {
+        let c = Camera {
+            distortion: [0.0; 2],
+            ..camera()
+        };
+        let f = c.frustum(640., 480., 0.1, 100.);
+        let inside = c.back_project(&[5., 470.]);
+        let outside = c.back_project(&[-5., 240.]);
+        let at = |l: &Line| ray(&c, l).at(10.);
+        assert!(f.contains(&at(&inside)));
+        assert!(!f.contains(&at(&outside)));
+    }
+}
@@ -0,0 +1,399 @@
+//! Sutherland-Hodgman clipping and splitting of polygons (ordered Point
+//! loops) and triangle meshes against planes.
+//!
+//! As in polyhedron, the inside of a plane n.x = d is n.x <= d, so
+//! clipping keeps the back. Where an edge crosses the plane the new vertex
+//! is the meet of the plane with the join of the edge's end points.
+//!
+//! Vertices closer than Settings::epsilon to the plane are on it. A
+//! polygon with all vertices on the plane is kept by clip, and split puts
+//! it in front if its normal agrees with the plane's and in back
+//! otherwise, like a BSP tree does.
+
+use super::{join, meet, Plane, Point};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    /// Vertices closer than this to the plane are on it.
+    pub epsilon: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self { epsilon: 1e-5 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    Front,
+    Back,
+    On,
+}
+
+pub fn side(plane: &Plane, p: &Point, settings: &Settings) -> Side {
+    let d = plane.distance(p);
+    if d > settings.epsilon {
+        Side::Front
+    } else if d < -settings.epsilon {
+        Side::Back
+    } else {
+        Side::On
+    }
+}
+
+/// The point where the segment from a to b crosses the plane.
+pub fn intersection(plane: &Plane, a: &Point, b: &Point) -> Point {
+    meet::plane_with_line(plane, &join::points(a, b)).normalize()
+}
+
+/// Newell's normal of the polygon, counterclockwise seen from its tip.
+pub fn normal(poly: &[Point]) -> [f32; 3] {
+    let mut n = na::Vec3::zeros();
+    for (i, a) in poly.iter().enumerate() {
+        let a = a.normalize().eucl();
+        let b = poly[(i + 1) % poly.len()].normalize().eucl();
+        n += na::Vec3::new(
+            (a[1] - b[1]) * (a[2] + b[2]),
+            (a[2] - b[2]) * (a[0] + b[0]),
+            (a[0] - b[0]) * (a[1] + b[1]),
+        );
+    }
+    n.into()
+}
+
+#[derive(Debug, Default)]
+pub struct Split<T> {
+    pub front: T,
+    pub back: T,
+}
+
+pub fn split_polygon(poly: &[Point], plane: &Plane, settings: &Settings) -> Split<Vec<Point>> {
+    divide_polygon(poly, plane, settings, false)
+}
+
+/// The part of the polygon behind the plane, empty if there is none.
+pub fn clip_polygon(poly: &[Point], plane: &Plane, settings: &Settings) -> Vec<Point> {
+    divide_polygon(poly, plane, settings, true).back
+}
+
+/// Clips by every plane in turn, e.g., the planes of a ConvexPolyhedron
+/// or a view volume.
+pub fn clip_polygon_planes(poly: &[Point], planes: &[Plane], settings: &Settings) -> Vec<Point> {
+    let mut poly = poly.to_vec();
+    for plane in planes.iter() {
+        if poly.is_empty() {
+            break;
+        }
+        poly = clip_polygon(&poly, plane, settings);
+    }
+    poly
+}
+
+/// With keep_on a polygon on the plane goes to back, otherwise by normal.
+fn divide_polygon(
+    poly: &[Point],
+    plane: &Plane,
+    settings: &Settings,
+    keep_on: bool,
+) -> Split<Vec<Point>> {
+    let sides: Vec<Side> = poly.iter().map(|p| side(plane, p, settings)).collect();
+    let mut split = Split {
+        front: Vec::new(),
+        back: Vec::new(),
+    };
+    match classify(&sides) {
+        Some(Side::Front) => split.front = poly.to_vec(),
+        Some(Side::Back) => split.back = poly.to_vec(),
+        Some(Side::On) if !keep_on && faces(poly, plane) => split.front = poly.to_vec(),
+        Some(Side::On) => split.back = poly.to_vec(),
+        None => {
+            for i in 0..poly.len() {
+                let j = (i + 1) % poly.len();
+                match sides[i] {
+                    Side::Front => split.front.push(poly[i]),
+                    Side::Back => split.back.push(poly[i]),
+                    Side::On => {
+                        split.front.push(poly[i]);
+                        split.back.push(poly[i]);
+                    }
+                }
+                if crosses(sides[i], sides[j]) {
+                    let x = intersection(plane, &poly[i], &poly[j]);
+                    split.front.push(x);
+                    split.back.push(x);
+                }
+            }
+        }
+    }
+    split
+}
+
+#[derive(Debug, Default)]
+pub struct Mesh {
+    pub vertices: Vec<Point>,
+    pub triangles: Vec<[usize; 3]>,
+}
+
+/// Splits every triangle and fans the pieces back into triangles. Vertices
+/// on a crossed edge are made once and shared by both triangles of the
+/// edge, so a closed mesh stays closed.
+pub fn split_mesh(mesh: &Mesh, plane: &Plane, settings: &Settings) -> Split<Mesh> {
+    divide_mesh(mesh, plane, settings, false)
+}
+
+/// The part of the mesh behind the plane, triangles on the plane are kept.
+pub fn clip_mesh(mesh: &Mesh, plane: &Plane, settings: &Settings) -> Mesh {
+    divide_mesh(mesh, plane, settings, true).back
+}
+
+pub fn clip_mesh_planes(mesh: &Mesh, planes: &[Plane], settings: &Settings) -> Mesh {
+    let mut mesh = compact(&mesh.vertices, &mesh.triangles);
+    for plane in planes.iter() {
+        mesh = clip_mesh(&mesh, plane, settings);
+    }
+    mesh
+}
+
+fn divide_mesh(mesh: &Mesh, plane: &Plane, settings: &Settings, keep_on: bool) -> Split<Mesh> {
+    let mut vertices = mesh.vertices.clone();
+    let sides: Vec<Side> = vertices.iter().map(|p| side(plane, p, settings)).collect();
+    let mut cut: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut front = Vec::new();
+    let mut back = Vec::new();
+    for t in mesh.triangles.iter() {
+        let s = [sides[t[0]], sides[t[1]], sides[t[2]]];
+        match classify(&s) {
+            Some(Side::Front) => front.push(*t),
+            Some(Side::Back) => back.push(*t),
+            Some(Side::On) => {
+                let poly: Vec<Point> = t.iter().map(|i| vertices[*i]).collect();
+                if !keep_on && faces(&poly, plane) {
+                    front.push(*t);
+                } else {
+                    back.push(*t);
+                }
+            }
+            None => {
+                let (mut f, mut b) = (Vec::new(), Vec::new());
+                for i in 0..3 {
+                    let (u, v) = (t[i], t[(i + 1) % 3]);
+                    match s[i] {
+                        Side::Front => f.push(u),
+                        Side::Back => b.push(u),
+                        Side::On => {
+                            f.push(u);
+                            b.push(u);
+                        }
+                    }
+                    if crosses(s[i], s[(i + 1) % 3]) {
+                        let x = *cut.entry((u.min(v), u.max(v))).or_insert_with(|| {
+                            vertices.push(intersection(
+                                plane,
+                                &mesh.vertices[u],
+                                &mesh.vertices[v],
+                            ));
+                            vertices.len() - 1
+                        });
+                        f.push(x);
+                        b.push(x);
+                    }
+                }
+                fan(&f, &mut front);
+                fan(&b, &mut back);
+            }
+        }
+    }
+    Split {
+        front: compact(&vertices, &front),
+        back: compact(&vertices, &back),
+    }
+}
+
+/// Front or Back if no vertex is on the other side, On if all are on the
+/// plane and None if the plane cuts through.
+fn classify(sides: &[Side]) -> Option<Side> {
+    let front = sides.contains(&Side::Front);
+    let back = sides.contains(&Side::Back);
+    match (front, back) {
+        (true, false) => Some(Side::Front),
+        (false, true) => Some(Side::Back),
+        (false, false) => Some(Side::On),
+        (true, true) => None,
+    }
+}
+
+fn crosses(a: Side, b: Side) -> bool {
+    matches!(
+        (a, b),
+        (Side::Front, Side::Back) | (Side::Back, Side::Front)
+    )
+}
+
+fn faces(poly: &[Point], plane: &Plane) -> bool {
+    let n = normal(poly);
+    let v = plane.vector;
+    n[0] * v[1] + n[1] * v[2] + n[2] * v[3] > 0.0
+}
+
+fn fan(poly: &[usize], triangles: &mut Vec<[usize; 3]>) {
+    for i in 1..poly.len().saturating_sub(1) {
+        triangles.push([poly[0], poly[i], poly[i + 1]]);
+    }
+}
+
+/// A mesh with only the vertices the triangles use.
+fn compact(vertices: &[Point], triangles: &[[usize; 3]]) -> Mesh {
+    let mut index: HashMap<usize, usize> = HashMap::new();
+    let mut mesh = Mesh::default();
+    for t in triangles.iter() {
+        let mut r = [0; 3];
+        for (r, i) in r.iter_mut().zip(t.iter()) {
+            *r = *index.entry(*i).or_insert_with(|| {
+                mesh.vertices.push(vertices[*i]);
+                mesh.vertices.len() - 1
+            });
+        }
+        mesh.triangles.push(r);
+    }
+    mesh
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clip::*;
+
+    fn square() -> Vec<Point> {
+        vec![
+            Point::new(&[0., 0., 0.]),
+            Point::new(&[1., 0., 0.]),
+            Point::new(&[1., 1., 0.]),
+            Point::new(&[0., 1., 0.]),
+        ]
+    }
+
+    fn area(poly: &[Point]) -> f32 {
+        na::Vec3::from(normal(poly)).norm() / 2.
+    }
+
+    /// The unit cube as 12 triangles, counterclockwise seen from outside.
+    fn cube() -> Mesh {
+        let vertices = (0..8)
+            .map(|i| Point::new(&[(i & 1) as f32, (i >> 1 & 1) as f32, (i >> 2 & 1) as f32]))
+            .collect();
+        let quads = [
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+        ];
+        let mut triangles = Vec::new();
+        for q in quads.iter() {
+            triangles.push([q[0], q[1], q[2]]);
+            triangles.push([q[0], q[2], q[3]]);
+        }
+        Mesh {
+            vertices,
+            triangles,
+        }
+    }
+
+    fn volume(mesh: &Mesh) -> f32 {
+        mesh.triangles
+            .iter()
+            .map(|t| {
+                let x = |i: usize| na::Vec3::from(mesh.vertices[t[i]].normalize().eucl());
+                x(0).dot(&x(1).cross(&x(2))) / 6.
+            })
+            .sum()
+    }
+
+    #[test]
+    fn polygon() {
+        let s = Settings::default();
+        let plane = Plane::new(0.25, &[1., 0., 0.]);
+        let split = split_polygon(&square(), &plane, &s);
+        assert_eq!(split.front.len(), 4);
+        assert_eq!(split.back.len(), 4);
+        assert!((area(&split.front) - 0.75).abs() < 1e-5);
+        assert!((area(&split.back) - 0.25).abs() < 1e-5);
+        assert!(split.back.contains(&Point::new(&[0.25, 1., 0.])));
+
+        let clipped = clip_polygon(&square(), &plane, &s);
+        assert!((area(&clipped) - 0.25).abs() < 1e-5);
+        assert!(clip_polygon(&square(), &Plane::new(-1., &[1., 0., 0.]), &s).is_empty());
+
+        // A corner on the plane is in both parts and not duplicated.
+        let diagonal = Plane::new(0., &[1., -1., 0.]);
+        let split = split_polygon(&square(), &diagonal, &s);
+        assert_eq!(split.front.len(), 3);
+        assert_eq!(split.back.len(), 3);
+    }
+
+    #[test]
+    fn coplanar() {
+        let s = Settings::default();
+        let up = Plane::new(0., &[0., 0., 1.]);
+        let down = up.neg();
+        assert_eq!(split_polygon(&square(), &up, &s).front.len(), 4);
+        assert_eq!(split_polygon(&square(), &down, &s).back.len(), 4);
+        assert_eq!(clip_polygon(&square(), &up, &s).len(), 4);
+        assert_eq!(clip_polygon(&square(), &down, &s).len(), 4);
+
+        let loose = Settings { epsilon: 0.1 };
+        let tilted = Plane::new(0., &[0.05, 0., 1.]).normalize();
+        assert_eq!(split_polygon(&square(), &tilted, &loose).front.len(), 4);
+        assert_eq!(split_polygon(&square(), &tilted, &s).front.len(), 4);
+        assert_eq!(split_polygon(&square(), &tilted, &s).back.len(), 0);
+    }
+
+    #[test]
+    fn planes() {
+        let s = Settings::default();
+        let planes = [
+            Plane::new(0.5, &[1., 0., 0.]),
+            Plane::new(0.5, &[0., 1., 0.]),
+            Plane::new(-0.25, &[-1., 0., 0.]),
+        ];
+        let clipped = clip_polygon_planes(&square(), &planes, &s);
+        assert!((area(&clipped) - 0.125).abs() < 1e-5);
+    }
+
+    #[test]
+    fn mesh() {
+        let s = Settings::default();
+        let cube = cube();
+        assert!((volume(&cube) - 1.).abs() < 1e-5);
+        let plane = Plane::new(0.3, &[0., 0., 1.]);
+        let split = split_mesh(&cube, &plane, &s);
+        // 4 corners and the cuts of 4 vertical and 4 diagonal edges, each
+        // made once for the two triangles of the edge.
+        assert_eq!(split.front.vertices.len(), 12);
+        assert_eq!(split.back.vertices.len(), 12);
+
+        let clipped = clip_mesh(&cube, &plane, &s);
+        assert_eq!(clipped.triangles.len(), split.back.triangles.len());
+        assert!(clipped
+            .vertices
+            .iter()
+            .all(|p| plane.distance(p) <= s.epsilon));
+        // Open at the cut, the missing cap would add x.n / 3 = 0.3 / 3.
+        assert!((volume(&clipped) - 0.2).abs() < 1e-5);
+
+        let on = clip_mesh(&cube, &Plane::new(1., &[0., 0., 1.]), &s);
+        assert_eq!(on.triangles.len(), 12);
+        let split = split_mesh(&cube, &Plane::new(1., &[0., 0., 1.]), &s);
+        assert_eq!(split.front.triangles.len(), 2);
+        assert_eq!(split.back.triangles.len(), 10);
+
+        let planes = [plane, Plane::new(0.5, &[1., 0., 0.])];
+        let clipped = clip_mesh_planes(&cube, &planes, &s);
+        assert!(clipped
+            .vertices
+            .iter()
+            .all(|p| planes.iter().all(|q| q.distance(p) <= s.epsilon)));
+    }
+}
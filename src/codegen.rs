@@ -0,0 +1,600 @@
+//! Generates the functions in the products module from the Cayley table of
+//! G(3,0,1) and the memory layout of the crate's types. The build script
+//! uses this, so this file must not depend on the rest of the crate.
+//!
+//! A product is expanded symbolically into one polynomial per component of
+//! the result. Products of two inputs that are needed more than once are
+//! computed once up front, after which every polynomial is factored by
+//! pulling out the input it has in most terms.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// The basis blades in the order of ganja.js.
+pub const BASIS: [&str; 16] = [
+    "1", "e0", "e1", "e2", "e3", "e01", "e02", "e03", "e12", "e31", "e23", "e021", "e013", "e032",
+    "e123", "e0123",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Product {
+    Geometric,
+    /// The wedge, meet.
+    Outer,
+    /// The vee, join. Defined like ganja.js.
+    Regressive,
+    /// The symmetric inner product.
+    Inner,
+    /// a b ~a, applying a to b.
+    Sandwich,
+}
+
+impl Product {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Product::Geometric => "geometric",
+            Product::Outer => "outer",
+            Product::Regressive => "regressive",
+            Product::Inner => "inner",
+            Product::Sandwich => "sandwich",
+        }
+    }
+}
+
+/// A crate type and which blades its components hold.
+#[derive(Debug)]
+pub struct Type {
+    pub name: &'static str,
+    /// The fields with the blades of their components. A field with one
+    /// blade is an f32, "0" is the field of a tuple struct and an empty
+    /// name the f32 itself.
+    pub fields: &'static [(&'static str, &'static [&'static str])],
+}
+
+pub const TYPES: &[Type] = &[
+    Type {
+        name: "f32",
+        fields: &[("", &["1"])],
+    },
+    Type {
+        name: "PseudoScalar",
+        fields: &[("0", &["e0123"])],
+    },
+    Type {
+        name: "Plane",
+        fields: &[("vector", &["e0", "e1", "e2", "e3"])],
+    },
+    Type {
+        name: "Point",
+        fields: &[("trivector", &["e123", "e032", "e013", "e021"])],
+    },
+    Type {
+        name: "Line",
+        fields: &[
+            ("e_bivector", &["e23", "e31", "e12"]),
+            ("v_bivector", &["e01", "e02", "e03"]),
+        ],
+    },
+    Type {
+        name: "Rotor",
+        fields: &[("scalar", &["1"]), ("e_bivector", &["e23", "e31", "e12"])],
+    },
+    Type {
+        name: "Translator",
+        fields: &[("scalar", &["1"]), ("v_bivector", &["e01", "e02", "e03"])],
+    },
+    Type {
+        name: "Motor",
+        fields: &[
+            ("scalar", &["1"]),
+            ("pseudo", &["e0123"]),
+            ("e_bivector", &["e23", "e31", "e12"]),
+            ("v_bivector", &["e01", "e02", "e03"]),
+        ],
+    },
+];
+
+/// The products the crate uses as (product, a, b, result). Blades of the
+/// result the result type has no room for must be zero, except for the
+/// sandwich product, which drops them.
+pub const PRODUCTS: &[(Product, &str, &str, &str)] = &[
+    (Product::Geometric, "Motor", "Motor", "Motor"),
+    (Product::Geometric, "Motor", "Translator", "Motor"),
+    (Product::Geometric, "Rotor", "Translator", "Motor"),
+    (Product::Geometric, "Translator", "Rotor", "Motor"),
+    (Product::Geometric, "Line", "Line", "Motor"),
+    (Product::Geometric, "Plane", "Plane", "Motor"),
+    (Product::Geometric, "Point", "Point", "Translator"),
+    (Product::Sandwich, "Motor", "Point", "Point"),
+    (Product::Sandwich, "Motor", "Plane", "Plane"),
+    (Product::Sandwich, "Motor", "Line", "Line"),
+    (Product::Sandwich, "Rotor", "Point", "Point"),
+    (Product::Sandwich, "Translator", "Point", "Point"),
+    (Product::Outer, "Plane", "Plane", "Line"),
+    (Product::Outer, "Plane", "Line", "Point"),
+    (Product::Outer, "Line", "Line", "PseudoScalar"),
+    (Product::Regressive, "Point", "Point", "Line"),
+    (Product::Regressive, "Line", "Point", "Plane"),
+    (Product::Inner, "Line", "Line", "f32"),
+];
+
+pub fn ty(name: &str) -> &'static Type {
+    TYPES
+        .iter()
+        .find(|t| t.name == name)
+        .unwrap_or_else(|| panic!("unknown type {}", name))
+}
+
+pub fn blade(name: &str) -> usize {
+    BASIS
+        .iter()
+        .position(|b| *b == name)
+        .unwrap_or_else(|| panic!("unknown blade {}", name))
+}
+
+fn grade(b: usize) -> u32 {
+    canonical(b).0.count_ones()
+}
+
+/// Bitmask of the basis vectors of the blade (e0 = 1, e1 = 2, ...) and its
+/// sign relative to their ordered product, e.g., e31 = -e13.
+fn canonical(b: usize) -> (u8, i64) {
+    let mut v: Vec<u8> = BASIS[b][1..].bytes().map(|c| c - b'0').collect();
+    let mut sign = 1;
+    for i in 0..v.len() {
+        for j in 0..v.len() - 1 - i {
+            if v[j] > v[j + 1] {
+                v.swap(j, j + 1);
+                sign = -sign;
+            }
+        }
+    }
+    (v.iter().fold(0, |m, i| m | 1 << i), sign)
+}
+
+/// The blade with the given mask, and its sign relative to the ordered product.
+fn from_mask(mask: u8) -> (usize, i64) {
+    let b = (0..16).find(|b| canonical(*b).0 == mask).unwrap();
+    (b, canonical(b).1)
+}
+
+/// Geometric product of two basis blades as (blade, sign), None when
+/// it vanishes because e0 e0 = 0.
+pub fn geometric(a: usize, b: usize) -> Option<(usize, i64)> {
+    let (ma, sa) = canonical(a);
+    let (mb, sb) = canonical(b);
+    if ma & mb & 1 != 0 {
+        return None;
+    }
+    // Moving every vector of b past the larger ones of a.
+    let swaps: u32 = (0..4)
+        .filter(|i| ma & 1 << i != 0)
+        .map(|i| (mb & ((1 << i) - 1)).count_ones())
+        .sum();
+    let (r, sr) = from_mask(ma ^ mb);
+    let sign = if swaps & 1 == 0 { 1 } else { -1 };
+    Some((r, sa * sb * sr * sign))
+}
+
+pub fn outer(a: usize, b: usize) -> Option<(usize, i64)> {
+    if canonical(a).0 & canonical(b).0 != 0 {
+        return None;
+    }
+    geometric(a, b)
+}
+
+pub fn inner(a: usize, b: usize) -> Option<(usize, i64)> {
+    let d = grade(a).max(grade(b)) - grade(a).min(grade(b));
+    geometric(a, b).filter(|(r, _)| grade(*r) == d)
+}
+
+/// J^-1(J(a) ^ J(b)), where J maps blade i to blade 15 - i and negates
+/// the bivectors. This is the vee of ganja.js.
+pub fn regressive(a: usize, b: usize) -> Option<(usize, i64)> {
+    let j = |b: usize| if grade(b) == 2 { -1 } else { 1 };
+    let (r, s) = outer(15 - a, 15 - b)?;
+    Some((15 - r, s * j(a) * j(b) * j(15 - r)))
+}
+
+fn reverse(b: usize) -> i64 {
+    match grade(b) {
+        2 | 3 => -1,
+        _ => 1,
+    }
+}
+
+/// An input component, or a precomputed product.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Var {
+    A(usize),
+    B(usize),
+    Temp(usize),
+}
+
+/// Sorted variables of a monomial, with their integer coefficient.
+type Poly = BTreeMap<Vec<Var>, i64>;
+
+fn mul(a: &Poly, b: &Poly, sign: i64, into: &mut Poly) {
+    for (ma, ca) in a.iter() {
+        for (mb, cb) in b.iter() {
+            let mut m = ma.clone();
+            m.extend_from_slice(mb);
+            m.sort();
+            *into.entry(m).or_insert(0) += ca * cb * sign;
+        }
+    }
+}
+
+/// A multivector with one polynomial per blade.
+fn symbols(t: &Type, var: fn(usize) -> Var) -> Vec<Poly> {
+    let mut mv = vec![Poly::new(); 16];
+    for (i, b) in t.fields.iter().flat_map(|(_, bs)| bs.iter()).enumerate() {
+        mv[blade(b)].insert(vec![var(i)], 1);
+    }
+    mv
+}
+
+fn product(table: fn(usize, usize) -> Option<(usize, i64)>, a: &[Poly], b: &[Poly]) -> Vec<Poly> {
+    let mut r = vec![Poly::new(); 16];
+    for (i, pa) in a.iter().enumerate() {
+        for (j, pb) in b.iter().enumerate() {
+            if let Some((k, s)) = table(i, j) {
+                mul(pa, pb, s, &mut r[k]);
+            }
+        }
+    }
+    for p in r.iter_mut() {
+        p.retain(|_, c| *c != 0);
+    }
+    r
+}
+
+/// The polynomials of the components of out = a * b.
+fn expand(p: Product, a: &Type, b: &Type, out: &Type) -> Vec<Poly> {
+    let sa = symbols(a, Var::A);
+    let sb = symbols(b, Var::B);
+    let r = match p {
+        Product::Geometric => product(geometric, &sa, &sb),
+        Product::Outer => product(outer, &sa, &sb),
+        Product::Regressive => product(regressive, &sa, &sb),
+        Product::Inner => product(inner, &sa, &sb),
+        Product::Sandwich => {
+            let rev: Vec<Poly> = sa
+                .iter()
+                .enumerate()
+                .map(|(i, p)| p.iter().map(|(m, c)| (m.clone(), c * reverse(i))).collect())
+                .collect();
+            product(geometric, &product(geometric, &sa, &sb), &rev)
+        }
+    };
+    let blades: Vec<usize> = out
+        .fields
+        .iter()
+        .flat_map(|(_, bs)| bs.iter().map(|b| blade(b)))
+        .collect();
+    if p != Product::Sandwich {
+        for (i, poly) in r.iter().enumerate() {
+            assert!(
+                poly.is_empty() || blades.contains(&i),
+                "{} {} {}: {} does not fit in {}",
+                p.name(),
+                a.name,
+                b.name,
+                BASIS[i],
+                out.name
+            );
+        }
+    }
+    blades.iter().map(|b| r[*b].clone()).collect()
+}
+
+/// Replaces products of two variables that occur in more than one
+/// monomial with temporaries, returns what the temporaries are.
+fn eliminate(polys: &mut [Poly]) -> Vec<(Var, Var)> {
+    let mut temps = Vec::new();
+    loop {
+        let mut count: BTreeMap<(Var, Var), usize> = BTreeMap::new();
+        for m in polys.iter().flat_map(|p| p.keys()) {
+            let mut pairs = Vec::new();
+            for i in 0..m.len() {
+                for j in i + 1..m.len() {
+                    pairs.push((m[i], m[j]));
+                }
+            }
+            pairs.sort();
+            pairs.dedup();
+            for pair in pairs {
+                *count.entry(pair).or_insert(0) += 1;
+            }
+        }
+        // Most used first, the smallest on a tie so the output is stable.
+        let best = count
+            .iter()
+            .filter(|(_, n)| **n > 1)
+            .max_by(|(p, n), (q, m)| n.cmp(m).then(q.cmp(p)));
+        let (x, y) = match best {
+            Some((pair, _)) => *pair,
+            None => return temps,
+        };
+        let t = Var::Temp(temps.len());
+        temps.push((x, y));
+        for p in polys.iter_mut() {
+            let mut q = Poly::new();
+            for (m, c) in p.iter() {
+                let mut m = m.clone();
+                if let Some(i) = m.iter().position(|v| *v == x) {
+                    m.remove(i);
+                    match m.iter().position(|v| *v == y) {
+                        Some(j) => {
+                            m.remove(j);
+                            m.push(t);
+                            m.sort();
+                        }
+                        None => m.insert(i, x),
+                    }
+                }
+                *q.entry(m).or_insert(0) += c;
+            }
+            *p = q;
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Factor {
+    Var(Var),
+    Sum(Vec<Term>),
+}
+
+#[derive(Debug)]
+struct Term {
+    coef: i64,
+    factors: Vec<Factor>,
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Factors out the variable most terms have, recursively.
+fn factor(terms: Vec<(Vec<Var>, i64)>) -> Vec<Term> {
+    let mut count: BTreeMap<Var, usize> = BTreeMap::new();
+    for (m, _) in terms.iter() {
+        let mut vars = m.clone();
+        vars.dedup();
+        for v in vars {
+            *count.entry(v).or_insert(0) += 1;
+        }
+    }
+    let best = count
+        .iter()
+        .filter(|(_, n)| **n > 1)
+        .max_by(|(v, n), (w, m)| n.cmp(m).then(w.cmp(v)));
+    let v = match best {
+        Some((v, _)) => *v,
+        None => {
+            return terms
+                .into_iter()
+                .map(|(m, coef)| Term {
+                    coef,
+                    factors: m.into_iter().map(Factor::Var).collect(),
+                })
+                .collect()
+        }
+    };
+    let (mut with, without): (Vec<_>, Vec<_>) =
+        terms.into_iter().partition(|(m, _)| m.contains(&v));
+    let mut g = with.iter().fold(0, |g, (_, c)| gcd(g, c.abs()));
+    if with.iter().all(|(_, c)| *c < 0) {
+        g = -g;
+    }
+    for (m, c) in with.iter_mut() {
+        let i = m.iter().position(|w| *w == v).unwrap();
+        m.remove(i);
+        *c /= g;
+    }
+    let mut inner = factor(with);
+    let term = if inner.len() == 1 {
+        let t = inner.pop().unwrap();
+        let mut factors = vec![Factor::Var(v)];
+        factors.extend(t.factors);
+        Term {
+            coef: g * t.coef,
+            factors,
+        }
+    } else {
+        Term {
+            coef: g,
+            factors: vec![Factor::Var(v), Factor::Sum(inner)],
+        }
+    };
+    let mut r = vec![term];
+    r.extend(factor(without));
+    r
+}
+
+/// How the function refers to a variable.
+fn var_name(v: Var, a: &Type, b: &Type) -> String {
+    let (t, name, i) = match v {
+        Var::A(i) => (a, "a", i),
+        Var::B(i) => (b, "b", i),
+        Var::Temp(i) => return format!("t{}", i),
+    };
+    let mut i = i;
+    for (field, blades) in t.fields.iter() {
+        if i < blades.len() {
+            return match (*field, blades.len()) {
+                ("", _) => name.to_string(),
+                (f, 1) => format!("{}.{}", name, f),
+                (f, _) => format!("{}.{}[{}]", name, f, i),
+            };
+        }
+        i -= blades.len();
+    }
+    unreachable!()
+}
+
+fn sum(terms: &[Term], names: &dyn Fn(Var) -> String) -> String {
+    if terms.is_empty() {
+        return "0.".into();
+    }
+    let mut s = String::new();
+    for (i, t) in terms.iter().enumerate() {
+        let factors: Vec<String> = t
+            .factors
+            .iter()
+            .map(|f| match f {
+                Factor::Var(v) => names(*v),
+                Factor::Sum(terms) => format!("({})", sum(terms, names)),
+            })
+            .collect();
+        let body = match (t.coef.abs(), factors.is_empty()) {
+            (c, true) => format!("{}.", c),
+            (1, false) => factors.join(" * "),
+            (c, false) => format!("{}. * {}", c, factors.join(" * ")),
+        };
+        match (i, t.coef < 0) {
+            (0, false) => {}
+            (0, true) => s.push('-'),
+            (_, false) => s.push_str(" + "),
+            (_, true) => s.push_str(" - "),
+        }
+        s.push_str(&body);
+    }
+    s
+}
+
+fn snake(name: &str) -> String {
+    let mut s = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            s.push('_');
+        }
+        s.push(c.to_ascii_lowercase());
+    }
+    s
+}
+
+/// The name of the generated function, e.g., geometric_motor_motor.
+pub fn function_name(p: Product, a: &str, b: &str) -> String {
+    format!("{}_{}_{}", p.name(), snake(a), snake(b))
+}
+
+/// Rust source of the function computing p for a and b.
+pub fn function(p: Product, a: &str, b: &str, out: &str) -> String {
+    let (ta, tb, tout) = (ty(a), ty(b), ty(out));
+    let mut polys = expand(p, ta, tb, tout);
+    let temps = eliminate(&mut polys);
+    let names = |v: Var| var_name(v, ta, tb);
+
+    let mut s = String::new();
+    writeln!(
+        s,
+        "#[inline]\npub fn {}(a: &{}, b: &{}) -> {} {{",
+        function_name(p, a, b),
+        a,
+        b,
+        out
+    )
+    .unwrap();
+    for (i, (x, y)) in temps.iter().enumerate() {
+        writeln!(s, "    let t{} = {} * {};", i, names(*x), names(*y)).unwrap();
+    }
+    let mut values = polys
+        .into_iter()
+        .map(|p| sum(&factor(p.into_iter().collect()), &names));
+    match out {
+        "f32" => writeln!(s, "    {}", values.next().unwrap()).unwrap(),
+        "PseudoScalar" => writeln!(s, "    PseudoScalar({})", values.next().unwrap()).unwrap(),
+        _ => {
+            writeln!(s, "    {} {{", out).unwrap();
+            for (field, blades) in tout.fields.iter() {
+                let v: Vec<String> = values.by_ref().take(blades.len()).collect();
+                if blades.len() == 1 {
+                    writeln!(s, "        {}: {},", field, v[0]).unwrap();
+                } else {
+                    writeln!(s, "        {}: [{}],", field, v.join(", ")).unwrap();
+                }
+            }
+            writeln!(s, "    }}").unwrap();
+        }
+    }
+    s.push_str("}\n");
+    s
+}
+
+/// Source of all the products, as included by the products module.
+pub fn generate(products: &[(Product, &str, &str, &str)]) -> String {
+    let mut s = String::from("// Generated by src/codegen.rs, do not edit.\n");
+    for (p, a, b, out) in products.iter() {
+        s.push('\n');
+        s.push_str(&function(*p, a, b, out));
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::*;
+
+    #[test]
+    fn cayley() {
+        let gp = |a: &str, b: &str| geometric(blade(a), blade(b)).map(|(r, s)| (BASIS[r], s));
+        assert_eq!(gp("e0", "e0"), None);
+        assert_eq!(gp("e1", "e1"), Some(("1", 1)));
+        assert_eq!(gp("e12", "e12"), Some(("1", -1)));
+        assert_eq!(gp("e3", "e1"), Some(("e31", 1)));
+        assert_eq!(gp("e1", "e3"), Some(("e31", -1)));
+        assert_eq!(gp("e0", "e123"), Some(("e0123", 1)));
+        assert_eq!(gp("e123", "e123"), Some(("1", -1)));
+
+        // Associativity, zero included.
+        let then = |x: Option<(usize, i64)>, c| {
+            x.and_then(|(r, s)| geometric(r, c).map(|(r, t)| (r, s * t)))
+        };
+        for a in 0..16 {
+            for b in 0..16 {
+                for c in 0..16 {
+                    let ab_c = then(geometric(a, b), c);
+                    let a_bc =
+                        geometric(b, c).and_then(|(r, s)| geometric(a, r).map(|(r, t)| (r, s * t)));
+                    assert_eq!(ab_c, a_bc);
+                }
+            }
+        }
+
+        assert_eq!(outer(blade("e1"), blade("e1")), None);
+        assert_eq!(inner(blade("e1"), blade("e12")), Some((blade("e2"), 1)));
+        assert_eq!(outer(blade("e1"), blade("e2")), Some((blade("e12"), 1)));
+        // The pseudoscalar is the unit of the join.
+        assert_eq!(
+            regressive(blade("e0123"), blade("e2")),
+            Some((blade("e2"), 1))
+        );
+    }
+
+    #[test]
+    fn any_pair() {
+        let f = function(Product::Geometric, "Point", "Plane", "Motor");
+        assert!(f.starts_with(
+            "#[inline]\npub fn geometric_point_plane(a: &Point, b: &Plane) -> Motor {"
+        ));
+        assert!(f.contains("scalar: 0.,"));
+        let f = function(Product::Inner, "Plane", "Plane", "f32");
+        assert_eq!(
+            f,
+            "#[inline]\npub fn inner_plane_plane(a: &Plane, b: &Plane) -> f32 {\n    \
+             a.vector[1] * b.vector[1] + a.vector[2] * b.vector[2] + a.vector[3] * b.vector[3]\n}\n"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn no_room() {
+        function(Product::Geometric, "Motor", "Motor", "Rotor");
+    }
+}
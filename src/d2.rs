@@ -0,0 +1,38 @@
+//! Plane-based geometric algebra of the plane, i.e., G(2,0,1), for planar
+//! problems like sketches or top-down navigation. Mirrors the 3D types and
+//! uses the same PGA4CS conventions: lines are vectors, points bivectors.
+//!
+//! Memory model:
+//!     scalar = 1
+//!     vector = { e0, e1, e2 }
+//!     bivector = { e12, e20, e01 }
+//!     e_bivector = e12
+//!     v_bivector = { e20, e01 }
+//!
+//! A motor is scalar + bivector, the even part of the algebra, which has
+//! no pseudoscalar in 2D (e012 is odd). The bivector is a point, so
+//! Point2::exp rotates around that point and Motor2::ln gives it back.
+//!
+//! The From conversions embed everything into 3D through the xy plane,
+//! e.g., Point2 (x, y) becomes Point (x, y, 0) and Motor2 a motor around
+//! the z axis.
+
+mod line;
+mod motor;
+mod point;
+mod rotor;
+mod translator;
+
+pub mod join;
+pub mod meet;
+
+pub use line::Line2;
+pub use motor::Motor2;
+pub use point::Point2;
+pub use rotor::Rotor2;
+pub use translator::Translator2;
+
+#[allow(non_upper_case_globals)]
+pub const vector: [&str; 3] = ["e0", "e1", "e2"];
+#[allow(non_upper_case_globals)]
+pub const bivector: [&str; 3] = ["e12", "e20", "e01"];
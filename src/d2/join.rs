@@ -0,0 +1,38 @@
+/// The line through p1 and p2, p1 & p2.
+pub fn points(p1: &super::Point2, p2: &super::Point2) -> super::Line2 {
+    let p = p1.bivector;
+    let q = p2.bivector;
+    super::Line2 {
+        vector: [
+            p[1] * q[2] - p[2] * q[1],
+            p[2] * q[0] - p[0] * q[2],
+            p[0] * q[1] - p[1] * q[0],
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::d2::*;
+    use crate::{Line, Point};
+
+    #[test]
+    fn incidence() {
+        let p1 = Point2::new(&[3., 4.]);
+        let p2 = Point2::new(&[-1., 2.]);
+        let l = join::points(&p1, &p2);
+        assert!(meet::line_with_point(&l, &p1).abs() < 1e-5);
+        assert!(meet::line_with_point(&l, &p2).abs() < 1e-5);
+        assert_eq!(l, meet::lines(&p1.dual(), &p2.dual()).dual());
+    }
+
+    #[test]
+    fn embedding() {
+        let p1 = Point2::random();
+        let p2 = Point2::random();
+        assert_eq!(
+            Line::from(&join::points(&p1, &p2)),
+            crate::join::points(&Point::from(&p1), &Point::from(&p2))
+        );
+    }
+}
@@ -0,0 +1,134 @@
+#[derive(Debug, Copy, Clone)]
+pub struct Line2 {
+    pub vector: [f32; 3],
+}
+
+impl Line2 {
+    /// So that d*n is on the line, like Plane::new.
+    /// Standard form: n_1x + n_2y = d.
+    pub fn new(d: f32, n: &[f32; 2]) -> Self {
+        Self {
+            vector: [d, n[0], n[1]],
+        }
+    }
+
+    pub fn random() -> Self {
+        let n = na::Vector2::<f32>::new_random().normalize();
+        Self::new(rand::random(), &n.into())
+    }
+    /// The x axis, y = 0.
+    pub fn x() -> Self {
+        Self::new(0.0, &[0., 1.])
+    }
+    /// The y axis, x = 0.
+    pub fn y() -> Self {
+        Self::new(0.0, &[1., 0.])
+    }
+
+    pub fn dual(&self) -> super::Point2 {
+        super::Point2 {
+            bivector: self.vector,
+        }
+    }
+
+    pub fn norm(&self) -> f32 {
+        (self.vector[1] * self.vector[1] + self.vector[2] * self.vector[2]).sqrt()
+    }
+    pub fn normalize(&self) -> Self {
+        self.div_scalar(self.norm())
+    }
+
+    pub fn neg(&self) -> Self {
+        self.mul_scalar(-1.)
+    }
+
+    pub fn inverse(&self) -> Self {
+        let v = self.vector;
+        self.mul_scalar(1. / (v[1] * v[1] + v[2] * v[2]))
+    }
+
+    pub fn mul_scalar(&self, s: f32) -> Self {
+        let v = self.vector;
+        Self {
+            vector: [v[0] * s, v[1] * s, v[2] * s],
+        }
+    }
+    pub fn div_scalar(&self, s: f32) -> Self {
+        self.mul_scalar(1. / s)
+    }
+
+    pub fn mul(&self, other: &Self) -> super::Motor2 {
+        let a = self.vector;
+        let b = other.vector;
+        super::Motor2 {
+            scalar: a[1] * b[1] + a[2] * b[2],
+            e_bivector: a[1] * b[2] - a[2] * b[1],
+            v_bivector: [a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]],
+        }
+    }
+
+    pub fn div(&self, other: &Self) -> super::Motor2 {
+        self.mul(&other.inverse())
+    }
+
+    pub fn move_to(&self, dest: &Self) -> super::Motor2 {
+        dest.div(self).sqrt()
+    }
+}
+
+impl PartialEq for Line2 {
+    fn eq(&self, other: &Self) -> bool {
+        !self
+            .vector
+            .iter()
+            .zip(other.vector.iter())
+            .any(|(a, b)| (a - b).abs() > 0.01)
+    }
+}
+
+/// The plane through the line, orthogonal to the xy plane.
+impl From<&Line2> for crate::Plane {
+    fn from(l: &Line2) -> Self {
+        let v = l.vector;
+        Self {
+            vector: [v[0], v[1], v[2], 0.0],
+        }
+    }
+}
+
+/// The line in the xy plane.
+impl From<&Line2> for crate::Line {
+    fn from(l: &Line2) -> Self {
+        crate::meet::planes(&crate::Plane::xy(), &crate::Plane::from(l))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::d2::*;
+    use crate::Plane;
+
+    #[test]
+    fn new() {
+        let l = Line2::new(2., &[0., 1.]);
+        assert_eq!(l.vector, [2., 0., 1.]);
+        assert!(meet::line_with_point(&l, &Point2::new(&[5., 2.])).abs() < 1e-6);
+        assert_eq!(Plane::from(&l), Plane::new(2., &[0., 1., 0.]));
+    }
+
+    #[test]
+    fn move_to() {
+        let l1 = Line2::random();
+        let l2 = Line2::random();
+        let m = l1.move_to(&l2);
+        assert_eq!(m.apply_to_line(&l1), l2);
+    }
+
+    #[test]
+    fn reflection() {
+        // A line times a line is the rotation by twice the angle between them.
+        let m = Line2::y().mul(&Line2::x());
+        let p = m.apply_to_point(&Point2::new(&[1., 1.]));
+        assert_eq!(p.normalize(), Point2::new(&[-1., -1.]));
+    }
+}
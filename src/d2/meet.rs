@@ -0,0 +1,45 @@
+/// l1 ^ l2 = (ae0 + be1 + ce2) ^ (xe0 + ye1 + ze2)
+///         = e12(bz - cy)
+///           + e20(cx - az)
+///           + e01(ay - bx)
+pub fn lines(l1: &super::Line2, l2: &super::Line2) -> super::Point2 {
+    let a = l1.vector;
+    let b = l2.vector;
+    super::Point2 {
+        bivector: [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ],
+    }
+}
+
+/// The e012 part of l ^ p, zero when p is on l. For a normalized line and
+/// point it is d - n.x, the signed distance.
+pub fn line_with_point(l: &super::Line2, p: &super::Point2) -> f32 {
+    let l = l.vector;
+    let p = p.bivector;
+    l[0] * p[0] + l[1] * p[1] + l[2] * p[2]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::d2::*;
+    use crate::{Plane, Point};
+
+    #[test]
+    fn intersection() {
+        let l1 = Line2::new(1., &[1., 0.]);
+        let l2 = Line2::new(2., &[0., 1.]);
+        assert_eq!(meet::lines(&l1, &l2).normalize(), Point2::new(&[1., 2.]));
+    }
+
+    #[test]
+    fn embedding() {
+        let l1 = Line2::random();
+        let l2 = Line2::random();
+        let p = Point::from(&meet::lines(&l1, &l2));
+        let p_ = crate::meet::three_planes(&Plane::from(&l1), &Plane::from(&l2), &Plane::xy());
+        assert_eq!(p.normalize(), p_.normalize());
+    }
+}
@@ -0,0 +1,253 @@
+#[derive(Debug, Copy, Clone)]
+pub struct Motor2 {
+    pub scalar: f32,
+    pub e_bivector: f32,
+    pub v_bivector: [f32; 2],
+}
+
+impl Motor2 {
+    pub fn one() -> Self {
+        Self {
+            scalar: 1.0,
+            e_bivector: 0.0,
+            v_bivector: [0.0; 2],
+        }
+    }
+    pub fn zero() -> Self {
+        Self {
+            scalar: 0.0,
+            ..Self::one()
+        }
+    }
+
+    pub fn random() -> Self {
+        Self {
+            scalar: rand::random(),
+            e_bivector: rand::random(),
+            v_bivector: na::Vector2::new_random().into(),
+        }
+    }
+
+    pub fn neg(&self) -> Self {
+        self.mul_scalar(-1.)
+    }
+
+    pub fn norm(&self) -> f32 {
+        (self.scalar * self.scalar + self.e_bivector * self.e_bivector).sqrt()
+    }
+    pub fn normalize(&self) -> Self {
+        self.div_scalar(self.norm())
+    }
+
+    /// For a normalized motor. Every 2D motor is simple, so this is
+    /// Motor::ssqrt.
+    pub fn sqrt(&self) -> Self {
+        self.add_scalar(1.).normalize()
+    }
+
+    pub fn ln(&self) -> super::Point2 {
+        let s = self.scalar;
+        let e = self.e_bivector;
+        let [v0, v1] = self.v_bivector;
+        // When self is a translator nothing has to be done
+        if e.abs() < 0.001 {
+            return super::Point2 {
+                bivector: [e / s, v0 / s, v1 / s],
+            };
+        }
+        let u = e.atan2(s) / e;
+        super::Point2 {
+            bivector: [e * u, v0 * u, v1 * u],
+        }
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        let (as_, ae, av) = (self.scalar, self.e_bivector, self.v_bivector);
+        let (bs, be, bv) = (other.scalar, other.e_bivector, other.v_bivector);
+        Self {
+            scalar: as_ * bs - ae * be,
+            e_bivector: as_ * be + ae * bs,
+            v_bivector: [
+                as_ * bv[0] + av[0] * bs + ae * bv[1] - av[1] * be,
+                as_ * bv[1] + av[1] * bs - ae * bv[0] + av[0] * be,
+            ],
+        }
+    }
+
+    pub fn div(&self, other: &Self) -> Self {
+        self.mul(&other.inverse())
+    }
+
+    pub fn mul_translator(&self, t: &super::Translator2) -> Self {
+        self.mul(&Self::from(t))
+    }
+
+    pub fn apply_to_point(&self, p: &super::Point2) -> super::Point2 {
+        let (s, e, [v0, v1]) = (self.scalar, self.e_bivector, self.v_bivector);
+        let p = p.bivector;
+        let c = s * s - e * e;
+        super::Point2 {
+            bivector: [
+                p[0] * (s * s + e * e),
+                c * p[1] + 2. * e * s * p[2] + 2. * p[0] * (e * v0 - s * v1),
+                c * p[2] - 2. * e * s * p[1] + 2. * p[0] * (e * v1 + s * v0),
+            ],
+        }
+    }
+
+    pub fn apply_to_line(&self, l: &super::Line2) -> super::Line2 {
+        let (s, e, [v0, v1]) = (self.scalar, self.e_bivector, self.v_bivector);
+        let l = l.vector;
+        let c = s * s - e * e;
+        super::Line2 {
+            vector: [
+                l[0] * (s * s + e * e)
+                    + 2. * l[1] * (e * v0 + s * v1)
+                    + 2. * l[2] * (e * v1 - s * v0),
+                c * l[1] + 2. * e * s * l[2],
+                c * l[2] - 2. * e * s * l[1],
+            ],
+        }
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        Self {
+            scalar: self.scalar + other.scalar,
+            e_bivector: self.e_bivector + other.e_bivector,
+            v_bivector: [
+                self.v_bivector[0] + other.v_bivector[0],
+                self.v_bivector[1] + other.v_bivector[1],
+            ],
+        }
+    }
+
+    pub fn inverse(&self) -> Self {
+        self.reverse()
+            .div_scalar(self.scalar * self.scalar + self.e_bivector * self.e_bivector)
+    }
+
+    pub fn mul_scalar(&self, s: f32) -> Self {
+        Self {
+            scalar: self.scalar * s,
+            e_bivector: self.e_bivector * s,
+            v_bivector: [self.v_bivector[0] * s, self.v_bivector[1] * s],
+        }
+    }
+    pub fn div_scalar(&self, s: f32) -> Self {
+        self.mul_scalar(1. / s)
+    }
+    pub fn add_scalar(&self, s: f32) -> Self {
+        Self {
+            scalar: self.scalar + s,
+            ..*self
+        }
+    }
+
+    pub fn reverse(&self) -> Self {
+        Self {
+            scalar: self.scalar,
+            e_bivector: -self.e_bivector,
+            v_bivector: [-self.v_bivector[0], -self.v_bivector[1]],
+        }
+    }
+}
+
+impl PartialEq for Motor2 {
+    fn eq(&self, other: &Self) -> bool {
+        self.v_bivector
+            .iter()
+            .zip(other.v_bivector.iter())
+            .all(|(a, b)| (a - b).abs() < 0.01)
+            && (self.scalar - other.scalar).abs() < 0.01
+            && (self.e_bivector - other.e_bivector).abs() < 0.01
+    }
+}
+
+impl From<&super::Point2> for Motor2 {
+    fn from(p: &super::Point2) -> Self {
+        let [e, v0, v1] = p.bivector;
+        Self {
+            scalar: 0.,
+            e_bivector: e,
+            v_bivector: [v0, v1],
+        }
+    }
+}
+
+impl From<&super::Translator2> for Motor2 {
+    fn from(t: &super::Translator2) -> Self {
+        Self {
+            scalar: t.scalar,
+            e_bivector: 0.,
+            v_bivector: t.v_bivector,
+        }
+    }
+}
+
+impl From<&super::Rotor2> for Motor2 {
+    fn from(r: &super::Rotor2) -> Self {
+        Self {
+            scalar: r.scalar,
+            e_bivector: r.e_bivector,
+            v_bivector: [0.; 2],
+        }
+    }
+}
+
+/// The motor around the z axis, e20 = -e02.
+impl From<&Motor2> for crate::Motor {
+    fn from(m: &Motor2) -> Self {
+        Self {
+            scalar: m.scalar,
+            e_bivector: [0., 0., m.e_bivector],
+            v_bivector: [m.v_bivector[1], -m.v_bivector[0], 0.],
+            pseudo: 0.,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::d2::*;
+    use crate::{Line, Motor, Plane, Point};
+
+    fn random_motor() -> Motor2 {
+        Point2::random().mul_scalar(rand::random()).exp()
+    }
+
+    #[test]
+    fn embedding() {
+        for _ in 0..10 {
+            let m = random_motor();
+            let p = Point2::random();
+            let l = Line2::random();
+            let m3 = Motor::from(&m);
+            assert_eq!(
+                Point::from(&m.apply_to_point(&p)),
+                m3.apply_to_point(&Point::from(&p))
+            );
+            assert_eq!(
+                Plane::from(&m.apply_to_line(&l)),
+                m3.apply_to_plane(&Plane::from(&l))
+            );
+            assert_eq!(
+                Line::from(&m.apply_to_line(&l)),
+                m3.apply_to_line(&Line::from(&l))
+            );
+            let n = random_motor();
+            assert_eq!(Motor::from(&m.mul(&n)), m3.mul(&Motor::from(&n)));
+        }
+    }
+
+    #[test]
+    fn ln() {
+        for _ in 0..10 {
+            let m = random_motor();
+            assert_eq!(m.ln().exp(), m);
+            assert_eq!(m.sqrt().mul(&m.sqrt()), m);
+            assert_eq!(m.mul(&m.inverse()), Motor2::one());
+        }
+        let t = Translator2::new(&[1., -2.]);
+        assert_eq!(Motor2::from(&t).ln().exp(), Motor2::from(&t));
+    }
+}
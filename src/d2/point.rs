@@ -0,0 +1,175 @@
+#[derive(Debug, Copy, Clone)]
+pub struct Point2 {
+    pub bivector: [f32; 3],
+}
+
+impl Point2 {
+    /// Like Point::new, (x, y) on basis { e02, e10 } and one times e12.
+    pub fn new([x, y]: &[f32; 2]) -> Self {
+        Self {
+            bivector: [1.0, -x, -y],
+        }
+    }
+
+    /// Directions, or points at infinity, have zero for e12.
+    pub fn inf([x, y]: &[f32; 2]) -> Self {
+        Self {
+            bivector: [0.0, -x, -y],
+        }
+    }
+
+    pub fn origin() -> Self {
+        Self {
+            bivector: [1.0, 0.0, 0.0],
+        }
+    }
+
+    pub fn x() -> Self {
+        Self::new(&[1., 0.])
+    }
+    pub fn y() -> Self {
+        Self::new(&[0., 1.])
+    }
+
+    pub fn eucl(&self) -> [f32; 2] {
+        [-self.bivector[1], -self.bivector[2]]
+    }
+
+    pub fn random() -> Self {
+        use rand::prelude::*;
+        let mut rng = rand::thread_rng();
+        Self {
+            bivector: [1., rng.gen::<f32>(), rng.gen::<f32>()],
+        }
+    }
+
+    pub fn dual(&self) -> super::Line2 {
+        super::Line2 {
+            vector: self.bivector,
+        }
+    }
+
+    pub fn neg(&self) -> Self {
+        self.mul_scalar(-1.)
+    }
+
+    pub fn norm(&self) -> f32 {
+        self.bivector[0]
+    }
+    pub fn normalize(&self) -> Self {
+        self.div_scalar(self.norm())
+    }
+
+    pub fn is_inf(&self) -> bool {
+        self.bivector[0].abs() < 0.01
+    }
+
+    pub fn inverse(&self) -> Self {
+        let p = self.bivector;
+        self.mul_scalar(-1. / (p[0] * p[0]))
+    }
+
+    pub fn mul_scalar(&self, s: f32) -> Self {
+        let p = self.bivector;
+        Self {
+            bivector: [p[0] * s, p[1] * s, p[2] * s],
+        }
+    }
+    pub fn div_scalar(&self, s: f32) -> Self {
+        self.mul_scalar(1. / s)
+    }
+
+    pub fn mul(&self, other: &Self) -> super::Translator2 {
+        let p = self.bivector;
+        let q = other.bivector;
+        super::Translator2 {
+            scalar: -p[0] * q[0],
+            v_bivector: [p[0] * q[2] - p[2] * q[0], p[1] * q[0] - p[0] * q[1]],
+        }
+    }
+
+    pub fn div(&self, other: &Self) -> super::Translator2 {
+        self.mul(&other.inverse())
+    }
+
+    pub fn move_to(&self, dest: &Self) -> super::Translator2 {
+        dest.div(self).sqrt()
+    }
+
+    /// The bivector as the generator of a motor, a rotation around the
+    /// point by twice its weight, or a translation if it is at infinity.
+    pub fn exp(&self) -> super::Motor2 {
+        let [u, v0, v1] = self.bivector;
+        if u.abs() < 0.001 {
+            return super::Motor2::from(self).add_scalar(1.);
+        }
+        let su = u.sin() / u;
+        super::Motor2 {
+            scalar: u.cos(),
+            e_bivector: u * su,
+            v_bivector: [v0 * su, v1 * su],
+        }
+    }
+
+    pub fn is_similar_to(&self, d: f32, other: &Self) -> bool {
+        !self
+            .bivector
+            .iter()
+            .zip(other.bivector.iter())
+            .any(|(a, b)| (a - b).abs() > d)
+    }
+}
+
+impl PartialEq for Point2 {
+    fn eq(&self, other: &Self) -> bool {
+        self.is_similar_to(0.01, other)
+    }
+}
+
+impl From<[f32; 2]> for Point2 {
+    fn from(x: [f32; 2]) -> Self {
+        Self::new(&x)
+    }
+}
+
+impl From<&Point2> for crate::Point {
+    fn from(p: &Point2) -> Self {
+        let p = p.bivector;
+        Self {
+            trivector: [p[0], p[1], p[2], 0.0],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::d2::*;
+    use crate::Point;
+
+    #[test]
+    fn new() {
+        let p = Point2::new(&[4., 3.]);
+        assert_eq!(p.bivector, [1., -4., -3.]);
+        assert_eq!(p.eucl(), [4., 3.]);
+        assert_eq!(Point::from(&p), Point::new(&[4., 3., 0.]));
+        assert_eq!(p, p.dual().dual());
+    }
+
+    #[test]
+    fn move_to() {
+        let p1 = Point2::random().normalize();
+        let p2 = Point2::random().normalize();
+        let t = p1.move_to(&p2);
+        assert_eq!(p2, t.apply_to_point(&p1));
+    }
+
+    #[test]
+    fn exp() {
+        let c = Point2::new(&[1., 2.]);
+        let m = c.mul_scalar(0.3).exp();
+        assert_eq!(m.apply_to_point(&c), c);
+        assert_eq!(m.ln(), c.mul_scalar(0.3));
+        let t = Point2::inf(&[1., 0.]).exp();
+        assert_eq!(t.ln(), Point2::inf(&[1., 0.]));
+    }
+}
@@ -0,0 +1,102 @@
+#[derive(Debug, Copy, Clone)]
+pub struct Rotor2 {
+    pub scalar: f32,
+    pub e_bivector: f32,
+}
+
+impl Rotor2 {
+    /// Rotation around the origin, like Rotor::new around the z axis.
+    pub fn new(a: f32) -> Self {
+        let ha = 0.5 * a;
+        Self {
+            scalar: ha.cos(),
+            e_bivector: -ha.sin(),
+        }
+    }
+
+    pub fn random() -> Self {
+        Self::new(rand::random::<f32>() * std::f32::consts::PI * 2.0)
+    }
+
+    pub fn norm(&self) -> f32 {
+        (self.scalar * self.scalar + self.e_bivector * self.e_bivector).sqrt()
+    }
+    pub fn normalize(&self) -> Self {
+        let fac = 1. / self.norm();
+        Self {
+            scalar: self.scalar * fac,
+            e_bivector: self.e_bivector * fac,
+        }
+    }
+
+    pub fn sqrt(&self) -> Self {
+        Self {
+            scalar: self.scalar + 1.,
+            ..*self
+        }
+        .normalize()
+    }
+
+    pub fn neg(&self) -> Self {
+        Self {
+            scalar: -self.scalar,
+            e_bivector: -self.e_bivector,
+        }
+    }
+
+    pub fn ln(&self) -> super::Point2 {
+        super::Point2 {
+            bivector: [self.e_bivector.atan2(self.scalar), 0., 0.],
+        }
+    }
+
+    pub fn apply_to_point(&self, p: &super::Point2) -> super::Point2 {
+        super::Motor2::from(self).apply_to_point(p)
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        Self {
+            scalar: self.scalar * other.scalar - self.e_bivector * other.e_bivector,
+            e_bivector: self.scalar * other.e_bivector + self.e_bivector * other.scalar,
+        }
+    }
+
+    pub fn mul_translator(&self, t: &super::Translator2) -> super::Motor2 {
+        super::Motor2::from(self).mul_translator(t)
+    }
+}
+
+impl PartialEq for Rotor2 {
+    fn eq(&self, other: &Self) -> bool {
+        (self.scalar - other.scalar).abs() < 0.01
+            && (self.e_bivector - other.e_bivector).abs() < 0.01
+    }
+}
+
+impl From<&Rotor2> for crate::Rotor {
+    fn from(r: &Rotor2) -> Self {
+        Self {
+            scalar: r.scalar,
+            e_bivector: [0., 0., r.e_bivector],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::d2::*;
+    use crate::{Point, Rotor};
+
+    #[test]
+    fn new() {
+        let r = Rotor2::new(1.2);
+        assert_eq!(Rotor::from(&r), Rotor::new(1.2, &[0., 0., 1.]));
+        let p = Point2::new(&[1., 2.]);
+        assert_eq!(
+            Point::from(&r.apply_to_point(&p)),
+            Rotor::from(&r).apply_to_point(&Point::from(&p))
+        );
+        assert_eq!(r.sqrt().mul(&r.sqrt()), r);
+        assert_eq!(r.ln().exp(), Motor2::from(&r));
+    }
+}
@@ -0,0 +1,87 @@
+#[derive(Debug, Copy, Clone)]
+pub struct Translator2 {
+    pub scalar: f32,
+    pub v_bivector: [f32; 2],
+}
+
+impl Translator2 {
+    /// Like Translator::new, e20 = -e02.
+    pub fn new([t1, t2]: &[f32; 2]) -> Self {
+        Self {
+            scalar: 1.,
+            v_bivector: [-t2 * 0.5, t1 * 0.5],
+        }
+    }
+    pub fn sqrt(&self) -> Self {
+        let ts = self.scalar;
+        let tv = self.v_bivector;
+        let fac = 2.0f32.sqrt() / (2. * (ts + 1.).sqrt());
+        Self {
+            scalar: 0.5 * (2. * ts + 2.).sqrt(),
+            v_bivector: [tv[0] * fac, tv[1] * fac],
+        }
+    }
+
+    pub fn norm(&self) -> f32 {
+        self.scalar.abs()
+    }
+    pub fn normalize(&self) -> Self {
+        self.div_scalar(self.norm())
+    }
+
+    pub fn apply_to_point(&self, p: &super::Point2) -> super::Point2 {
+        super::Motor2::from(self).apply_to_point(p)
+    }
+
+    pub fn mul_rotor(&self, r: &super::Rotor2) -> super::Motor2 {
+        super::Motor2::from(self).mul(&super::Motor2::from(r))
+    }
+
+    pub fn add_scalar(&self, s: f32) -> Self {
+        Self {
+            scalar: self.scalar + s,
+            ..*self
+        }
+    }
+    pub fn mul_scalar(&self, s: f32) -> Self {
+        Self {
+            scalar: self.scalar * s,
+            v_bivector: [self.v_bivector[0] * s, self.v_bivector[1] * s],
+        }
+    }
+    pub fn div_scalar(&self, s: f32) -> Self {
+        self.mul_scalar(1. / s)
+    }
+}
+
+impl From<&Translator2> for crate::Translator {
+    fn from(t: &Translator2) -> Self {
+        Self {
+            scalar: t.scalar,
+            v_bivector: [t.v_bivector[1], -t.v_bivector[0], 0.],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::d2::*;
+    use crate::{Motor, Point, Rotor, Translator};
+
+    #[test]
+    fn new() {
+        let t = Translator2::new(&[1., 2.]);
+        let t3 = Translator::from(&t);
+        assert_eq!(t3.v_bivector, Translator::new(&[1., 2., 0.]).v_bivector);
+        let p = t.apply_to_point(&Point2::new(&[3., -1.]));
+        assert_eq!(
+            Point::from(&p),
+            t3.apply_to_point(&Point::new(&[3., -1., 0.]))
+        );
+        let m = t.mul_rotor(&Rotor2::new(0.4));
+        assert_eq!(
+            Motor::from(&m),
+            t3.mul_rotor(&Rotor::from(&Rotor2::new(0.4)))
+        );
+    }
+}
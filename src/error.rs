@@ -29,3 +29,18 @@ where
 }
 
 impl<'a, F: fmt::Debug> std::error::Error for CastError<'a, F> {}
+
+/// An error in a text format (BVH, URDF, GLSL) on a 1-based line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
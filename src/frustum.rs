@@ -0,0 +1,206 @@
+//! View frustums for culling, six planes with outward normals as in
+//! polyhedron. The camera looks along its local +z with x to the right
+//! and y down, and the pose motor moves it from the origin into the world.
+
+use super::bounds::{Aabb, Obb, Sphere};
+use super::polyhedron::ConvexPolyhedron;
+use super::{Applicable, Motor, Plane, Point};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Containment {
+    Inside,
+    Outside,
+    Intersecting,
+}
+
+/// Planes in the order -x, +x, -y, +y, near, far.
+#[derive(Debug)]
+pub struct Frustum {
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    pub fn new(planes: [Plane; 6]) -> Self {
+        Self {
+            planes: planes.map(|p| p.normalize()),
+        }
+    }
+
+    /// fov_y is the full vertical angle, aspect is width over height.
+    pub fn perspective(pose: &Motor, fov_y: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let hy = (fov_y / 2.).tan();
+        let hx = hy * aspect;
+        // x <= z hx, i.e., x - hx z <= 0
+        let local = Self::new([
+            Plane::new(0., &[-1., 0., -hx]),
+            Plane::new(0., &[1., 0., -hx]),
+            Plane::new(0., &[0., -1., -hy]),
+            Plane::new(0., &[0., 1., -hy]),
+            Plane::new(-near, &[0., 0., -1.]),
+            Plane::new(far, &[0., 0., 1.]),
+        ]);
+        local.apply(pose)
+    }
+
+    /// The box between min and max in camera coordinates, with the near
+    /// and far planes at min[2] and max[2].
+    pub fn orthographic(pose: &Motor, min: &[f32; 3], max: &[f32; 3]) -> Self {
+        let local = Self::new([
+            Plane::new(-min[0], &[-1., 0., 0.]),
+            Plane::new(max[0], &[1., 0., 0.]),
+            Plane::new(-min[1], &[0., -1., 0.]),
+            Plane::new(max[1], &[0., 1., 0.]),
+            Plane::new(-min[2], &[0., 0., -1.]),
+            Plane::new(max[2], &[0., 0., 1.]),
+        ]);
+        local.apply(pose)
+    }
+
+    pub fn contains(&self, p: &Point) -> bool {
+        self.planes.iter().all(|plane| plane.distance(p) <= 0.0)
+    }
+
+    pub fn point(&self, p: &Point) -> Containment {
+        self.classify(&na::Vec3::from(p.normalize().eucl()), |_| 0.0)
+    }
+
+    pub fn sphere(&self, s: &Sphere) -> Containment {
+        self.classify(&na::Vec3::from(s.center.normalize().eucl()), |_| s.radius)
+    }
+
+    pub fn aabb(&self, b: &Aabb) -> Containment {
+        let h = na::Vec3::from(b.half());
+        self.classify(&na::Vec3::from(b.center()), |n| n.abs().dot(&h))
+    }
+
+    pub fn obb(&self, b: &Obb) -> Containment {
+        let r = b.rotation();
+        let h = na::Vec3::from(b.half);
+        self.classify(&na::Vec3::from(b.center.normalize().eucl()), |n| {
+            (r.transpose() * n).abs().dot(&h)
+        })
+    }
+
+    /// The usual test of the center against every plane, pushed out by the
+    /// extent of the volume along the normal. Conservative, a volume near
+    /// an edge of the frustum may be Intersecting while it is outside.
+    fn classify(&self, c: &na::Vec3, extent: impl Fn(&na::Vec3) -> f32) -> Containment {
+        let mut result = Containment::Inside;
+        for plane in self.planes.iter() {
+            let v = plane.vector;
+            let n = na::Vec3::new(v[1], v[2], v[3]);
+            let d = n.dot(c) - v[0];
+            let r = extent(&n);
+            if d > r {
+                return Containment::Outside;
+            }
+            if d > -r {
+                result = Containment::Intersecting;
+            }
+        }
+        result
+    }
+}
+
+impl Applicable for Frustum {
+    fn apply(&self, m: &Motor) -> Self {
+        Self {
+            planes: [0, 1, 2, 3, 4, 5].map(|i| m.apply_to_plane(&self.planes[i])),
+        }
+    }
+}
+
+impl From<&Frustum> for ConvexPolyhedron {
+    fn from(f: &Frustum) -> Self {
+        Self::new(f.planes.iter().map(|p| p.normalize()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bounds::*;
+    use crate::frustum::*;
+    use crate::*;
+
+    fn pose() -> Motor {
+        let r = Rotor::new(0.7, &na::Vec3::new(1., 2., -0.5).normalize().into());
+        Translator::new(&[3., -1., 2.]).mul_rotor(&r)
+    }
+
+    #[test]
+    fn perspective() {
+        let f = Frustum::perspective(&Motor::one(), std::f32::consts::FRAC_PI_2, 2., 1., 10.);
+        assert!(f.contains(&Point::new(&[0., 0., 5.])));
+        assert!(f.contains(&Point::new(&[9.9, 4.9, 5.])));
+        assert!(!f.contains(&Point::new(&[0., 5.1, 5.])));
+        assert!(!f.contains(&Point::new(&[0., 0., 0.5])));
+        assert!(!f.contains(&Point::new(&[0., 0., -5.])));
+        assert!(!f.contains(&Point::new(&[0., 0., 11.])));
+        let corners = ConvexPolyhedron::from(&f).vertices();
+        assert_eq!(corners.len(), 8);
+        assert!(corners.iter().any(|p| *p == Point::new(&[20., 10., 10.])));
+    }
+
+    #[test]
+    fn volumes() {
+        let m = pose();
+        let f = Frustum::perspective(&m, 1., 1.5, 0.5, 20.);
+        let local = |x: &[f32; 3]| m.apply_to_point(&Point::new(x));
+        let sphere = |x: &[f32; 3], r| Sphere {
+            center: local(x),
+            radius: r,
+        };
+        assert_eq!(f.point(&local(&[0., 0., 5.])), Containment::Inside);
+        assert_eq!(f.point(&local(&[0., 0., -5.])), Containment::Outside);
+        assert_eq!(f.sphere(&sphere(&[0., 0., 5.], 1.)), Containment::Inside);
+        assert_eq!(
+            f.sphere(&sphere(&[0., 0., 19.5], 1.)),
+            Containment::Intersecting
+        );
+        assert_eq!(f.sphere(&sphere(&[0., 0., -2.], 1.)), Containment::Outside);
+
+        let b = |min: &[f32; 3], max: &[f32; 3]| Obb::from(&Aabb::new(min, max)).apply(&m);
+        assert_eq!(
+            f.obb(&b(&[-1., -1., 4.], &[1., 1., 6.])),
+            Containment::Inside
+        );
+        assert_eq!(
+            f.obb(&b(&[-1., -1., 0.], &[1., 1., 2.])),
+            Containment::Intersecting
+        );
+        assert_eq!(
+            f.obb(&b(&[-1., -1., -3.], &[1., 1., -1.])),
+            Containment::Outside
+        );
+
+        let world = Frustum::perspective(&Motor::one(), 1., 1.5, 0.5, 20.);
+        assert_eq!(
+            world.aabb(&Aabb::new(&[-1., -1., 4.], &[1., 1., 6.])),
+            Containment::Inside
+        );
+        assert_eq!(
+            world.aabb(&Aabb::new(&[-1., -1., 19.], &[1., 1., 21.])),
+            Containment::Intersecting
+        );
+        assert_eq!(
+            world.aabb(&Aabb::new(&[30., -1., 4.], &[31., 1., 6.])),
+            Containment::Outside
+        );
+    }
+
+    #[test]
+    fn apply() {
+        let m = pose();
+        let f = Frustum::orthographic(&Motor::one(), &[-2., -1., 0.], &[2., 1., 5.]);
+        let moved = f.apply(&m);
+        let direct = Frustum::orthographic(&m, &[-2., -1., 0.], &[2., 1., 5.]);
+        for (a, b) in moved.planes.iter().zip(direct.planes.iter()) {
+            assert_eq!(a, b);
+        }
+        for x in [[1.9, 0.9, 4.9], [-1.9, -0.9, 0.1], [0., 0., 2.5]].iter() {
+            assert!(moved.contains(&m.apply_to_point(&Point::new(x))));
+            assert!(!f.contains(&m.apply_to_point(&Point::new(x))));
+        }
+        assert!(!moved.contains(&m.apply_to_point(&Point::new(&[2.1, 0., 2.]))));
+    }
+}
@@ -0,0 +1,206 @@
+//! Mirrors of the geometric types that can be uploaded to GPU buffers,
+//! behind the bytemuck feature. They follow the blocks-of-four layout of
+//! glsl/ppga.glsl:
+//!     p0 -> (e0, e1, e2, e3)
+//!     p1 -> (1, e23, e31, e12)
+//!     p2 -> (e0123, e01, e02, e03)
+//!     p3 -> (e123, e032, e013, e021)
+//! Every block is a vec4 and every type is 16 byte aligned without padding,
+//! so the layout is the same under std140 and std430, also in arrays.
+
+use super::{Motor, Plane, Point, Rotor};
+use bytemuck::{Pod, Zeroable};
+
+/// struct ppga_motor { vec4 p1; vec4 p2; }
+#[repr(C, align(16))]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Pod, Zeroable)]
+pub struct GpuMotor {
+    pub p1: [f32; 4],
+    pub p2: [f32; 4],
+}
+
+/// struct ppga_rotor { vec4 p1; }
+#[repr(C, align(16))]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Pod, Zeroable)]
+pub struct GpuRotor {
+    pub p1: [f32; 4],
+}
+
+#[repr(C, align(16))]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Pod, Zeroable)]
+pub struct GpuPoint {
+    pub p3: [f32; 4],
+}
+
+#[repr(C, align(16))]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Pod, Zeroable)]
+pub struct GpuPlane {
+    pub p0: [f32; 4],
+}
+
+impl From<&Motor> for GpuMotor {
+    fn from(m: &Motor) -> Self {
+        let [p1, p2] = m.into_klein();
+        Self { p1, p2 }
+    }
+}
+impl From<&GpuMotor> for Motor {
+    fn from(m: &GpuMotor) -> Self {
+        Self {
+            scalar: m.p1[0],
+            e_bivector: [m.p1[1], m.p1[2], m.p1[3]],
+            pseudo: m.p2[0],
+            v_bivector: [m.p2[1], m.p2[2], m.p2[3]],
+        }
+    }
+}
+
+impl From<&Rotor> for GpuRotor {
+    fn from(r: &Rotor) -> Self {
+        let e = r.e_bivector;
+        Self {
+            p1: [r.scalar, e[0], e[1], e[2]],
+        }
+    }
+}
+impl From<&GpuRotor> for Rotor {
+    fn from(r: &GpuRotor) -> Self {
+        Self {
+            scalar: r.p1[0],
+            e_bivector: [r.p1[1], r.p1[2], r.p1[3]],
+        }
+    }
+}
+
+impl From<&Point> for GpuPoint {
+    fn from(p: &Point) -> Self {
+        Self { p3: p.trivector }
+    }
+}
+impl From<&GpuPoint> for Point {
+    fn from(p: &GpuPoint) -> Self {
+        Self { trivector: p.p3 }
+    }
+}
+
+impl From<&Plane> for GpuPlane {
+    fn from(p: &Plane) -> Self {
+        Self { p0: p.vector }
+    }
+}
+impl From<&GpuPlane> for Plane {
+    fn from(p: &GpuPlane) -> Self {
+        Self { vector: p.p0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gpu::*;
+    use std::mem::{align_of, size_of};
+
+    /// The blocks of the layout comment in ppga.glsl, e.g.,
+    /// "p1" -> ["1", "e23", "e31", "e12"].
+    fn glsl_blocks() -> Vec<(String, Vec<String>)> {
+        include_str!("../glsl/ppga.glsl")
+            .lines()
+            .filter_map(|l| {
+                let l = l.strip_prefix("// p")?;
+                let (block, basis) = l.split_once(" -> ")?;
+                let basis = basis.trim().strip_prefix('(')?.strip_suffix(')')?;
+                let basis = basis.split(", ").map(String::from).collect();
+                Some((format!("p{}", block), basis))
+            })
+            .collect()
+    }
+
+    /// Name of the basis element stored at every f32 of the value, found by
+    /// putting a marker in one CPU component at a time.
+    fn layout<G: Pod>(names: &[&str], marked: impl Fn(usize) -> G) -> Vec<String> {
+        let mut found = vec![String::new(); size_of::<G>() / 4];
+        for (i, name) in names.iter().enumerate() {
+            let g = marked(i);
+            let floats: &[f32] = bytemuck::cast_slice(bytemuck::bytes_of(&g));
+            let at = floats.iter().position(|x| *x == 1.).unwrap();
+            found[at] = name.to_string();
+        }
+        found
+    }
+
+    fn unit<const N: usize>(i: usize) -> [f32; N] {
+        let mut a = [0.; N];
+        a[i] = 1.;
+        a
+    }
+
+    #[test]
+    fn glsl_layout() {
+        let blocks = glsl_blocks();
+        let block = |name: &str| -> Vec<String> {
+            blocks.iter().find(|(b, _)| b == name).unwrap().1.clone()
+        };
+        assert_eq!(blocks.len(), 4);
+
+        let motor = layout(
+            &["1", "e23", "e31", "e12", "e0123", "e01", "e02", "e03"],
+            |i| {
+                let c: [f32; 8] = unit(i);
+                GpuMotor::from(&Motor {
+                    scalar: c[0],
+                    e_bivector: [c[1], c[2], c[3]],
+                    pseudo: c[4],
+                    v_bivector: [c[5], c[6], c[7]],
+                })
+            },
+        );
+        assert_eq!(motor, [block("p1"), block("p2")].concat());
+
+        let rotor = layout(&["1", "e23", "e31", "e12"], |i| {
+            let c: [f32; 4] = unit(i);
+            GpuRotor::from(&Rotor {
+                scalar: c[0],
+                e_bivector: [c[1], c[2], c[3]],
+            })
+        });
+        assert_eq!(rotor, block("p1"));
+
+        let point = layout(&crate::trivector, |i| {
+            GpuPoint::from(&Point { trivector: unit(i) })
+        });
+        assert_eq!(point, block("p3"));
+
+        let plane = layout(&crate::vector, |i| {
+            GpuPlane::from(&Plane { vector: unit(i) })
+        });
+        assert_eq!(plane, block("p0"));
+
+        // std140 and std430 agree: vec4 blocks, 16 byte aligned, no padding.
+        assert_eq!((size_of::<GpuMotor>(), align_of::<GpuMotor>()), (32, 16));
+        assert_eq!((size_of::<GpuRotor>(), align_of::<GpuRotor>()), (16, 16));
+        assert_eq!((size_of::<GpuPoint>(), align_of::<GpuPoint>()), (16, 16));
+        assert_eq!((size_of::<GpuPlane>(), align_of::<GpuPlane>()), (16, 16));
+        assert_eq!(size_of::<[GpuMotor; 3]>(), 96);
+    }
+
+    #[test]
+    fn round_trip() {
+        let m = Motor::random();
+        assert_eq!(
+            Motor::from(&GpuMotor::from(&m)).into_klein(),
+            m.into_klein()
+        );
+        let motors = vec![GpuMotor::from(&m); 4];
+        let bytes: &[u8] = bytemuck::cast_slice(&motors);
+        assert_eq!(bytes.len(), 128);
+        let back: &[GpuMotor] = bytemuck::cast_slice(bytes);
+        assert_eq!(back, &motors[..]);
+
+        let p = Point::random();
+        assert_eq!(Point::from(&GpuPoint::from(&p)).trivector, p.trivector);
+        let p = Plane::random();
+        assert_eq!(Plane::from(&GpuPlane::from(&p)).vector, p.vector);
+        let r = Rotor::new(0.5, &[0., 0., 1.]);
+        let r_ = Rotor::from(&GpuRotor::from(&r));
+        assert_eq!((r_.scalar, r_.e_bivector), (r.scalar, r.e_bivector));
+    }
+}
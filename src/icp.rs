@@ -0,0 +1,318 @@
+//! Iterative closest point registration of point clouds.
+//!
+//! Every iteration moves the source cloud with the current motor, pairs each
+//! moved point with its nearest target point (k-d tree), drops pairs that are
+//! too far apart and estimates an incremental motor from the rest:
+//!     point-to-point: the least squares fit of Motor::fit_points,
+//!     point-to-plane: one Gauss-Newton step using Motor::jacobian_point.
+//! The incremental motor is composed in front of the current one.
+
+use super::kdtree::KdTree;
+use super::{Line, Motor, Plane, Point};
+
+#[derive(Debug, Copy, Clone)]
+pub struct Settings {
+    pub max_iterations: usize,
+    /// Correspondences further apart than this are rejected as outliers.
+    pub max_distance: f32,
+    /// Stop when the error changes less than this between iterations.
+    pub tolerance: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            max_iterations: 50,
+            max_distance: f32::INFINITY,
+            tolerance: 1e-6,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Registration {
+    /// Moves the source onto the target.
+    pub motor: Motor,
+    /// Root mean square error of the accepted correspondences per iteration.
+    pub errors: Vec<f32>,
+    /// Number of accepted correspondences per iteration.
+    pub inliers: Vec<usize>,
+    pub converged: bool,
+}
+
+/// Registers source onto target, starting from initial. If normals are given
+/// (one plane through every target point), the point-to-plane error is
+/// minimized, otherwise the point-to-point error. Normals should be normalized.
+/// Points at infinity, in the source or the target, are skipped.
+pub fn register(
+    source: &[Point],
+    target: &[Point],
+    normals: Option<&[Plane]>,
+    initial: &Motor,
+    settings: &Settings,
+) -> Registration {
+    // The tree leaves out the target points at infinity.
+    let tree = KdTree::from_points(target);
+    let target: Vec<Point> = target.iter().map(|p| p.normalize()).collect();
+    let mut m = *initial;
+    let mut errors = Vec::new();
+    let mut inliers = Vec::new();
+    let mut converged = false;
+
+    for _ in 0..settings.max_iterations {
+        let (from, to) = correspondences(&tree, source, &m, settings.max_distance);
+        if from.len() < 3 {
+            break;
+        }
+
+        let (error, dm) = match normals {
+            None => {
+                let from_pts: Vec<Point> = from.iter().map(|(p, _)| p.normalize()).collect();
+                let to_pts: Vec<Point> = to.iter().map(|i| target[*i].normalize()).collect();
+                let error = rms(from_pts
+                    .iter()
+                    .zip(to_pts.iter())
+                    .map(|(a, b)| distance(&a.eucl(), &b.eucl())));
+                (error, Motor::fit_points(&from_pts, &to_pts))
+            }
+            Some(normals) => {
+                let planes: Vec<&Plane> = to.iter().map(|i| &normals[*i]).collect();
+                point_to_plane_step(&from, &planes)
+            }
+        };
+        errors.push(error);
+        inliers.push(from.len());
+
+        m = dm.mul(&m).rigid_normalize();
+
+        let n = errors.len();
+        if n > 1 && (errors[n - 2] - errors[n - 1]).abs() < settings.tolerance {
+            converged = true;
+            break;
+        }
+    }
+
+    Registration {
+        motor: m,
+        errors,
+        inliers,
+        converged,
+    }
+}
+
+/// Moved source points (with their source index) paired with target indices.
+/// With the rayon feature the nearest neighbours are searched in parallel.
+fn correspondences(
+    tree: &KdTree,
+    source: &[Point],
+    m: &Motor,
+    max_distance: f32,
+) -> (Vec<(Point, usize)>, Vec<usize>) {
+    let pair = |p: &Point| {
+        if p.trivector[0] == 0.0 {
+            return None;
+        }
+        let moved = m.apply_to_point(p).normalize();
+        match tree.nearest(&moved.eucl()) {
+            Some((j, d)) if d <= max_distance => Some((moved, j)),
+            _ => None,
+        }
+    };
+    #[cfg(feature = "rayon")]
+    let pairs: Vec<Option<(Point, usize)>> = {
+        use rayon::prelude::*;
+        source.par_iter().with_min_len(256).map(pair).collect()
+    };
+    #[cfg(not(feature = "rayon"))]
+    let pairs: Vec<Option<(Point, usize)>> = source.iter().map(pair).collect();
+
+    let mut from = Vec::new();
+    let mut to = Vec::new();
+    for (i, pair) in pairs.into_iter().enumerate() {
+        if let Some((moved, j)) = pair {
+            from.push((moved, i));
+            to.push(j);
+        }
+    }
+    (from, to)
+}
+
+/// Linearizes r = n . x - d around the identity and solves the 6x6 normal
+/// equations. Returns the error before the step and the incremental motor.
+fn point_to_plane_step(from: &[(Point, usize)], planes: &[&Plane]) -> (f32, Motor) {
+    let one = Motor::one();
+    let mut h = na::Matrix6::<f32>::zeros();
+    let mut g = na::Vector6::<f32>::zeros();
+    let mut residuals = Vec::with_capacity(from.len());
+    for ((p, _), plane) in from.iter().zip(planes.iter()) {
+        let x = p.eucl();
+        let v = plane.vector;
+        let r = v[1] * x[0] + v[2] * x[1] + v[3] * x[2] - v[0];
+        // p is normalized, so the derivative of the euclidean part is
+        // minus the derivative of the trivector.
+        let dp = one.jacobian_point(p);
+        let j = na::Vector6::from_fn(|k, _| {
+            let d = dp[k].trivector;
+            -(v[1] * d[1] + v[2] * d[2] + v[3] * d[3])
+        });
+        h += j * j.transpose();
+        g += j * r;
+        residuals.push(r.abs());
+    }
+    let error = rms(residuals.into_iter());
+    let d = match (h + na::Matrix6::identity() * 1e-6).cholesky() {
+        Some(c) => c.solve(&-g),
+        None => return (error, one),
+    };
+    let t = [d[0], d[1], d[2], d[3], d[4], d[5]];
+    (error, Line::from_tangent(&t).exp())
+}
+
+fn distance(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    na::distance(&na::Point3::from(*a), &na::Point3::from(*b))
+}
+
+fn rms<I: Iterator<Item = f32>>(x: I) -> f32 {
+    let (sum, n) = x.fold((0.0, 0), |(s, n), x| (s + x * x, n + 1));
+    (sum / n.max(1) as f32).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::icp::*;
+    use crate::*;
+
+    /// Points on the faces of a box, with their face planes.
+    fn scan() -> (Vec<Point>, Vec<Plane>) {
+        let mut points = Vec::new();
+        let mut planes = Vec::new();
+        let size = [2.0, 1.5, 1.0];
+        for axis in 0..3 {
+            for side in [-1.0f32, 1.0].iter() {
+                let mut n = [0.0; 3];
+                n[axis] = *side;
+                for u in 0..8 {
+                    for v in 0..8 {
+                        let mut x = [0.0; 3];
+                        x[axis] = side * size[axis];
+                        x[(axis + 1) % 3] = size[(axis + 1) % 3] * (u as f32 / 3.5 - 1.);
+                        x[(axis + 2) % 3] = size[(axis + 2) % 3] * (v as f32 / 3.5 - 1.);
+                        points.push(Point::new(&x));
+                        planes.push(Plane::new(size[axis], &n));
+                    }
+                }
+            }
+        }
+        (points, planes)
+    }
+
+    fn truth() -> Motor {
+        let r = Rotor::new(0.15, &na::Vec3::new(0.3, 1., 0.2).normalize().into());
+        Translator::new(&[0.1, -0.15, 0.05]).mul_rotor(&r)
+    }
+
+    #[test]
+    fn point_to_point() {
+        let (target, _) = scan();
+        let m = truth();
+        let source: Vec<Point> = target
+            .iter()
+            .map(|p| m.reverse().apply_to_point(p))
+            .collect();
+        let reg = register(&source, &target, None, &Motor::one(), &Settings::default());
+        assert!(reg.errors.last().unwrap() < &0.01);
+        assert!(reg.errors.first().unwrap() > reg.errors.last().unwrap());
+        let p = Point::new(&[1., 2., 3.]);
+        assert_eq!(reg.motor.apply_to_point(&p), m.apply_to_point(&p));
+    }
+
+    #[test]
+    fn point_to_plane() {
+        let (target, planes) = scan();
+        let m = truth();
+        let source: Vec<Point> = target
+            .iter()
+            .map(|p| m.reverse().apply_to_point(p))
+            .collect();
+        let reg = register(
+            &source,
+            &target,
+            Some(&planes),
+            &Motor::one(),
+            &Settings::default(),
+        );
+        assert!(reg.errors.last().unwrap() < &0.01);
+        let p = Point::new(&[1., 2., 3.]);
+        assert_eq!(reg.motor.apply_to_point(&p), m.apply_to_point(&p));
+    }
+
+    #[test]
+    fn screw_step() {
+        // From exact pairs, one step recovers a small screw, with its
+        // translation along the axis, up to second order.
+        let (target, planes) = scan();
+        let axis = Line::new(&[0.5, -0.3, 0.2], &[0., 0.6, 0.8]);
+        let along = Line {
+            e_bivector: [0.; 3],
+            v_bivector: [0., 0.6, 0.8],
+        };
+        let m = axis.add(&along).mul_scalar(0.02).exp();
+        let from: Vec<(Point, usize)> = target
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (m.reverse().apply_to_point(p).normalize(), i))
+            .collect();
+        let planes: Vec<&Plane> = planes.iter().collect();
+        let (error, dm) = point_to_plane_step(&from, &planes);
+        let after = rms(from
+            .iter()
+            .zip(planes.iter())
+            .map(|((p, _), plane)| plane.distance(&dm.apply_to_point(p).normalize())));
+        assert!(after < 0.05 * error, "{} {}", after, error);
+    }
+
+    #[test]
+    fn outliers() {
+        let (target, _) = scan();
+        let m = truth();
+        let mut source: Vec<Point> = target
+            .iter()
+            .map(|p| m.reverse().apply_to_point(p))
+            .collect();
+        source.push(Point::new(&[10., 10., 10.]));
+        source.push(Point::new(&[-8., 9., 0.]));
+        let settings = Settings {
+            max_distance: 1.0,
+            ..Settings::default()
+        };
+        let reg = register(&source, &target, None, &Motor::one(), &settings);
+        assert_eq!(*reg.inliers.last().unwrap(), target.len());
+        let p = Point::new(&[1., 2., 3.]);
+        assert_eq!(reg.motor.apply_to_point(&p), m.apply_to_point(&p));
+    }
+
+    #[test]
+    fn at_infinity() {
+        let (mut target, mut planes) = scan();
+        let m = truth();
+        let mut source: Vec<Point> = target
+            .iter()
+            .map(|p| m.reverse().apply_to_point(p))
+            .collect();
+        source.push(Point::inf(&[1., 0., 0.]));
+        target.insert(0, Point::inf(&[0., 1., 0.]));
+        planes.insert(0, Plane::new(0., &[0., 1., 0.]));
+        for normals in [None, Some(&planes[..])].iter() {
+            let reg = register(
+                &source,
+                &target,
+                *normals,
+                &Motor::one(),
+                &Settings::default(),
+            );
+            assert_eq!(*reg.inliers.last().unwrap(), source.len() - 1);
+            let p = Point::new(&[1., 2., 3.]);
+            assert_eq!(reg.motor.apply_to_point(&p), m.apply_to_point(&p));
+        }
+    }
+}
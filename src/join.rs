@@ -1,32 +1,9 @@
 pub fn points(p1: &super::Point, p2: &super::Point) -> super::Line {
-    let t1 = p1.trivector;
-    let t2 = p2.trivector;
-    super::Line {
-        v_bivector: [
-            -t1[2] * t2[3] + t1[3] * t2[2],
-            t1[1] * t2[3] - t1[3] * t2[1],
-            -t1[1] * t2[2] + t1[2] * t2[1],
-        ],
-        e_bivector: [
-            -t1[0] * t2[1] + t1[1] * t2[0],
-            -t1[0] * t2[2] + t1[2] * t2[0],
-            -t1[0] * t2[3] + t1[3] * t2[0],
-        ],
-    }
+    super::products::regressive_point_point(p1, p2)
 }
 
 pub fn line_to_point(l: &super::Line, p: &super::Point) -> super::Plane {
-    let t1 = p.trivector;
-    let vb = l.v_bivector;
-    let eb = l.e_bivector;
-    super::Plane {
-        vector: [
-            -t1[1] * vb[0] - t1[2] * vb[1] - t1[3] * vb[2],
-            eb[1] * t1[3] - eb[2] * t1[2] + t1[0] * vb[0],
-            -eb[0] * t1[3] + eb[2] * t1[1] + t1[0] * vb[1],
-            eb[0] * t1[2] - eb[1] * t1[1] + t1[0] * vb[2],
-        ],
-    }
+    super::products::regressive_line_point(l, p)
 }
 
 pub fn three_points(p1: &super::Point, p2: &super::Point, p3: &super::Point) -> super::Plane {
@@ -0,0 +1,170 @@
+//! A static 3D k-d tree for nearest neighbour queries, e.g., to find
+//! correspondences between point clouds.
+
+#[derive(Debug, Clone)]
+pub struct KdTree {
+    points: Vec<[f32; 3]>,
+    // Permutation of the point indices. The median of every range is
+    // the splitting node, split on axis depth % 3.
+    order: Vec<usize>,
+}
+
+impl KdTree {
+    /// Points that are not finite, e.g., points at infinity, are left out.
+    /// The others keep their index.
+    pub fn new(points: &[[f32; 3]]) -> Self {
+        let mut order: Vec<usize> = (0..points.len())
+            .filter(|i| points[*i].iter().all(|x| x.is_finite()))
+            .collect();
+        build(points, &mut order, 0);
+        Self {
+            points: points.to_vec(),
+            order,
+        }
+    }
+
+    /// Builds the tree out of the euclidean part of the points.
+    pub fn from_points(points: &[super::Point]) -> Self {
+        let eucl: Vec<[f32; 3]> = points.iter().map(|p| p.normalize().eucl()).collect();
+        Self::new(&eucl)
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Index of the closest point and its distance to q, None if the tree
+    /// is empty or q is not finite.
+    pub fn nearest(&self, q: &[f32; 3]) -> Option<(usize, f32)> {
+        let mut best = (usize::MAX, f32::INFINITY);
+        self.search(q, 0, self.order.len(), 0, &mut best);
+        if best.0 == usize::MAX {
+            return None;
+        }
+        Some((best.0, best.1.sqrt()))
+    }
+
+    /// Indices of all points within radius of q, in no particular order.
+    pub fn within(&self, q: &[f32; 3], radius: f32) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.collect(q, radius * radius, 0, self.order.len(), 0, &mut found);
+        found
+    }
+
+    fn search(&self, q: &[f32; 3], lo: usize, hi: usize, depth: usize, best: &mut (usize, f32)) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        let i = self.order[mid];
+        let p = self.points[i];
+        let d = distance_squared(&p, q);
+        if d < best.1 {
+            *best = (i, d);
+        }
+        let axis = depth % 3;
+        let diff = q[axis] - p[axis];
+        let (near, far) = if diff < 0.0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.search(q, near.0, near.1, depth + 1, best);
+        if diff * diff < best.1 {
+            self.search(q, far.0, far.1, depth + 1, best);
+        }
+    }
+
+    fn collect(
+        &self,
+        q: &[f32; 3],
+        r2: f32,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        found: &mut Vec<usize>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        let i = self.order[mid];
+        let p = self.points[i];
+        if distance_squared(&p, q) <= r2 {
+            found.push(i);
+        }
+        let axis = depth % 3;
+        let diff = q[axis] - p[axis];
+        if diff < 0.0 || diff * diff <= r2 {
+            self.collect(q, r2, lo, mid, depth + 1, found);
+        }
+        if diff >= 0.0 || diff * diff <= r2 {
+            self.collect(q, r2, mid + 1, hi, depth + 1, found);
+        }
+    }
+}
+
+fn build(points: &[[f32; 3]], order: &mut [usize], depth: usize) {
+    if order.len() <= 1 {
+        return;
+    }
+    let axis = depth % 3;
+    let mid = order.len() / 2;
+    order.select_nth_unstable_by(mid, |a, b| points[*a][axis].total_cmp(&points[*b][axis]));
+    let (left, right) = order.split_at_mut(mid);
+    build(points, left, depth + 1);
+    build(points, &mut right[1..], depth + 1);
+}
+
+fn distance_squared(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    let d = [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+    d[0] * d[0] + d[1] * d[1] + d[2] * d[2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest() {
+        let points: Vec<[f32; 3]> = (0..500).map(|_| na::Vec3::new_random().into()).collect();
+        let tree = KdTree::new(&points);
+        for _ in 0..50 {
+            let q: [f32; 3] = na::Vec3::new_random().into();
+            let brute = points
+                .iter()
+                .map(|p| distance_squared(p, &q))
+                .enumerate()
+                .fold((0, f32::INFINITY), |a, b| if b.1 < a.1 { b } else { a });
+            let (i, d) = tree.nearest(&q).unwrap();
+            assert_eq!(i, brute.0);
+            assert!((d - brute.1.sqrt()).abs() < 1e-6);
+
+            let mut found = tree.within(&q, 0.2);
+            found.sort_unstable();
+            let brute: Vec<usize> = (0..points.len())
+                .filter(|i| distance_squared(&points[*i], &q) <= 0.04)
+                .collect();
+            assert_eq!(found, brute);
+        }
+    }
+
+    #[test]
+    fn not_finite() {
+        let points = [
+            [0., 0., 0.],
+            [f32::NAN, 0., 0.],
+            [1., f32::INFINITY, 0.],
+            [1., 1., 1.],
+        ];
+        let tree = KdTree::new(&points);
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.nearest(&[0.9, 0.9, 0.9]).unwrap().0, 3);
+        assert!(tree.nearest(&[f32::NAN, 0., 0.]).is_none());
+        assert!(KdTree::new(&[]).nearest(&[0., 0., 0.]).is_none());
+    }
+}
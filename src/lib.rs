@@ -5,9 +5,12 @@
 //! Some functions use the variant proposed by Gunn (2018)
 //! (https://bivector.net/PROJECTIVE_GEOMETRIC_ALGEBRA.pdf)
 //!
-//! Most calculations are generated using the python version of Steven de Keninck's
-//! ganja.js (https://github.com/enkimute/ganja.js/) and sympy. See the
-//! generate folder for details.
+//! The products of the types with each other are generated at build time
+//! by build.rs from the Cayley table, see src/codegen.rs. Some of the other
+//! formulas were derived using the python version of Steven de Keninck's
+//! ganja.js (https://github.com/enkimute/ganja.js/) and sympy. The scripts
+//! in the generate folder are kept for reference only, the build does not
+//! use them.
 //!
 //! Memory model:
 //!     scalar = 1
@@ -19,19 +22,53 @@
 
 mod line;
 mod motor;
+mod multivector;
 mod plane;
 mod point;
 mod rotor;
 mod translator;
 
 mod error;
+// The build script includes it too and uses the parts the crate does not.
+#[allow(dead_code)]
+mod codegen;
 
+pub mod batch;
+pub mod bounds;
+pub mod bvh;
+pub mod camera;
+pub mod clip;
+pub mod d2;
+pub mod frustum;
+#[cfg(feature = "bytemuck")]
+pub mod gpu;
+pub mod icp;
 pub mod inner;
 pub mod join;
+pub mod kdtree;
 pub mod meet;
+#[cfg(feature = "rayon")]
+pub mod par;
+pub mod pnp;
+pub mod polyhedron;
+pub mod pose_graph;
+pub mod products;
+pub mod ray;
+#[cfg(feature = "serde")]
+pub mod serialize;
+pub mod shader;
+pub mod simd;
+pub mod skeleton;
+pub mod skinning;
+pub mod soa;
+pub mod sparse;
+pub mod triangulate;
+pub mod urdf;
 
+pub use error::ParseError;
 pub use line::Line;
-pub use motor::Motor;
+pub use motor::{Alignable, Applicable, Motor};
+pub use multivector::{Element, Multivector};
 pub use plane::Plane;
 pub use point::Point;
 pub use rotor::Rotor;
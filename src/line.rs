@@ -17,6 +17,23 @@ impl Line {
         }
     }
 
+    /// Builds a line out of the six coordinates
+    /// [e23, e31, e12, e01, e02, e03], which is how
+    /// motor perturbations are parameterized (see Motor::jacobian_point).
+    pub fn from_tangent(t: &[f32; 6]) -> Self {
+        Self {
+            e_bivector: [t[0], t[1], t[2]],
+            v_bivector: [t[3], t[4], t[5]],
+        }
+    }
+
+    /// Inverse of Line::from_tangent.
+    pub fn tangent(&self) -> [f32; 6] {
+        let e = self.e_bivector;
+        let v = self.v_bivector;
+        [e[0], e[1], e[2], v[0], v[1], v[2]]
+    }
+
     pub fn random() -> Self {
         Self {
             v_bivector: na::Vec3::new_random().into(),
@@ -159,30 +176,13 @@ impl Line {
         .normalize()
     }
 
-    /// SIGGRAPH Course Notes 8.1.3 & 8.1.4.
-    /// Implementation taken from ganja.js. More stable.
+    /// SIGGRAPH Course Notes 8.1.3 & 8.1.4. With u = |e| and a = sin(u)/u,
+    ///     exp(e + v) = cos(u) + a e + a v + b (e.v) e + a (e.v) e0123
+    /// for b = (cos(u) - a)/u^2. The b term is the part of v along e, the
+    /// translation along the axis of a screw.
+    #[inline]
     pub fn exp(&self) -> super::Motor {
-        let bdb = -super::inner::lines(&self, &self);
-        let u = bdb.sqrt();
-        if u < 0.001 {
-            return super::Motor::from(self).add_scalar(1.);
-        }
-        let v = super::meet::lines(&self, &self).div_scalar(-2. * u);
-        let cu = u.cos();
-        let su = u.sin();
-        let be = self.e_bivector;
-        let bv = self.v_bivector;
-        let u2 = u * u;
-        super::Motor {
-            scalar: cu,
-            pseudo: -su * v.0,
-            v_bivector: [
-                -be[0] * cu * v.0 / u - be[0] * su * v.0 / u2 + bv[0] * su / u,
-                -be[1] * cu * v.0 / u - be[1] * su * v.0 / u2 + bv[1] * su / u,
-                -be[2] * cu * v.0 / u - be[2] * su * v.0 / u2 + bv[2] * su / u,
-            ],
-            e_bivector: [be[0] * su / u, be[1] * su / u, be[2] * su / u],
-        }
+        exp(&self.e_bivector, &self.v_bivector)
     }
 
     /// PGA4CS chapter 5.6
@@ -220,29 +220,7 @@ impl Line {
     }
 
     pub fn mul(&self, other: &Self) -> super::Motor {
-        let e1 = self.e_bivector;
-        let v1 = self.v_bivector;
-        let e2 = other.e_bivector;
-        let v2 = other.v_bivector;
-        super::Motor {
-            scalar: -e1[0] * e2[0] - e1[1] * e2[1] - e1[2] * e2[2],
-            v_bivector: [
-                -e1[1] * v2[2] + e1[2] * v2[1] + e2[1] * v1[2] - e2[2] * v1[1],
-                e1[0] * v2[2] - e1[2] * v2[0] - e2[0] * v1[2] + e2[2] * v1[0],
-                -e1[0] * v2[1] + e1[1] * v2[0] + e2[0] * v1[1] - e2[1] * v1[0],
-            ],
-            e_bivector: [
-                -e1[1] * e2[2] + e1[2] * e2[1],
-                e1[0] * e2[2] - e1[2] * e2[0],
-                -e1[0] * e2[1] + e1[1] * e2[0],
-            ],
-            pseudo: e1[0] * v2[0]
-                + e1[1] * v2[1]
-                + e1[2] * v2[2]
-                + e2[0] * v1[0]
-                + e2[1] * v1[1]
-                + e2[2] * v1[2],
-        }
+        super::products::geometric_line_line(self, other)
     }
 
     pub fn div(&self, other: &Self) -> super::Motor {
@@ -302,6 +280,32 @@ impl PartialEq for Line {
     }
 }
 
+/// Line::exp of the line with bivectors be and bv. Both the series and the
+/// closed form are computed and one is selected, without a branch, so that
+/// soa can run it on many lines at once.
+#[inline(always)]
+pub(crate) fn exp(be: &[f32; 3], bv: &[f32; 3]) -> super::Motor {
+    let u2 = be[0] * be[0] + be[1] * be[1] + be[2] * be[2];
+    let u = u2.sqrt();
+    let ev = be[0] * bv[0] + be[1] * bv[1] + be[2] * bv[2];
+    // Series around 0, exact to f32 precision below 1e-2.
+    let series = u < 0.01;
+    let (sin, cos) = u.sin_cos();
+    let cu = if series { 1. - 0.5 * u2 } else { cos };
+    let a = if series { 1. - u2 / 6. } else { sin / u };
+    let b = if series { -1. / 3. } else { (cos - a) / u2 };
+    super::Motor {
+        scalar: cu,
+        pseudo: a * ev,
+        v_bivector: [
+            a * bv[0] + b * ev * be[0],
+            a * bv[1] + b * ev * be[1],
+            a * bv[2] + b * ev * be[2],
+        ],
+        e_bivector: [a * be[0], a * be[1], a * be[2]],
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::*;
@@ -6,18 +6,7 @@
 ///           + e31(dy - bw)
 ///           + e12(bz - cy)
 pub fn planes(p1: &super::Plane, p2: &super::Plane) -> super::Line {
-    super::Line {
-        v_bivector: [
-            p1.vector[0] * p2.vector[1] - p1.vector[1] * p2.vector[0],
-            p1.vector[0] * p2.vector[2] - p1.vector[2] * p2.vector[0],
-            p1.vector[0] * p2.vector[3] - p1.vector[3] * p2.vector[0],
-        ],
-        e_bivector: [
-            p1.vector[2] * p2.vector[3] - p1.vector[3] * p2.vector[2],
-            p1.vector[3] * p2.vector[1] - p1.vector[1] * p2.vector[3],
-            p1.vector[1] * p2.vector[2] - p1.vector[2] * p2.vector[1],
-        ],
-    }
+    super::products::outer_plane_plane(p1, p2)
 }
 
 pub fn three_planes(p1: &super::Plane, p2: &super::Plane, p3: &super::Plane) -> super::Point {
@@ -32,34 +21,11 @@ pub fn three_planes(p1: &super::Plane, p2: &super::Plane, p3: &super::Plane) ->
 ///         + e013(-v0b4 - v1b2 + v3b0)
 ///         + e021(-v0b3 + v1b1 - v2b0)
 pub fn plane_with_line(p: &super::Plane, l: &super::Line) -> super::Point {
-    super::Point {
-        trivector: [
-            p.vector[1] * l.e_bivector[0]
-                + p.vector[2] * l.e_bivector[1]
-                + p.vector[3] * l.e_bivector[2],
-            -p.vector[0] * l.e_bivector[0] + p.vector[2] * l.v_bivector[2]
-                - p.vector[3] * l.v_bivector[1],
-            -p.vector[0] * l.e_bivector[1] - p.vector[1] * l.v_bivector[2]
-                + p.vector[3] * l.v_bivector[0],
-            -p.vector[0] * l.e_bivector[2] + p.vector[1] * l.v_bivector[1]
-                - p.vector[2] * l.v_bivector[0],
-        ],
-    }
+    super::products::outer_plane_line(p, l)
 }
 
 pub fn lines(b1: &super::Line, b2: &super::Line) -> super::PseudoScalar {
-    let e1 = b1.e_bivector;
-    let e2 = b2.e_bivector;
-    let v1 = b1.v_bivector;
-    let v2 = b2.v_bivector;
-    super::PseudoScalar(
-        e1[0] * v2[0]
-            + e1[1] * v2[1]
-            + e1[2] * v2[2]
-            + e2[0] * v1[0]
-            + e2[1] * v1[1]
-            + e2[2] * v1[2],
-    )
+    super::products::outer_line_line(b1, b2)
 }
 
 #[cfg(test)]
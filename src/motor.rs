@@ -88,6 +88,58 @@ impl Motor {
         v
     }
 
+    /// Least squares version of Motor::from_point_correspondences, for any
+    /// number (at least three) of point pairs. Returns the motor that minimizes
+    /// the squared distances between the moved points and their destinations.
+    ///
+    /// Uses the closed form solution of Horn (1987), where the rotor is the
+    /// dominant eigenvector of a 4x4 matrix built from the cross covariance.
+    pub fn fit_points(from: &[super::Point], to: &[super::Point]) -> Self {
+        let n = from.len().min(to.len());
+        if n == 0 {
+            return Self::one();
+        }
+        let a: Vec<na::Vec3> = from[..n]
+            .iter()
+            .map(|p| na::Vec3::from(p.normalize().eucl()))
+            .collect();
+        let b: Vec<na::Vec3> = to[..n]
+            .iter()
+            .map(|p| na::Vec3::from(p.normalize().eucl()))
+            .collect();
+        let ca = a.iter().sum::<na::Vec3>() / n as f32;
+        let cb = b.iter().sum::<na::Vec3>() / n as f32;
+        let s = a
+            .iter()
+            .zip(b.iter())
+            .map(|(a, b)| (a - ca) * (b - cb).transpose())
+            .sum::<na::Matrix3<f32>>();
+
+        let (sxx, sxy, sxz) = (s[(0, 0)], s[(0, 1)], s[(0, 2)]);
+        let (syx, syy, syz) = (s[(1, 0)], s[(1, 1)], s[(1, 2)]);
+        let (szx, szy, szz) = (s[(2, 0)], s[(2, 1)], s[(2, 2)]);
+        #[rustfmt::skip]
+        let k = na::Matrix4::new(
+            sxx + syy + szz, syz - szy,       szx - sxz,       sxy - syx,
+            syz - szy,       sxx - syy - szz, sxy + syx,       szx + sxz,
+            szx - sxz,       sxy + syx,       -sxx + syy - szz, syz + szy,
+            sxy - syx,       szx + sxz,       syz + szy,       -sxx - syy + szz,
+        );
+        let eig = na::SymmetricEigen::new(k);
+        let imax = eig.eigenvalues.imax();
+        let q = eig.eigenvectors.column(imax);
+
+        // A quaternion (w, x, y, z) is the rotor w - x e23 - y e31 - z e12
+        let r = super::Rotor {
+            scalar: q[0],
+            e_bivector: [-q[1], -q[2], -q[3]],
+        }
+        .normalize();
+        let rca = r.apply_to_point(&super::Point::new(&ca.into())).eucl();
+        let t = [cb[0] - rca[0], cb[1] - rca[1], cb[2] - rca[2]];
+        super::Translator::new(&t).mul_rotor(&r)
+    }
+
     pub fn into_rotor_checked(&self) -> Result<super::Rotor, error::CastError<Self>> {
         if self.v_bivector.iter().any(|e| e.abs() > 0.1) || self.pseudo.abs() > 0.1 {
             return Err(error::CastError::new(self, "Rotor"));
@@ -133,6 +185,13 @@ impl Motor {
             + self.scalar * self.scalar
     }
 
+    /// Scalar product of the rotor parts, positive when both are in the
+    /// same half, i.e., closer than their negations.
+    pub fn rotor_dot(&self, other: &Self) -> f32 {
+        let (ea, eb) = (self.e_bivector, other.e_bivector);
+        self.scalar * other.scalar + ea[0] * eb[0] + ea[1] * eb[1] + ea[2] * eb[2]
+    }
+
     pub fn normalize(&self) -> Self {
         let fac = 1. / self.norm();
         Self {
@@ -149,6 +208,22 @@ impl Motor {
         }
     }
 
+    /// Normalizes and removes the part that makes the motor non-rigid,
+    /// so that scalar * pseudo = e_bivector . v_bivector holds again.
+    /// Approximations (e.g., a first order exp) and many products in a row
+    /// slowly violate this.
+    pub fn rigid_normalize(&self) -> Self {
+        let m = self.normalize();
+        let e = m.e_bivector;
+        let v = m.v_bivector;
+        let b = e[0] * v[0] + e[1] * v[1] + e[2] * v[2] - m.scalar * m.pseudo;
+        Self {
+            pseudo: m.pseudo + b * m.scalar,
+            v_bivector: [v[0] - b * e[0], v[1] - b * e[1], v[2] - b * e[2]],
+            ..m
+        }
+    }
+
     /// Check whether the motor has no grade 4 part, and therefore
     /// called 'simple'. E.g., rotation that is not around the origin.
     pub fn is_simple(&self) -> bool {
@@ -233,37 +308,33 @@ impl Motor {
         }
     }
 
-    /// SIGGRAPH Course Notes 8.1.6.
+    /// SIGGRAPH Course Notes 8.1.6, the inverse of Line::exp. With
+    /// s = |e| and u = atan2(s, scalar),
+    ///     ln = (u/s) e + (u/s) v - e0123 (u scalar - s)/s^3 e,
+    /// which does not depend on the scale of self. Near s = 0 with a
+    /// negative scalar, e.g., -1 or a negated translator, this is the log
+    /// of -self, which is the same motion.
     pub fn ln(&self) -> super::Line {
         let s1 = self.scalar;
-        let p1 = self.pseudo;
-        let b = super::Line::from(self);
-        let bdb = -super::inner::lines(&b, &b);
-        // When self is a translator nothing has to be done
-        if bdb.abs() < 0.001 {
-            return super::Line::from(self);
-        }
-        let s2 = bdb.sqrt();
-        let p2 = super::meet::lines(&b, &b).mul_scalar(-2. * s2);
-
-        let (u, v) = if s1.abs() > 0.001 {
-            let u = s2.atan2(s1);
-            let v = p2.div_scalar(s1);
-            (u, v)
+        let p = self.pseudo;
+        let be = self.e_bivector;
+        let bv = self.v_bivector;
+        let s2sq = be[0] * be[0] + be[1] * be[1] + be[2] * be[2];
+        let s2 = s2sq.sqrt();
+        // Series of u/s and (u s1 - s)/s^3 around s = 0. For s1 < 0 it is
+        // the series of -self, negated, since u/s is odd in s1.
+        let (r, c) = if s2 < 0.01 * s1.abs() {
+            (1. / s1 - s2sq / (3. * s1 * s1 * s1), -1. / (3. * s1 * s1))
         } else {
-            let u = (-p1).atan2(p2.0);
-            let v = super::PseudoScalar(-p1 / s2);
-            (u, v)
+            let u = s2.atan2(s1);
+            (u / s2, (u * s1 - s2) / (s2sq * s2))
         };
-        let be = b.e_bivector;
-        let bv = b.v_bivector;
-        let s2sq = s2 * s2;
         super::Line {
-            e_bivector: [be[0] * u / s2, be[1] * u / s2, be[2] * u / s2],
+            e_bivector: [r * be[0], r * be[1], r * be[2]],
             v_bivector: [
-                -be[0] * p2.0 * u / s2sq - be[0] * v.0 / s2 + bv[0] * u / s2,
-                -be[1] * p2.0 * u / s2sq - be[1] * v.0 / s2 + bv[1] * u / s2,
-                -be[2] * p2.0 * u / s2sq - be[2] * v.0 / s2 + bv[2] * u / s2,
+                r * bv[0] - c * p * be[0],
+                r * bv[1] - c * p * be[1],
+                r * bv[2] - c * p * be[2],
             ],
         }
     }
@@ -285,45 +356,7 @@ impl Motor {
     }
 
     pub fn mul(&self, other: &Self) -> Self {
-        let s1 = self.scalar;
-        let s2 = other.scalar;
-        let ps1 = self.pseudo;
-        let ps2 = other.pseudo;
-        let v1 = self.v_bivector;
-        let v2 = other.v_bivector;
-        let e1 = self.e_bivector;
-        let e2 = other.e_bivector;
-        Self {
-            scalar: -e1[0] * e2[0] - e1[1] * e2[1] - e1[2] * e2[2] + s1 * s2,
-            pseudo: e1[0] * v2[0]
-                + e1[1] * v2[1]
-                + e1[2] * v2[2]
-                + e2[0] * v1[0]
-                + e2[1] * v1[1]
-                + e2[2] * v1[2]
-                + ps1 * s2
-                + ps2 * s1,
-            v_bivector: [
-                -e1[0] * ps2 - e1[1] * v2[2] + e1[2] * v2[1] - e2[0] * ps1 + e2[1] * v1[2]
-                    - e2[2] * v1[1]
-                    + s1 * v2[0]
-                    + s2 * v1[0],
-                e1[0] * v2[2] - e1[1] * ps2 - e1[2] * v2[0] - e2[0] * v1[2] - e2[1] * ps1
-                    + e2[2] * v1[0]
-                    + s1 * v2[1]
-                    + s2 * v1[1],
-                -e1[0] * v2[1] + e1[1] * v2[0] - e1[2] * ps2 + e2[0] * v1[1]
-                    - e2[1] * v1[0]
-                    - e2[2] * ps1
-                    + s1 * v2[2]
-                    + s2 * v1[2],
-            ],
-            e_bivector: [
-                e1[0] * s2 - e1[1] * e2[2] + e1[2] * e2[1] + e2[0] * s1,
-                e1[0] * e2[2] + e1[1] * s2 - e1[2] * e2[0] + e2[1] * s1,
-                -e1[0] * e2[1] + e1[1] * e2[0] + e1[2] * s2 + e2[2] * s1,
-            ],
-        }
+        super::products::geometric_motor_motor(self, other)
     }
 
     pub fn div(&self, other: &Self) -> Self {
@@ -331,22 +364,7 @@ impl Motor {
     }
 
     pub fn mul_translator(&self, t: &super::Translator) -> Self {
-        let ts = t.scalar;
-        let tv = t.v_bivector;
-        let ms = self.scalar;
-        let mps = self.pseudo;
-        let mv = self.v_bivector;
-        let me = self.e_bivector;
-        Self {
-            scalar: ms * ts,
-            pseudo: me[0] * tv[0] + me[1] * tv[1] + me[2] * tv[2] + mps * ts,
-            v_bivector: [
-                -me[1] * tv[2] + me[2] * tv[1] + ms * tv[0] + mv[0] * ts,
-                me[0] * tv[2] - me[2] * tv[0] + ms * tv[1] + mv[1] * ts,
-                -me[0] * tv[1] + me[1] * tv[0] + ms * tv[2] + mv[2] * ts,
-            ],
-            e_bivector: [me[0] * ts, me[1] * ts, me[2] * ts],
-        }
+        super::products::geometric_motor_translator(self, t)
     }
 
     pub fn apply_to<T: Applicable>(&self, g: &T) -> T {
@@ -354,184 +372,87 @@ impl Motor {
     }
 
     pub fn apply_to_point(&self, p: &super::Point) -> super::Point {
-        let ms = self.scalar;
-        let mps = self.pseudo;
-        let mv = self.v_bivector;
-        let me = self.e_bivector;
-        let p = p.trivector;
-        super::Point {
-            trivector: [
-                p[0] * (me[0] * me[0] + me[1] * me[1] + me[2] * me[2] + ms * ms),
-                me[0] * me[0] * p[1] + 2. * me[0] * me[1] * p[2] + 2. * me[0] * me[2] * p[3]
-                    - 2. * me[0] * mps * p[0]
-                    - me[1] * me[1] * p[1]
-                    - 2. * me[1] * ms * p[3]
-                    + 2. * me[1] * mv[2] * p[0]
-                    - me[2] * me[2] * p[1]
-                    + 2. * me[2] * ms * p[2]
-                    - 2. * me[2] * mv[1] * p[0]
-                    + ms * ms * p[1]
-                    - 2. * ms * mv[0] * p[0],
-                -me[0] * me[0] * p[2] + 2. * me[0] * me[1] * p[1] + 2. * me[0] * ms * p[3]
-                    - 2. * me[0] * mv[2] * p[0]
-                    + me[1] * me[1] * p[2]
-                    + 2. * me[1] * me[2] * p[3]
-                    - 2. * me[1] * mps * p[0]
-                    - me[2] * me[2] * p[2]
-                    - 2. * me[2] * ms * p[1]
-                    + 2. * me[2] * mv[0] * p[0]
-                    + ms * ms * p[2]
-                    - 2. * ms * mv[1] * p[0],
-                -me[0] * me[0] * p[3] + 2. * me[0] * me[2] * p[1] - 2. * me[0] * ms * p[2]
-                    + 2. * me[0] * mv[1] * p[0]
-                    - me[1] * me[1] * p[3]
-                    + 2. * me[1] * me[2] * p[2]
-                    + 2. * me[1] * ms * p[1]
-                    - 2. * me[1] * mv[0] * p[0]
-                    + me[2] * me[2] * p[3]
-                    - 2. * me[2] * mps * p[0]
-                    + ms * ms * p[3]
-                    - 2. * ms * mv[2] * p[0],
-            ],
-        }
+        super::products::sandwich_motor_point(self, p)
     }
 
     pub fn apply_to_plane(&self, p: &super::Plane) -> super::Plane {
-        let pvec = p.vector;
-        let ms = self.scalar;
-        let mps = self.pseudo;
-        let mv = self.v_bivector;
-        let me = self.e_bivector;
-        super::Plane {
-            vector: [
-                me[0] * me[0] * pvec[0] + 2. * me[0] * mps * pvec[1] + 2. * me[0] * mv[1] * pvec[3]
-                    - 2. * me[0] * mv[2] * pvec[2]
-                    + me[1] * me[1] * pvec[0]
-                    + 2. * me[1] * mps * pvec[2]
-                    - 2. * me[1] * mv[0] * pvec[3]
-                    + 2. * me[1] * mv[2] * pvec[1]
-                    + me[2] * me[2] * pvec[0]
-                    + 2. * me[2] * mps * pvec[3]
-                    + 2. * me[2] * mv[0] * pvec[2]
-                    - 2. * me[2] * mv[1] * pvec[1]
-                    + ms * ms * pvec[0]
-                    + 2. * ms * mv[0] * pvec[1]
-                    + 2. * ms * mv[1] * pvec[2]
-                    + 2. * ms * mv[2] * pvec[3],
-                me[0] * me[0] * pvec[1]
-                    + 2. * me[0] * me[1] * pvec[2]
-                    + 2. * me[0] * me[2] * pvec[3]
-                    - me[1] * me[1] * pvec[1]
-                    - 2. * me[1] * ms * pvec[3]
-                    - me[2] * me[2] * pvec[1]
-                    + 2. * me[2] * ms * pvec[2]
-                    + ms * ms * pvec[1],
-                -me[0] * me[0] * pvec[2]
-                    + 2. * me[0] * me[1] * pvec[1]
-                    + 2. * me[0] * ms * pvec[3]
-                    + me[1] * me[1] * pvec[2]
-                    + 2. * me[1] * me[2] * pvec[3]
-                    - me[2] * me[2] * pvec[2]
-                    - 2. * me[2] * ms * pvec[1]
-                    + ms * ms * pvec[2],
-                -me[0] * me[0] * pvec[3] + 2. * me[0] * me[2] * pvec[1]
-                    - 2. * me[0] * ms * pvec[2]
-                    - me[1] * me[1] * pvec[3]
-                    + 2. * me[1] * me[2] * pvec[2]
-                    + 2. * me[1] * ms * pvec[1]
-                    + me[2] * me[2] * pvec[3]
-                    + ms * ms * pvec[3],
-            ],
-        }
+        super::products::sandwich_motor_plane(self, p)
     }
 
     pub fn apply_to_line(&self, l: &super::Line) -> super::Line {
-        let ms = self.scalar;
-        let mps = self.pseudo;
-        let mv = self.v_bivector;
-        let me = self.e_bivector;
-        let le = l.e_bivector;
-        let lv = l.v_bivector;
-        super::Line {
-            e_bivector: [
-                le[0] * me[0] * me[0] - le[0] * me[1] * me[1] - le[0] * me[2] * me[2]
-                    + le[0] * ms * ms
-                    + 2. * le[1] * me[0] * me[1]
-                    + 2. * le[1] * me[2] * ms
-                    + 2. * le[2] * me[0] * me[2]
-                    - 2. * le[2] * me[1] * ms,
-                2. * le[0] * me[0] * me[1] - 2. * le[0] * me[2] * ms - le[1] * me[0] * me[0]
-                    + le[1] * me[1] * me[1]
-                    - le[1] * me[2] * me[2]
-                    + le[1] * ms * ms
-                    + 2. * le[2] * me[0] * ms
-                    + 2. * le[2] * me[1] * me[2],
-                2. * le[0] * me[0] * me[2] + 2. * le[0] * me[1] * ms - 2. * le[1] * me[0] * ms
-                    + 2. * le[1] * me[1] * me[2]
-                    - le[2] * me[0] * me[0]
-                    - le[2] * me[1] * me[1]
-                    + le[2] * me[2] * me[2]
-                    + le[2] * ms * ms,
-            ],
-            v_bivector: [
-                2. * le[0] * me[0] * mv[0]
-                    - 2. * le[0] * me[1] * mv[1]
-                    - 2. * le[0] * me[2] * mv[2]
-                    - 2. * le[0] * mps * ms
-                    + 2. * le[1] * me[0] * mv[1]
-                    + 2. * le[1] * me[1] * mv[0]
-                    - 2. * le[1] * me[2] * mps
-                    + 2. * le[1] * ms * mv[2]
-                    + 2. * le[2] * me[0] * mv[2]
-                    + 2. * le[2] * me[1] * mps
-                    + 2. * le[2] * me[2] * mv[0]
-                    - 2. * le[2] * ms * mv[1]
-                    + lv[0] * me[0] * me[0]
-                    - lv[0] * me[1] * me[1]
-                    - lv[0] * me[2] * me[2]
-                    + lv[0] * ms * ms
-                    + 2. * lv[1] * me[0] * me[1]
-                    + 2. * lv[1] * me[2] * ms
-                    + 2. * lv[2] * me[0] * me[2]
-                    - 2. * lv[2] * me[1] * ms,
-                2. * le[0] * me[0] * mv[1] + 2. * le[0] * me[1] * mv[0] + 2. * le[0] * me[2] * mps
-                    - 2. * le[0] * ms * mv[2]
-                    - 2. * le[1] * me[0] * mv[0]
-                    + 2. * le[1] * me[1] * mv[1]
-                    - 2. * le[1] * me[2] * mv[2]
-                    - 2. * le[1] * mps * ms
-                    - 2. * le[2] * me[0] * mps
-                    + 2. * le[2] * me[1] * mv[2]
-                    + 2. * le[2] * me[2] * mv[1]
-                    + 2. * le[2] * ms * mv[0]
-                    + 2. * lv[0] * me[0] * me[1]
-                    - 2. * lv[0] * me[2] * ms
-                    - lv[1] * me[0] * me[0]
-                    + lv[1] * me[1] * me[1]
-                    - lv[1] * me[2] * me[2]
-                    + lv[1] * ms * ms
-                    + 2. * lv[2] * me[0] * ms
-                    + 2. * lv[2] * me[1] * me[2],
-                2. * le[0] * me[0] * mv[2] - 2. * le[0] * me[1] * mps
-                    + 2. * le[0] * me[2] * mv[0]
-                    + 2. * le[0] * ms * mv[1]
-                    + 2. * le[1] * me[0] * mps
-                    + 2. * le[1] * me[1] * mv[2]
-                    + 2. * le[1] * me[2] * mv[1]
-                    - 2. * le[1] * ms * mv[0]
-                    - 2. * le[2] * me[0] * mv[0]
-                    - 2. * le[2] * me[1] * mv[1]
-                    + 2. * le[2] * me[2] * mv[2]
-                    - 2. * le[2] * mps * ms
-                    + 2. * lv[0] * me[0] * me[2]
-                    + 2. * lv[0] * me[1] * ms
-                    - 2. * lv[1] * me[0] * ms
-                    + 2. * lv[1] * me[1] * me[2]
-                    - lv[2] * me[0] * me[0]
-                    - lv[2] * me[1] * me[1]
-                    + lv[2] * me[2] * me[2]
-                    + lv[2] * ms * ms,
-            ],
+        super::products::sandwich_motor_line(self, l)
+    }
+
+    /// Derivative of apply_to_point with respect to the perturbation
+    /// exp(d) * self, evaluated at d = 0. Column k is the derivative along
+    /// the k-th tangent coordinate of d (see Line::from_tangent).
+    ///
+    /// The sandwich product is a quadratic form Q in the motor, so the
+    /// derivative along n = d * self follows exactly from polarization:
+    ///     dQ = (Q(m + n) - Q(m - n)) / 2
+    pub fn jacobian_point(&self, p: &super::Point) -> [super::Point; 6] {
+        let col = |k: usize| {
+            let (plus, min) = self.polarize(k);
+            let a = plus.apply_to_point(p).trivector;
+            let b = min.apply_to_point(p).trivector;
+            super::Point {
+                trivector: [
+                    0.5 * (a[0] - b[0]),
+                    0.5 * (a[1] - b[1]),
+                    0.5 * (a[2] - b[2]),
+                    0.5 * (a[3] - b[3]),
+                ],
+            }
+        };
+        [col(0), col(1), col(2), col(3), col(4), col(5)]
+    }
+
+    /// Derivative of apply_to_plane, see Motor::jacobian_point.
+    pub fn jacobian_plane(&self, p: &super::Plane) -> [super::Plane; 6] {
+        let col = |k: usize| {
+            let (plus, min) = self.polarize(k);
+            let a = plus.apply_to_plane(p).vector;
+            let b = min.apply_to_plane(p).vector;
+            super::Plane {
+                vector: [
+                    0.5 * (a[0] - b[0]),
+                    0.5 * (a[1] - b[1]),
+                    0.5 * (a[2] - b[2]),
+                    0.5 * (a[3] - b[3]),
+                ],
+            }
+        };
+        [col(0), col(1), col(2), col(3), col(4), col(5)]
+    }
+
+    /// Derivative of apply_to_line, see Motor::jacobian_point.
+    pub fn jacobian_line(&self, l: &super::Line) -> [super::Line; 6] {
+        let col = |k: usize| {
+            let (plus, min) = self.polarize(k);
+            plus.apply_to_line(l)
+                .add(&min.apply_to_line(l).neg())
+                .mul_scalar(0.5)
+        };
+        [col(0), col(1), col(2), col(3), col(4), col(5)]
+    }
+
+    /// Returns (self + d * self, self - d * self), with d the k-th
+    /// tangent basis line.
+    fn polarize(&self, k: usize) -> (Self, Self) {
+        let mut t = [0.0; 6];
+        t[k] = 1.0;
+        let n = Self::from(&super::Line::from_tangent(&t)).mul(self);
+        (self.add(&n), self.add(&n.neg()))
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        Self {
+            scalar: self.scalar + other.scalar,
+            pseudo: self.pseudo + other.pseudo,
+            e_bivector: (na::Vector3::from(self.e_bivector) + na::Vector3::from(other.e_bivector))
+                .into(),
+            v_bivector: (na::Vector3::from(self.v_bivector) + na::Vector3::from(other.v_bivector))
+                .into(),
         }
     }
 
@@ -787,6 +708,17 @@ mod tests {
         assert_eq!(v_cba, rot);
     }
 
+    #[test]
+    fn fit_points() {
+        let m = test_motor2().normalize();
+        let from: Vec<Point> = (0..20).map(|_| Point::random()).collect();
+        let to: Vec<Point> = from.iter().map(|p| m.apply_to_point(p)).collect();
+        let m_ = Motor::fit_points(&from, &to);
+        for (p, p_) in from.iter().zip(to.iter()) {
+            assert_eq!(m_.apply_to_point(p), *p_);
+        }
+    }
+
     #[test]
     fn logarithm1() {
         let m = test_motor2();
@@ -855,6 +787,86 @@ mod tests {
         assert!(m.is_similar_to(0.01, &m_));
     }
 
+    #[test]
+    fn jacobians() {
+        // Compare with central differences of the perturbed motor.
+        let m = test_motor2().normalize();
+        let p = Point::new(&[0.3, -1.2, 0.8]);
+        let pl = Plane::new(0.7, &[0.6, 0.0, 0.8]);
+        let l = Line::new(&[0.5, 0.1, -0.4], &[0.0, 0.6, 0.8]);
+        let jp = m.jacobian_point(&p);
+        let jpl = m.jacobian_plane(&pl);
+        let jl = m.jacobian_line(&l);
+        let h = 1e-2;
+        for k in 0..6 {
+            let mut t = [0.0; 6];
+            t[k] = h;
+            let plus = Line::from_tangent(&t).exp().mul(&m);
+            let min = Line::from_tangent(&t).neg().exp().mul(&m);
+
+            let a = plus.apply_to_point(&p).trivector;
+            let b = min.apply_to_point(&p).trivector;
+            for i in 0..4 {
+                assert!(((a[i] - b[i]) / (2. * h) - jp[k].trivector[i]).abs() < 0.01);
+            }
+            let a = plus.apply_to_plane(&pl).vector;
+            let b = min.apply_to_plane(&pl).vector;
+            for i in 0..4 {
+                assert!(((a[i] - b[i]) / (2. * h) - jpl[k].vector[i]).abs() < 0.01);
+            }
+            let a = plus.apply_to_line(&l).tangent();
+            let b = min.apply_to_line(&l).tangent();
+            let j = jl[k].tangent();
+            for i in 0..6 {
+                assert!(((a[i] - b[i]) / (2. * h) - j[i]).abs() < 0.01);
+            }
+        }
+    }
+
+    #[test]
+    fn exp_of_screws() {
+        // A screw, rotating around and translating along the same line.
+        let t = Line::new(&[0.5, 0.1, -0.4], &[0.0, 0.6, 0.8]).add(&Line {
+            e_bivector: [0.; 3],
+            v_bivector: [0.0, 0.6, 0.8],
+        });
+        for s in [0.001, 0.01, 0.3, 1.2, 2.5].iter() {
+            let b = t.mul_scalar(*s);
+            let e = b.e_bivector;
+            let v = b.v_bivector;
+            assert!(e[0] * v[0] + e[1] * v[1] + e[2] * v[2] != 0.);
+            // Against the power series sum b^n/n!.
+            let mut term = Motor::one();
+            let mut series = Motor::one();
+            for n in 1..30 {
+                term = term.mul(&Motor::from(&b)).mul_scalar(1. / n as f32);
+                series = series.add(&term);
+            }
+            let m = b.exp();
+            assert!(m.is_similar_to(1e-5, &series), "{:?} {:?}", m, series);
+            let back = m.ln();
+            for (x, y) in back.tangent().iter().zip(b.tangent().iter()) {
+                assert!((x - y).abs() < 1e-4, "{:?} {:?}", back, b);
+            }
+        }
+        let screw = Translator::new(&[0., 0., 2.]).mul_rotor(&Rotor::new(1., &[0., 0., 1.]));
+        assert!(screw.ln().exp().is_similar_to(1e-5, &screw));
+        assert!(screw.mul_scalar(3.).ln().exp().is_similar_to(1e-5, &screw));
+    }
+
+    #[test]
+    fn ln_of_negated() {
+        let t = Motor::from(&Translator::new(&[1., -2., 0.5]));
+        let l = t.neg().ln();
+        assert_eq!(l.tangent(), t.ln().tangent());
+        assert!(l.exp().is_similar_to(1e-6, &t));
+        assert_eq!(Motor::one().neg().ln().tangent(), [0.; 6]);
+        let r = Motor::from(&Rotor::new(0.001, &[0., 1., 0.]));
+        for (x, y) in r.neg().ln().tangent().iter().zip(r.ln().tangent().iter()) {
+            assert!((x - y).abs() < 1e-7);
+        }
+    }
+
     #[test]
     fn does_same_as_neg() {
         let m = test_motor();
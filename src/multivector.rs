@@ -0,0 +1,440 @@
+//! The general multivector and the ppga_type! macro.
+//!
+//! Multivector holds all 16 components and multiplies them through the
+//! Cayley tables of codegen, which is a lot slower than the generated
+//! products of the crate's types but works for any combination of blades.
+//! ppga_type! uses it to give new grade-sparse types all products with the
+//! existing ones without writing them out.
+
+use super::codegen::{self, Product, BASIS};
+use super::{Line, Motor, Plane, Point, PseudoScalar, Rotor, Translator};
+use std::sync::OnceLock;
+
+/// All components, in the order of codegen::BASIS:
+/// 1, e0, e1, e2, e3, e01, e02, e03, e12, e31, e23, e021, e013, e032, e123, e0123.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Multivector(pub [f32; 16]);
+
+/// Conversion to and from the general multivector. Implemented for the
+/// crate's types and the ones made with ppga_type!.
+pub trait Element {
+    fn to_multivector(&self) -> Multivector;
+    /// Keeps the blades of Self, the others are dropped.
+    fn from_multivector(m: &Multivector) -> Self;
+}
+
+/// (blade, sign) of the product of every pair of blades, sign 0 where it vanishes.
+type Table = [[(usize, f32); 16]; 16];
+
+fn table(p: Product) -> &'static Table {
+    static TABLES: OnceLock<[Table; 4]> = OnceLock::new();
+    let tables = TABLES.get_or_init(|| {
+        let products = [
+            codegen::geometric,
+            codegen::outer,
+            codegen::regressive,
+            codegen::inner,
+        ];
+        let mut tables = [[[(0, 0.0); 16]; 16]; 4];
+        for (t, f) in tables.iter_mut().zip(products.iter()) {
+            for (a, row) in t.iter_mut().enumerate() {
+                for (b, r) in row.iter_mut().enumerate() {
+                    if let Some((blade, sign)) = f(a, b) {
+                        *r = (blade, sign as f32);
+                    }
+                }
+            }
+        }
+        tables
+    });
+    match p {
+        Product::Geometric => &tables[0],
+        Product::Outer => &tables[1],
+        Product::Regressive => &tables[2],
+        Product::Inner => &tables[3],
+        Product::Sandwich => panic!("the sandwich is not bilinear"),
+    }
+}
+
+const GRADE: [usize; 16] = [0, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 3, 3, 3, 3, 4];
+
+impl Multivector {
+    pub fn zero() -> Self {
+        Self([0.0; 16])
+    }
+
+    pub fn one() -> Self {
+        let mut m = Self::zero();
+        m.0[0] = 1.0;
+        m
+    }
+
+    /// The component of a blade by name, e.g., "e31".
+    pub fn get(&self, blade: &str) -> f32 {
+        self.0[codegen::blade(blade)]
+    }
+    pub fn set(&mut self, blade: &str, x: f32) {
+        self.0[codegen::blade(blade)] = x;
+    }
+
+    /// Only the components of grade g.
+    pub fn grade(&self, g: usize) -> Self {
+        let mut r = Self::zero();
+        for (i, x) in self.0.iter().enumerate() {
+            if GRADE[i] == g {
+                r.0[i] = *x;
+            }
+        }
+        r
+    }
+
+    fn product(&self, other: &Self, p: Product) -> Self {
+        let t = table(p);
+        let mut r = Self::zero();
+        for (a, x) in self.0.iter().enumerate() {
+            if *x == 0.0 {
+                continue;
+            }
+            for (b, y) in other.0.iter().enumerate() {
+                let (blade, sign) = t[a][b];
+                r.0[blade] += sign * x * y;
+            }
+        }
+        r
+    }
+
+    /// The geometric product.
+    pub fn mul(&self, other: &Self) -> Self {
+        self.product(other, Product::Geometric)
+    }
+    /// The wedge, meet.
+    pub fn outer(&self, other: &Self) -> Self {
+        self.product(other, Product::Outer)
+    }
+    /// The vee, join.
+    pub fn regressive(&self, other: &Self) -> Self {
+        self.product(other, Product::Regressive)
+    }
+    pub fn inner(&self, other: &Self) -> Self {
+        self.product(other, Product::Inner)
+    }
+    /// self other ~self
+    pub fn sandwich(&self, other: &Self) -> Self {
+        self.mul(other).mul(&self.reverse())
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        let mut r = *self;
+        for (r, x) in r.0.iter_mut().zip(other.0.iter()) {
+            *r += x;
+        }
+        r
+    }
+    pub fn mul_scalar(&self, s: f32) -> Self {
+        Self(self.0.map(|x| x * s))
+    }
+
+    pub fn reverse(&self) -> Self {
+        let mut r = *self;
+        for (i, x) in r.0.iter_mut().enumerate() {
+            if GRADE[i] == 2 || GRADE[i] == 3 {
+                *x = -*x;
+            }
+        }
+        r
+    }
+
+    /// Like the dual of the crate's types, blade i goes to blade 15 - i.
+    pub fn dual(&self) -> Self {
+        let mut r = self.0;
+        r.reverse();
+        Self(r)
+    }
+
+    /// sqrt(|<x ~x>_0|), only sees the euclidean part.
+    pub fn norm(&self) -> f32 {
+        self.mul(&self.reverse()).0[0].abs().sqrt()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|x| *x == 0.0)
+    }
+}
+
+impl std::fmt::Display for Multivector {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut first = true;
+        for (x, b) in self.0.iter().zip(BASIS.iter()) {
+            if *x == 0.0 {
+                continue;
+            }
+            if !first {
+                write!(f, " + ")?;
+            }
+            first = false;
+            match *b {
+                "1" => write!(f, "{}", x)?,
+                b => write!(f, "{}{}", x, b)?,
+            }
+        }
+        if first {
+            write!(f, "0")?;
+        }
+        Ok(())
+    }
+}
+
+impl Element for Multivector {
+    fn to_multivector(&self) -> Multivector {
+        *self
+    }
+    fn from_multivector(m: &Multivector) -> Self {
+        *m
+    }
+}
+
+impl Element for PseudoScalar {
+    fn to_multivector(&self) -> Multivector {
+        let mut m = Multivector::zero();
+        m.0[15] = self.0;
+        m
+    }
+    fn from_multivector(m: &Multivector) -> Self {
+        Self(m.0[15])
+    }
+}
+
+crate::ppga_type!(@element Point { trivector });
+crate::ppga_type!(@element Plane { vector });
+crate::ppga_type!(@element Line { e_bivector, v_bivector });
+crate::ppga_type!(@element Rotor { scalar, e_bivector });
+crate::ppga_type!(@element Translator { scalar, v_bivector });
+crate::ppga_type!(@element Motor { scalar, pseudo, e_bivector, v_bivector });
+
+/// Defines a new type holding some of the blades, named like the fields of
+/// the crate's types (scalar, pseudo, vector, e_bivector, v_bivector,
+/// trivector), e.g.,
+///
+/// ```
+/// ppga::ppga_type!(pub Screw { e_bivector, v_bivector, pseudo });
+///
+/// let s = Screw { e_bivector: [0., 0., 1.], v_bivector: [1., 0., 0.], pseudo: 0.5 };
+/// let m = s.mul(&ppga::Motor::one());
+/// assert_eq!(Screw::from(&m), s);
+/// ```
+///
+/// The type gets Element and conversions from and to Multivector, the
+/// geometric (mul), outer, regressive and inner products with anything
+/// that is Element, reverse, dual, norm and Applicable. The products
+/// return a Multivector, project it back with From.
+#[macro_export]
+macro_rules! ppga_type {
+    (@element $name:ident { $($field:ident),+ }) => {
+        impl $crate::Element for $name {
+            fn to_multivector(&self) -> $crate::Multivector {
+                let mut m = $crate::Multivector::zero();
+                $($crate::__ppga_field!(write $field, m, self.$field);)+
+                m
+            }
+            fn from_multivector(m: &$crate::Multivector) -> Self {
+                Self {
+                    $($field: $crate::__ppga_field!(read $field, m),)+
+                }
+            }
+        }
+
+        impl From<&$name> for $crate::Multivector {
+            fn from(x: &$name) -> Self {
+                $crate::Element::to_multivector(x)
+            }
+        }
+
+        impl From<&$crate::Multivector> for $name {
+            fn from(m: &$crate::Multivector) -> Self {
+                <$name as $crate::Element>::from_multivector(m)
+            }
+        }
+    };
+    ($(#[$meta:meta])* $vis:vis $name:ident { $($field:ident),+ $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Copy, Clone, PartialEq, Default)]
+        $vis struct $name {
+            $(pub $field: $crate::__ppga_field!(type $field),)+
+        }
+
+        $crate::ppga_type!(@element $name { $($field),+ });
+
+        impl $name {
+            pub fn mul<T: $crate::Element>(&self, other: &T) -> $crate::Multivector {
+                $crate::Element::to_multivector(self).mul(&$crate::Element::to_multivector(other))
+            }
+            pub fn outer<T: $crate::Element>(&self, other: &T) -> $crate::Multivector {
+                $crate::Element::to_multivector(self).outer(&$crate::Element::to_multivector(other))
+            }
+            pub fn regressive<T: $crate::Element>(&self, other: &T) -> $crate::Multivector {
+                $crate::Element::to_multivector(self).regressive(&$crate::Element::to_multivector(other))
+            }
+            pub fn inner<T: $crate::Element>(&self, other: &T) -> $crate::Multivector {
+                $crate::Element::to_multivector(self).inner(&$crate::Element::to_multivector(other))
+            }
+            pub fn reverse(&self) -> Self {
+                Self::from(&$crate::Element::to_multivector(self).reverse())
+            }
+            pub fn dual(&self) -> $crate::Multivector {
+                $crate::Element::to_multivector(self).dual()
+            }
+            pub fn norm(&self) -> f32 {
+                $crate::Element::to_multivector(self).norm()
+            }
+        }
+
+        impl $crate::Applicable for $name {
+            fn apply(&self, m: &$crate::Motor) -> Self {
+                let m = $crate::Element::to_multivector(m);
+                Self::from(&m.sandwich(&$crate::Element::to_multivector(self)))
+            }
+        }
+    };
+}
+
+/// Type and blades of the fields of ppga_type!.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ppga_field {
+    (type scalar) => { f32 };
+    (type pseudo) => { f32 };
+    (type vector) => { [f32; 4] };
+    (type e_bivector) => { [f32; 3] };
+    (type v_bivector) => { [f32; 3] };
+    (type trivector) => { [f32; 4] };
+    (blades vector) => { [1usize, 2, 3, 4] };
+    (blades v_bivector) => { [5usize, 6, 7] };
+    (blades e_bivector) => { [10usize, 9, 8] };
+    (blades trivector) => { [14usize, 13, 12, 11] };
+    (write scalar, $m:ident, $x:expr) => { $m.0[0] = $x; };
+    (write pseudo, $m:ident, $x:expr) => { $m.0[15] = $x; };
+    (write $field:ident, $m:ident, $x:expr) => {
+        for (i, x) in $crate::__ppga_field!(blades $field).iter().zip($x.iter()) {
+            $m.0[*i] = *x;
+        }
+    };
+    (read scalar, $m:expr) => { $m.0[0] };
+    (read pseudo, $m:expr) => { $m.0[15] };
+    (read $field:ident, $m:expr) => {
+        $crate::__ppga_field!(blades $field).map(|i| $m.0[i])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    ppga_type!(Screw {
+        e_bivector,
+        v_bivector,
+        pseudo
+    });
+    ppga_type!(Flat { vector });
+
+    fn close(a: &Multivector, b: &Multivector) -> bool {
+        a.0.iter()
+            .zip(b.0.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-4)
+    }
+
+    fn mv<T: Element>(x: &T) -> Multivector {
+        x.to_multivector()
+    }
+
+    fn random_motor() -> Motor {
+        Line::random().exp().normalize()
+    }
+
+    #[test]
+    fn blades() {
+        let l = Line {
+            e_bivector: [1., 2., 3.],
+            v_bivector: [4., 5., 6.],
+        };
+        let m = mv(&l);
+        assert_eq!(m.get("e23"), 1.);
+        assert_eq!(m.get("e12"), 3.);
+        assert_eq!(m.get("e01"), 4.);
+        assert_eq!(m.get("e03"), 6.);
+        let p = Point::new(&[1., 2., 3.]);
+        assert_eq!(mv(&p).get("e032"), -1.);
+        assert_eq!(mv(&p).get("e021"), -3.);
+        assert_eq!(Line::from(&m), l);
+        assert_eq!(m.grade(2), m);
+        assert!(m.grade(1).is_zero());
+    }
+
+    #[test]
+    fn products() {
+        for _ in 0..10 {
+            let (a, b) = (random_motor(), random_motor());
+            assert!(close(&mv(&a).mul(&mv(&b)), &mv(&a.mul(&b))));
+            let (p, q) = (Plane::random(), Plane::random());
+            assert!(close(&mv(&p).outer(&mv(&q)), &mv(&meet::planes(&p, &q))));
+            let (x, y) = (Point::random(), Point::random());
+            assert!(close(
+                &mv(&x).regressive(&mv(&y)),
+                &mv(&join::points(&x, &y))
+            ));
+            let (k, l) = (Line::random(), Line::random());
+            assert!((mv(&k).inner(&mv(&l)).0[0] - inner::lines(&k, &l)).abs() < 1e-5);
+            let moved = mv(&a).sandwich(&mv(&x));
+            assert!(close(&moved, &mv(&a.apply_to_point(&x))));
+            assert!(close(&mv(&x).dual(), &mv(&x.dual())));
+            assert!(close(&mv(&k).dual(), &mv(&k.dual())));
+        }
+    }
+
+    #[test]
+    fn custom() {
+        let m = random_motor();
+        let s = Screw {
+            e_bivector: [0.3, -1., 2.],
+            v_bivector: [1., 0.5, -0.2],
+            pseudo: 0.7,
+        };
+        let l = Line {
+            e_bivector: s.e_bivector,
+            v_bivector: s.v_bivector,
+        };
+        let as_motor = Motor {
+            scalar: 0.,
+            pseudo: s.pseudo,
+            e_bivector: s.e_bivector,
+            v_bivector: s.v_bivector,
+        };
+        assert!(close(&s.mul(&m), &mv(&as_motor.mul(&m))));
+        assert!(close(&s.outer(&l), &mv(&as_motor).outer(&mv(&l))));
+        assert!(close(&s.regressive(&l), &mv(&as_motor).regressive(&mv(&l))));
+        assert!(close(&s.inner(&m), &mv(&as_motor).inner(&mv(&m))));
+        assert!(close(
+            &Screw::from(&mv(&s)).mul(&l),
+            &mv(&as_motor).mul(&mv(&l))
+        ));
+
+        let applied = s.apply(&m);
+        let expected = m.apply_to_line(&l);
+        assert!(close(&mv(&Line::from(&mv(&applied))), &mv(&expected)));
+        assert!((applied.pseudo - s.pseudo).abs() < 1e-4);
+
+        assert_eq!(s.reverse().pseudo, s.pseudo);
+        assert_eq!(s.reverse().e_bivector, l.reverse().e_bivector);
+        assert_eq!(s.dual().0[0], s.pseudo);
+        assert!((s.norm() - l.norm()).abs() < 1e-5);
+
+        let f = Flat::from(&mv(&Plane::new(2., &[0., 0., 1.])));
+        let x = Point::new(&[0., 0., 2.]);
+        assert!(f.outer(&x).0[15].abs() < 1e-6);
+        assert!(f.regressive(&x.dual()).is_zero());
+        assert_eq!(f.mul(&f).0[0], f.inner(&f).0[0]);
+        assert_eq!(f.reverse(), f);
+        assert_eq!(Point::from(&f.dual()).trivector, f.vector);
+        assert_eq!(f.norm(), 1.);
+        assert_eq!(f.apply(&Motor::one()), f);
+    }
+}
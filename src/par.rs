@@ -0,0 +1,187 @@
+//! Parallel versions of the bulk operations in batch, using rayon.
+//! Only available with the rayon feature. Every element is computed exactly
+//! as in the serial version, so the results are identical.
+
+use super::batch::{self, CHAIN_BLOCK};
+use super::skinning::{self, Weights};
+use super::{Line, Motor, Plane, Point};
+use rayon::prelude::*;
+
+/// Below this many elements per task, splitting costs more than it gains.
+const MIN_LEN: usize = 1024;
+
+impl Motor {
+    pub fn par_apply_to_points(&self, ps: &[Point]) -> Vec<Point> {
+        let m = self.matrix();
+        ps.par_iter()
+            .with_min_len(MIN_LEN)
+            .map(|p| m.apply_to_point(p))
+            .collect()
+    }
+    pub fn par_apply_to_points_mut(&self, ps: &mut [Point]) {
+        let m = self.matrix();
+        ps.par_iter_mut()
+            .with_min_len(MIN_LEN)
+            .for_each(|p| *p = m.apply_to_point(p));
+    }
+
+    pub fn par_apply_to_planes(&self, ps: &[Plane]) -> Vec<Plane> {
+        let m = self.matrix();
+        ps.par_iter()
+            .with_min_len(MIN_LEN)
+            .map(|p| m.apply_to_plane(p))
+            .collect()
+    }
+    pub fn par_apply_to_planes_mut(&self, ps: &mut [Plane]) {
+        let m = self.matrix();
+        ps.par_iter_mut()
+            .with_min_len(MIN_LEN)
+            .for_each(|p| *p = m.apply_to_plane(p));
+    }
+
+    pub fn par_apply_to_lines(&self, ls: &[Line]) -> Vec<Line> {
+        let m = self.matrix();
+        ls.par_iter()
+            .with_min_len(MIN_LEN)
+            .map(|l| m.apply_to_line(l))
+            .collect()
+    }
+    pub fn par_apply_to_lines_mut(&self, ls: &mut [Line]) {
+        let m = self.matrix();
+        ls.par_iter_mut()
+            .with_min_len(MIN_LEN)
+            .for_each(|l| *l = m.apply_to_line(l));
+    }
+
+    /// Moves raw euclidean coordinates. Assumes a normalized motor.
+    pub fn par_apply_to_eucl(&self, xs: &[[f32; 3]]) -> Vec<[f32; 3]> {
+        let m = self.matrix();
+        xs.par_iter()
+            .with_min_len(MIN_LEN)
+            .map(|x| m.apply_to_eucl(x))
+            .collect()
+    }
+    pub fn par_apply_to_eucl_mut(&self, xs: &mut [[f32; 3]]) {
+        let m = self.matrix();
+        xs.par_iter_mut()
+            .with_min_len(MIN_LEN)
+            .for_each(|x| *x = m.apply_to_eucl(x));
+    }
+}
+
+/// batch::chain, the blocks are accumulated and moved in parallel.
+pub fn chain(ms: &[Motor]) -> Vec<Motor> {
+    let mut r = ms.to_vec();
+    r.par_chunks_mut(CHAIN_BLOCK).for_each(batch::prefix);
+    let offsets = batch::block_offsets(&r);
+    r.par_chunks_mut(CHAIN_BLOCK)
+        .skip(1)
+        .zip(offsets.par_iter())
+        .for_each(|(block, offset)| batch::apply_offset(block, offset));
+    r
+}
+
+pub fn exp(ls: &[Line]) -> Vec<Motor> {
+    ls.par_iter()
+        .with_min_len(MIN_LEN)
+        .map(|l| l.exp())
+        .collect()
+}
+
+pub fn ln(ms: &[Motor]) -> Vec<Line> {
+    ms.par_iter()
+        .with_min_len(MIN_LEN)
+        .map(|m| m.ln())
+        .collect()
+}
+
+/// skinning::skin_mut, the vertices in parallel.
+pub fn skin_mut(
+    joints: &[Motor],
+    weights: &[Weights],
+    positions: &mut [[f32; 3]],
+    normals: &mut [[f32; 3]],
+) {
+    assert_eq!(weights.len(), positions.len());
+    if normals.is_empty() {
+        positions
+            .par_iter_mut()
+            .zip(weights.par_iter())
+            .with_min_len(MIN_LEN)
+            .for_each(|(x, w)| skinning::skin_vertex(joints, w, x, None));
+    } else {
+        assert_eq!(normals.len(), positions.len());
+        positions
+            .par_iter_mut()
+            .zip(normals.par_iter_mut())
+            .zip(weights.par_iter())
+            .with_min_len(MIN_LEN)
+            .for_each(|((x, n), w)| skinning::skin_vertex(joints, w, x, Some(n)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn same_as_serial() {
+        let n = 5000;
+        let m = Motor::random().normalize();
+        let points: Vec<Point> = (0..n).map(|_| Point::random()).collect();
+        let planes: Vec<Plane> = (0..n).map(|_| Plane::random()).collect();
+        let lines: Vec<Line> = (0..n).map(|_| Line::random()).collect();
+        let motors: Vec<Motor> = lines
+            .iter()
+            .map(|l| l.mul_scalar(0.01).exp().normalize())
+            .collect();
+
+        let bits = |ps: &[Point]| -> Vec<[u32; 4]> {
+            ps.iter().map(|p| p.trivector.map(f32::to_bits)).collect()
+        };
+        assert_eq!(
+            bits(&m.apply_to_points(&points)),
+            bits(&m.par_apply_to_points(&points))
+        );
+        let mut moved = points.clone();
+        m.par_apply_to_points_mut(&mut moved);
+        assert_eq!(bits(&m.apply_to_points(&points)), bits(&moved));
+
+        let serial: Vec<[f32; 4]> = m
+            .apply_to_planes(&planes)
+            .iter()
+            .map(|p| p.vector)
+            .collect();
+        let parallel: Vec<[f32; 4]> = m
+            .par_apply_to_planes(&planes)
+            .iter()
+            .map(|p| p.vector)
+            .collect();
+        assert_eq!(serial, parallel);
+
+        let tangents = |ls: &[Line]| -> Vec<[f32; 6]> { ls.iter().map(|l| l.tangent()).collect() };
+        assert_eq!(
+            tangents(&m.apply_to_lines(&lines)),
+            tangents(&m.par_apply_to_lines(&lines))
+        );
+        assert_eq!(tangents(&batch::ln(&motors)), tangents(&par::ln(&motors)));
+
+        let klein =
+            |ms: &[Motor]| -> Vec<[[f32; 4]; 2]> { ms.iter().map(|m| m.into_klein()).collect() };
+        assert_eq!(klein(&batch::exp(&lines)), klein(&par::exp(&lines)));
+        assert_eq!(klein(&batch::chain(&motors)), klein(&par::chain(&motors)));
+
+        let weights: Vec<skinning::Weights> = (0..n)
+            .map(|i| skinning::Weights {
+                joints: [i % 7, (i + 3) % 7, 0, 0],
+                weights: [0.6, 0.4, 0., 0.],
+            })
+            .collect();
+        let xs: Vec<[f32; 3]> = points.iter().map(|p| p.eucl()).collect();
+        let (serial, serial_normals) = skinning::skin(&motors[..7], &weights, &xs, &xs);
+        let (mut parallel, mut parallel_normals) = (xs.clone(), xs.clone());
+        par::skin_mut(&motors[..7], &weights, &mut parallel, &mut parallel_normals);
+        assert_eq!(serial, parallel);
+        assert_eq!(serial_normals, parallel_normals);
+    }
+}
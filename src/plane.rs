@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone)]
 pub struct Plane {
     pub vector: [f32; 4],
 }
@@ -49,6 +49,14 @@ impl Plane {
         na::Vec3::from_row_slice(&self.vector[1..=3]).norm()
     }
 
+    /// n.x - d, the signed distance of p. Positive on the side the normal
+    /// points to.
+    pub fn distance(&self, p: &super::Point) -> f32 {
+        let v = self.vector;
+        let t = p.trivector;
+        -(v[0] * t[0] + v[1] * t[1] + v[2] * t[2] + v[3] * t[3]) / (t[0] * self.norm())
+    }
+
     pub fn neg(&self) -> Self {
         Self {
             vector: (-na::Vector4::from_row_slice(&self.vector)).into(),
@@ -74,22 +82,7 @@ impl Plane {
     }
 
     pub fn mul(&self, other: &Self) -> super::Motor {
-        let v1 = self.vector;
-        let v2 = other.vector;
-        super::Motor {
-            scalar: v1[1] * v2[1] + v1[2] * v2[2] + v1[3] * v2[3],
-            v_bivector: [
-                v1[0] * v2[1] - v1[1] * v2[0],
-                v1[0] * v2[2] - v1[2] * v2[0],
-                v1[0] * v2[3] - v1[3] * v2[0],
-            ],
-            e_bivector: [
-                v1[2] * v2[3] - v1[3] * v2[2],
-                -v1[1] * v2[3] + v1[3] * v2[1],
-                v1[1] * v2[2] - v1[2] * v2[1],
-            ],
-            pseudo: 0.,
-        }
+        super::products::geometric_plane_plane(self, other)
     }
 
     pub fn div(&self, other: &Self) -> super::Motor {
@@ -138,6 +131,18 @@ mod tests {
         assert_eq!(p, p.dual().dual());
     }
 
+    #[test]
+    fn distance() {
+        let p = Plane::new(2., &[0., 0., 2.]);
+        assert_eq!(p.distance(&Point::new(&[3., 1., 4.])), 3.);
+        assert_eq!(
+            p.distance(&Point {
+                trivector: [2., -6., -2., -2.]
+            }),
+            0.
+        );
+    }
+
     #[test]
     fn move_to() {
         let p1 = Plane::random().mul_scalar(-4.5).nnormalize();
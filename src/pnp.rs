@@ -0,0 +1,590 @@
+//! Camera pose from known 3D points or lines and where they are seen in
+//! the image. Only the intrinsics of the camera are used, and the result
+//! is its pose, e.g., Camera { pose, ..camera }.
+//!
+//! Everything works on undistorted image points x/z and y/z (see
+//! Camera::image_point), so distortion is taken care of up front.
+
+use super::camera::Camera;
+use super::d2::Line2;
+use super::pose_graph::{eucl_with_jacobian, retract};
+use super::{Line, Motor, Point};
+
+/// The four poses that fit three points, at most, from Grunert's quartic
+/// in the ratios of the distances to the camera. Empty with fewer than
+/// three points.
+pub fn p3p_solutions(camera: &Camera, points: &[Point], pixels: &[[f32; 2]]) -> Vec<Motor> {
+    if points.len() < 3 || pixels.len() < 3 {
+        return Vec::new();
+    }
+    let x: Vec<na::Vector3<f64>> = points[..3]
+        .iter()
+        .map(|p| na::Vector3::from(p.normalize().eucl()).map(f64::from))
+        .collect();
+    let j: Vec<na::Vector3<f64>> = pixels[..3]
+        .iter()
+        .map(|px| bearing(camera, px).map(f64::from))
+        .collect();
+    let a2 = (x[1] - x[2]).norm_squared();
+    let b2 = (x[0] - x[2]).norm_squared();
+    let c2 = (x[0] - x[1]).norm_squared();
+    let (ca, cb, cg) = (j[1].dot(&j[2]), j[0].dot(&j[2]), j[0].dot(&j[1]));
+    // With s2 = u s1 and s3 = v s1 for the distances s to the camera,
+    // the law of cosines gives u = n(v) / d(v), and substituting it into
+    // c^2 = s1^2 (1 + u^2 - 2 u cg) with s1^2 = b^2 / (1 + v^2 - 2 v cb)
+    // leaves n^2 - 2 cg n d + q d^2 = 0.
+    let k = (a2 - c2) / b2;
+    let n = [1. + k, -2. * k * cb, k - 1.];
+    let d = [2. * cg, -2. * ca];
+    let q = [1. - c2 / b2, 2. * c2 / b2 * cb, -c2 / b2];
+    let nn = poly_mul(&n, &n);
+    let nd = poly_mul(&n, &d);
+    let qdd = poly_mul(&q, &poly_mul(&d, &d));
+    let quartic: Vec<f64> = (0..5)
+        .map(|i| nn[i] - 2. * cg * nd.get(i).unwrap_or(&0.) + qdd[i])
+        .collect();
+
+    let mut poses = Vec::new();
+    for v in real_roots(&quartic) {
+        let dv = d[0] + d[1] * v;
+        if v <= 0. || dv.abs() < 1e-12 {
+            continue;
+        }
+        let u = (n[0] + n[1] * v + n[2] * v * v) / dv;
+        let s1 = (b2 / (1. + v * v - 2. * v * cb)).sqrt();
+        if u <= 0. || !s1.is_finite() {
+            continue;
+        }
+        let local: Vec<Point> = [s1, u * s1, v * s1]
+            .iter()
+            .zip(j.iter())
+            .map(|(s, j)| Point::new(&(j * *s).map(|x| x as f32).into()))
+            .collect();
+        poses.push(Motor::fit_points(&local, &points[..3]));
+    }
+    poses
+}
+
+/// The P3P solution on the first three points that best reprojects all of
+/// them, so a fourth point picks the right one.
+pub fn p3p(camera: &Camera, points: &[Point], pixels: &[[f32; 2]]) -> Option<Motor> {
+    best(
+        camera,
+        points,
+        pixels,
+        p3p_solutions(camera, points, pixels),
+    )
+}
+
+/// EPnP of Lepetit et al. (2009). The points are weighted sums of four
+/// control points (three for planar scenes), whose coordinates in the
+/// camera frame lie in the null space of the projection equations. The
+/// null space vectors are weighed to keep the distances between the
+/// control points, with one, two or three of them, and the best of these
+/// is returned. Needs at least four points.
+pub fn epnp(camera: &Camera, points: &[Point], pixels: &[[f32; 2]]) -> Option<Motor> {
+    assert_eq!(points.len(), pixels.len());
+    let world: Vec<na::Vec3> = points
+        .iter()
+        .map(|p| na::Vec3::from(p.normalize().eucl()))
+        .collect();
+    let n = world.len();
+    if n < 4 {
+        return None;
+    }
+    // Control points at the centroid and along the principal directions.
+    let c0 = world.iter().sum::<na::Vec3>() / n as f32;
+    let cov = world
+        .iter()
+        .map(|x| (x - c0) * (x - c0).transpose())
+        .sum::<na::Matrix3<f32>>()
+        / n as f32;
+    let eig = na::SymmetricEigen::new(cov);
+    let largest = eig.eigenvalues.max();
+    let axes: Vec<(na::Vec3, f32)> = (0..3)
+        .filter(|i| eig.eigenvalues[*i] > 1e-6 * largest)
+        .map(|i| (eig.eigenvectors.column(i).into(), eig.eigenvalues[i].sqrt()))
+        .collect();
+    let nc = axes.len() + 1;
+    let mut control = vec![c0];
+    control.extend(axes.iter().map(|(e, s)| c0 + e * *s));
+    let alphas: Vec<Vec<f32>> = world
+        .iter()
+        .map(|x| {
+            let mut a: Vec<f32> = axes.iter().map(|(e, s)| (x - c0).dot(e) / s).collect();
+            a.insert(0, 1. - a.iter().sum::<f32>());
+            a
+        })
+        .collect();
+
+    let mut m = na::DMatrix::<f32>::zeros(2 * n, 3 * nc);
+    for (i, (a, pixel)) in alphas.iter().zip(pixels.iter()).enumerate() {
+        let [u, v] = camera.image_point(pixel);
+        for (k, a) in a.iter().enumerate() {
+            m[(2 * i, 3 * k)] = *a;
+            m[(2 * i, 3 * k + 2)] = -a * u;
+            m[(2 * i + 1, 3 * k + 1)] = *a;
+            m[(2 * i + 1, 3 * k + 2)] = -a * v;
+        }
+    }
+    let eig = na::SymmetricEigen::new(m.transpose() * m);
+    let mut order: Vec<usize> = (0..3 * nc).collect();
+    order.sort_by(|i, j| eig.eigenvalues[*i].total_cmp(&eig.eigenvalues[*j]));
+    let null: Vec<na::DVector<f32>> = order[..3]
+        .iter()
+        .map(|i| eig.eigenvectors.column(*i).into())
+        .collect();
+
+    let pairs: Vec<(usize, usize)> = (0..nc)
+        .flat_map(|a| (a + 1..nc).map(move |b| (a, b)))
+        .collect();
+    let diff = |v: &na::DVector<f32>, (a, b): (usize, usize)| {
+        na::Vec3::new(
+            v[3 * a] - v[3 * b],
+            v[3 * a + 1] - v[3 * b + 1],
+            v[3 * a + 2] - v[3 * b + 2],
+        )
+    };
+    let dist2: Vec<f32> = pairs
+        .iter()
+        .map(|(a, b)| (control[*a] - control[*b]).norm_squared())
+        .collect();
+
+    let mut candidates = Vec::new();
+    for k in 1..=3 {
+        // Unknowns b_ij = beta_i beta_j for i <= j, linear in the squared
+        // distances.
+        let products: Vec<(usize, usize)> =
+            (0..k).flat_map(|i| (i..k).map(move |j| (i, j))).collect();
+        if products.len() > pairs.len() {
+            break;
+        }
+        let l = na::DMatrix::from_fn(pairs.len(), products.len(), |row, col| {
+            let (i, j) = products[col];
+            let s = if i == j { 1. } else { 2. };
+            s * diff(&null[i], pairs[row]).dot(&diff(&null[j], pairs[row]))
+        });
+        let rhs = na::DVector::from_column_slice(&dist2);
+        let b = match l.svd(true, true).solve(&rhs, 1e-7) {
+            Ok(b) => b,
+            Err(_) => continue,
+        };
+        // b_00 gives beta_0, then b_0j gives the others.
+        let beta0 = b[0].abs().sqrt();
+        if beta0 < 1e-9 {
+            continue;
+        }
+        let betas: Vec<f32> = (0..k)
+            .map(|j| if j == 0 { beta0 } else { b[j] / beta0 })
+            .collect();
+        let mut x = na::DVector::zeros(3 * nc);
+        for (beta, v) in betas.iter().zip(null.iter()) {
+            x += v * *beta;
+        }
+        let local: Vec<na::Vec3> = alphas
+            .iter()
+            .map(|a| {
+                (0..nc)
+                    .map(|c| na::Vec3::new(x[3 * c], x[3 * c + 1], x[3 * c + 2]) * a[c])
+                    .sum()
+            })
+            .collect();
+        // The null space has no sign, the scene is in front of the camera.
+        let sign = if local.iter().map(|x| x[2]).sum::<f32>() < 0. {
+            -1.
+        } else {
+            1.
+        };
+        let local: Vec<Point> = local
+            .iter()
+            .map(|x| Point::new(&(x * sign).into()))
+            .collect();
+        candidates.push(Motor::fit_points(&local, points));
+    }
+    best(camera, points, pixels, candidates)
+}
+
+/// Gauss-Newton on the reprojection error of the points, starting at pose.
+/// The world to camera motor is updated in the bivector tangent space as
+/// in pose_graph, m <- exp(d) * m.
+pub fn refine(
+    camera: &Camera,
+    points: &[Point],
+    pixels: &[[f32; 2]],
+    pose: &Motor,
+    iterations: usize,
+) -> Motor {
+    let observed: Vec<[f32; 2]> = pixels.iter().map(|px| camera.image_point(px)).collect();
+    let residuals = |m: &Motor| {
+        let mut r = Vec::with_capacity(2 * points.len());
+        let mut j = Vec::with_capacity(2 * points.len());
+        for (p, [u, v]) in points.iter().zip(observed.iter()) {
+            let ([x, y, z], jx) = eucl_with_jacobian(m, p);
+            r.push(x / z - u);
+            r.push(y / z - v);
+            let du = |k: usize| (jx[0][k] - x / z * jx[2][k]) / z;
+            let dv = |k: usize| (jx[1][k] - y / z * jx[2][k]) / z;
+            j.push([du(0), du(1), du(2), du(3), du(4), du(5)]);
+            j.push([dv(0), dv(1), dv(2), dv(3), dv(4), dv(5)]);
+        }
+        (r, j)
+    };
+    gauss_newton(&pose.reverse(), residuals, iterations).reverse()
+}
+
+/// The pose from lines, PnL. Every seen line back projects to a plane
+/// through the camera center, which has to contain two points of the world
+/// line. These equations are linear in the 3x4 world to camera matrix,
+/// which makes a direct linear transform for six lines or more, followed
+/// by refine_lines.
+pub fn pnl(camera: &Camera, lines: &[Line], images: &[Line2]) -> Option<Motor> {
+    assert_eq!(lines.len(), images.len());
+    if lines.len() < 6 {
+        return None;
+    }
+    let mut ata = na::DMatrix::<f32>::zeros(12, 12);
+    for (l, image) in lines.iter().zip(images.iter()) {
+        let n = local_normal(camera, image);
+        for x in line_points(l).iter() {
+            let mut row = na::DVector::<f32>::zeros(12);
+            for r in 0..3 {
+                for c in 0..3 {
+                    row[3 * r + c] = n[r] * x[c];
+                }
+                row[9 + r] = n[r];
+            }
+            ata += &row * row.transpose();
+        }
+    }
+    let eig = na::SymmetricEigen::new(ata);
+    let v = eig.eigenvectors.column(eig.eigenvalues.imin());
+    let r = na::Matrix3::from_fn(|i, j| v[3 * i + j]);
+    let t = na::Vec3::new(v[9], v[10], v[11]);
+    // Scaled by the cube root of the determinant, which fixes the sign too.
+    let s = r.determinant().cbrt();
+    if s.abs() < 1e-9 {
+        return None;
+    }
+    let world: Vec<Point> = [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]]
+        .iter()
+        .map(Point::new)
+        .collect();
+    let local: Vec<Point> = world
+        .iter()
+        .map(|p| Point::new(&((r * na::Vec3::from(p.eucl()) + t) / s).into()))
+        .collect();
+    let pose = Motor::fit_points(&local, &world);
+    Some(refine_lines(camera, lines, images, &pose, 10))
+}
+
+/// Gauss-Newton on the distances of two points of every world line to the
+/// plane back projected from its image, see refine.
+pub fn refine_lines(
+    camera: &Camera,
+    lines: &[Line],
+    images: &[Line2],
+    pose: &Motor,
+    iterations: usize,
+) -> Motor {
+    let planes: Vec<(na::Vec3, [Point; 2])> = lines
+        .iter()
+        .zip(images.iter())
+        .map(|(l, image)| {
+            let [a, b] = line_points(l);
+            (
+                local_normal(camera, image),
+                [Point::new(&a.into()), Point::new(&b.into())],
+            )
+        })
+        .collect();
+    let residuals = |m: &Motor| {
+        let mut r = Vec::with_capacity(2 * planes.len());
+        let mut j = Vec::with_capacity(2 * planes.len());
+        for (n, points) in planes.iter() {
+            for p in points.iter() {
+                let (x, jx) = eucl_with_jacobian(m, p);
+                r.push(n.dot(&na::Vec3::from(x)));
+                let d = |k: usize| n[0] * jx[0][k] + n[1] * jx[1][k] + n[2] * jx[2][k];
+                j.push([d(0), d(1), d(2), d(3), d(4), d(5)]);
+            }
+        }
+        (r, j)
+    };
+    gauss_newton(&pose.reverse(), residuals, iterations).reverse()
+}
+
+/// The root mean square distance in pixels between the projections of the
+/// points from the camera at pose and the pixels. Infinite when a point is
+/// behind the camera.
+pub fn reprojection_error(
+    camera: &Camera,
+    pose: &Motor,
+    points: &[Point],
+    pixels: &[[f32; 2]],
+) -> f32 {
+    let camera = Camera {
+        pose: *pose,
+        ..*camera
+    };
+    let mut sum = 0.0;
+    for (p, pixel) in points.iter().zip(pixels.iter()) {
+        match camera.project(p) {
+            Some([u, v]) => sum += (u - pixel[0]).powi(2) + (v - pixel[1]).powi(2),
+            None => return f32::INFINITY,
+        }
+    }
+    (sum / points.len() as f32).sqrt()
+}
+
+fn best(
+    camera: &Camera,
+    points: &[Point],
+    pixels: &[[f32; 2]],
+    poses: Vec<Motor>,
+) -> Option<Motor> {
+    poses
+        .into_iter()
+        .map(|m| (reprojection_error(camera, &m, points, pixels), m))
+        .filter(|(e, _)| e.is_finite())
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, m)| m)
+}
+
+/// The unit direction of the pixel in camera coordinates.
+fn bearing(camera: &Camera, pixel: &[f32; 2]) -> na::Vec3 {
+    let [x, y] = camera.image_point(pixel);
+    na::Vec3::new(x, y, 1.).normalize()
+}
+
+/// The unit normal of the plane back projected from the image line, in
+/// camera coordinates.
+fn local_normal(camera: &Camera, image: &Line2) -> na::Vec3 {
+    let local = Camera {
+        pose: Motor::one(),
+        ..*camera
+    };
+    let v = local.back_project_line(image).vector;
+    na::Vec3::new(v[1], v[2], v[3]).normalize()
+}
+
+/// The point of the line closest to the origin, and one a unit further.
+fn line_points(l: &Line) -> [na::Vec3; 2] {
+    let n = na::Vec3::from(l.e_bivector).norm();
+    let d = na::Vec3::from(l.e_bivector) / n;
+    let p = (na::Vec3::from(l.v_bivector) / n).cross(&d);
+    [p, p + d]
+}
+
+/// Levenberg-Marquardt damped Gauss-Newton on a motor, with residuals
+/// returning the residual vector and its jacobian rows.
+fn gauss_newton(
+    m: &Motor,
+    residuals: impl Fn(&Motor) -> (Vec<f32>, Vec<[f32; 6]>),
+    iterations: usize,
+) -> Motor {
+    let cost = |r: &[f32]| r.iter().map(|x| x * x).sum::<f32>();
+    let mut m = *m;
+    let mut damping = 1e-4;
+    for _ in 0..iterations {
+        let (r, j) = residuals(&m);
+        let mut h = na::Matrix6::<f32>::zeros();
+        let mut g = na::Vector6::<f32>::zeros();
+        for (r, j) in r.iter().zip(j.iter()) {
+            let j = na::Vector6::from_column_slice(j);
+            h += j * j.transpose();
+            g += j * *r;
+        }
+        let c = cost(&r);
+        let mut improved = false;
+        while damping < 1e6 {
+            let damped = h + na::Matrix6::from_diagonal(&h.diagonal()) * damping;
+            let d = match damped.cholesky() {
+                Some(ch) => -ch.solve(&g),
+                None => break,
+            };
+            let next = retract(&m, &[d[0], d[1], d[2], d[3], d[4], d[5]]);
+            if cost(&residuals(&next).0) < c {
+                m = next;
+                damping = (damping * 0.1).max(1e-8);
+                improved = true;
+                break;
+            }
+            damping *= 10.;
+        }
+        if !improved {
+            break;
+        }
+    }
+    m
+}
+
+fn poly_mul(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut c = vec![0.; a.len() + b.len() - 1];
+    for (i, a) in a.iter().enumerate() {
+        for (j, b) in b.iter().enumerate() {
+            c[i + j] += a * b;
+        }
+    }
+    c
+}
+
+/// The real roots of the polynomial with coefficients from the constant
+/// up, as eigenvalues of the companion matrix, polished by Newton.
+fn real_roots(p: &[f64]) -> Vec<f64> {
+    let n = p.len() - 1;
+    let lead = p[n];
+    if lead.abs() < 1e-12 {
+        return real_roots(&p[..n]);
+    }
+    if n == 0 {
+        return Vec::new();
+    }
+    let companion = na::DMatrix::from_fn(n, n, |i, j| {
+        if i == 0 {
+            -p[n - 1 - j] / lead
+        } else if i == j + 1 {
+            1.
+        } else {
+            0.
+        }
+    });
+    let eval = |x: f64| {
+        p.iter()
+            .rev()
+            .fold((0., 0.), |(f, df), c| (f * x + c, df * x + f))
+    };
+    companion
+        .complex_eigenvalues()
+        .iter()
+        .filter(|z| z.im.abs() < 1e-6 * (1. + z.re.abs()))
+        .map(|z| {
+            let mut x = z.re;
+            for _ in 0..5 {
+                let (f, df) = eval(x);
+                if df.abs() > 1e-15 {
+                    x -= f / df;
+                }
+            }
+            x
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::camera::Camera;
+    use crate::pnp::*;
+    use crate::*;
+
+    fn truth() -> Motor {
+        let r = Rotor::new(0.5, &na::Vec3::new(0.3, 1., -0.2).normalize().into());
+        r.mul_translator(&Translator::new(&[0.2, -0.3, -8.]))
+    }
+
+    fn camera() -> Camera {
+        Camera::new(&Motor::one(), &[800., 780.], &[320., 240.]).with_distortion(-0.05, 0.)
+    }
+
+    fn scene() -> Vec<Point> {
+        [
+            [0.5, -1., 0.3],
+            [-1., 0.2, 0.8],
+            [0.9, 0.7, -0.6],
+            [-0.4, -0.8, -1.],
+            [0.1, 1., 0.1],
+            [-0.9, -0.3, 0.4],
+            [0.6, 0.2, 1.],
+            [-0.2, 0.6, -0.7],
+        ]
+        .iter()
+        .map(Point::new)
+        .collect()
+    }
+
+    fn pixels(points: &[Point]) -> Vec<[f32; 2]> {
+        let c = Camera {
+            pose: truth(),
+            ..camera()
+        };
+        points.iter().map(|p| c.project(p).unwrap()).collect()
+    }
+
+    /// Compares where the poses put a few points.
+    fn same_pose(a: &Motor, b: &Motor) -> bool {
+        scene().iter().all(|p| {
+            a.apply_to_point(p)
+                .is_similar_to(1e-2, &b.apply_to_point(p))
+        })
+    }
+
+    #[test]
+    fn p3p() {
+        let points = scene();
+        let pixels = pixels(&points);
+        let solutions = p3p_solutions(&camera(), &points, &pixels);
+        assert!(!solutions.is_empty() && solutions.len() <= 4);
+        assert!(solutions.iter().any(|m| same_pose(m, &truth())));
+        let m = pnp::p3p(&camera(), &points[..4], &pixels[..4]).unwrap();
+        assert!(same_pose(&m, &truth()), "{:?}", m);
+        assert!(reprojection_error(&camera(), &m, &points, &pixels) < 0.1);
+        assert!(p3p_solutions(&camera(), &points[..2], &pixels[..2]).is_empty());
+        assert!(pnp::p3p(&camera(), &points[..2], &pixels[..2]).is_none());
+    }
+
+    #[test]
+    fn epnp() {
+        let points = scene();
+        let pixels = pixels(&points);
+        let m = pnp::epnp(&camera(), &points, &pixels).unwrap();
+        assert!(same_pose(&m, &truth()), "{:?}", m);
+        // A planar target.
+        let planar: Vec<Point> = scene()
+            .iter()
+            .map(|p| Point::new(&[p.eucl()[0], p.eucl()[1], 0.]))
+            .collect();
+        let m = pnp::epnp(&camera(), &planar, &self::pixels(&planar)).unwrap();
+        assert!(same_pose(&m, &truth()), "{:?}", m);
+    }
+
+    #[test]
+    fn refine() {
+        let points = scene();
+        let pixels = pixels(&points);
+        let start = Translator::new(&[0.1, 0., 0.2]).mul_rotor(&Rotor::new(0.05, &[0., 0., 1.]));
+        let start = start.mul(&truth());
+        let m = pnp::refine(&camera(), &points, &pixels, &start, 20);
+        assert!(same_pose(&m, &truth()), "{:?}", m);
+        // With noise it fits the pixels at least as well as the truth.
+        let noisy: Vec<[f32; 2]> = pixels
+            .iter()
+            .enumerate()
+            .map(|(i, x)| [x[0] + 0.3 * (i % 3) as f32, x[1] - 0.2 * (i % 2) as f32])
+            .collect();
+        let m = pnp::refine(&camera(), &points, &noisy, &start, 20);
+        let error = |m: &Motor| reprojection_error(&camera(), m, &points, &noisy);
+        assert!(error(&m) <= error(&truth()));
+    }
+
+    #[test]
+    fn pnl() {
+        let camera = Camera {
+            distortion: [0.; 2],
+            ..camera()
+        };
+        let seen = Camera {
+            pose: truth(),
+            ..camera
+        };
+        let points = scene();
+        let lines: Vec<Line> = (0..points.len())
+            .map(|i| join::points(&points[i], &points[(i + 3) % points.len()]))
+            .collect();
+        let images: Vec<_> = lines
+            .iter()
+            .map(|l| seen.project_line(l).unwrap())
+            .collect();
+        let m = pnp::pnl(&camera, &lines, &images).unwrap();
+        assert!(same_pose(&m, &truth()), "{:?}", m);
+        assert!(pnp::pnl(&camera, &lines[..5], &images[..5]).is_none());
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone)]
 pub struct Point {
     pub trivector: [f32; 4],
 }
@@ -88,16 +88,7 @@ impl Point {
     }
 
     pub fn mul(&self, other: &Self) -> super::Translator {
-        let p1 = self.trivector;
-        let p2 = other.trivector;
-        super::Translator {
-            scalar: -p1[0] * p2[0],
-            v_bivector: [
-                -p1[0] * p2[1] + p1[1] * p2[0],
-                -p1[0] * p2[2] + p1[2] * p2[0],
-                -p1[0] * p2[3] + p1[3] * p2[0],
-            ],
-        }
+        super::products::geometric_point_point(self, other)
     }
 
     pub fn div(&self, other: &Self) -> super::Translator {
@@ -0,0 +1,336 @@
+//! Convex polyhedra as intersections of half-spaces. A plane n.x = d
+//! bounds the half-space n.x <= d, so the normals point outwards.
+//!
+//! Everything derived from the planes (vertices, edges, the hull) is
+//! computed with the meet and join of the planes and points, with EPSILON
+//! as the tolerance for a point to lie on a plane.
+
+use super::{join, meet, Applicable, Motor, Plane, Point};
+
+pub const EPSILON: f32 = 1e-4;
+
+#[derive(Debug)]
+pub struct ConvexPolyhedron {
+    /// Normalized, with outward normals.
+    pub planes: Vec<Plane>,
+}
+
+impl ConvexPolyhedron {
+    pub fn new(planes: Vec<Plane>) -> Self {
+        Self {
+            planes: planes.iter().map(|p| p.normalize()).collect(),
+        }
+    }
+
+    /// The axis aligned box between min and max.
+    pub fn cuboid(min: &[f32; 3], max: &[f32; 3]) -> Self {
+        let mut planes = Vec::with_capacity(6);
+        for i in 0..3 {
+            let mut n = [0.0; 3];
+            n[i] = 1.0;
+            planes.push(Plane::new(max[i], &n));
+            n[i] = -1.0;
+            planes.push(Plane::new(-min[i], &n));
+        }
+        Self::new(planes)
+    }
+
+    /// The convex hull of the points, None if they are all on one plane.
+    /// Incremental: every point outside the hull so far replaces the faces
+    /// it sees by faces to their horizon. Points at infinity are skipped.
+    pub fn from_points(points: &[Point]) -> Option<Self> {
+        let xs: Vec<na::Vec3> = points
+            .iter()
+            .map(|p| na::Vec3::from(p.normalize().eucl()))
+            .filter(|x| x.iter().all(|c| c.is_finite()))
+            .collect();
+        let tetra = initial_tetrahedron(&xs)?;
+        let centroid = tetra.iter().map(|i| xs[*i]).sum::<na::Vec3>() / 4.;
+        let inside = Point::new(&centroid.into());
+        let point = |i: usize| Point::new(&xs[i].into());
+        let face = |[a, b, c]: [usize; 3]| {
+            let plane = join::three_points(&point(a), &point(b), &point(c)).normalize();
+            if plane.distance(&inside) > 0.0 {
+                plane.neg()
+            } else {
+                plane
+            }
+        };
+
+        let [a, b, c, d] = tetra;
+        let mut faces: Vec<([usize; 3], Plane)> = [[a, b, c], [a, b, d], [a, c, d], [b, c, d]]
+            .iter()
+            .map(|f| (*f, face(*f)))
+            .collect();
+        for (i, x) in xs.iter().enumerate() {
+            let p = Point::new(&(*x).into());
+            let (visible, hidden): (Vec<_>, Vec<_>) = faces
+                .into_iter()
+                .partition(|(_, plane)| plane.distance(&p) > EPSILON);
+            faces = hidden;
+            let mut edges: Vec<[usize; 2]> = Vec::new();
+            for (f, _) in visible.iter() {
+                for e in [[f[0], f[1]], [f[1], f[2]], [f[2], f[0]]].iter() {
+                    let e = [e[0].min(e[1]), e[0].max(e[1])];
+                    match edges.iter().position(|x| *x == e) {
+                        Some(j) => {
+                            edges.swap_remove(j);
+                        }
+                        None => edges.push(e),
+                    }
+                }
+            }
+            for [a, b] in edges {
+                faces.push(([a, b, i], face([a, b, i])));
+            }
+        }
+
+        let mut planes: Vec<Plane> = Vec::new();
+        for (_, plane) in faces {
+            if !planes.iter().any(|p| similar(p, &plane)) {
+                planes.push(plane);
+            }
+        }
+        Some(Self { planes })
+    }
+
+    pub fn contains(&self, p: &Point) -> bool {
+        self.planes.iter().all(|plane| plane.distance(p) <= EPSILON)
+    }
+
+    /// The meets of every three planes that are inside all others.
+    pub fn vertices(&self) -> Vec<Point> {
+        let n = self.planes.len();
+        let mut vs: Vec<Point> = Vec::new();
+        for i in 0..n {
+            for j in i + 1..n {
+                let l = meet::planes(&self.planes[i], &self.planes[j]);
+                for k in j + 1..n {
+                    let v = meet::plane_with_line(&self.planes[k], &l);
+                    if v.is_inf() {
+                        continue;
+                    }
+                    let v = v.normalize();
+                    if self.contains(&v) && !vs.iter().any(|w| w.is_similar_to(EPSILON, &v)) {
+                        vs.push(v);
+                    }
+                }
+            }
+        }
+        vs
+    }
+
+    /// The edges as pairs of vertices. An edge lies on the meet of two of
+    /// the planes, its ends are the outermost vertices on that line.
+    pub fn edges(&self) -> Vec<(Point, Point)> {
+        let vs = self.vertices();
+        let n = self.planes.len();
+        let mut edges = Vec::new();
+        for i in 0..n {
+            for j in i + 1..n {
+                let (pi, pj) = (&self.planes[i], &self.planes[j]);
+                let dir = na::Vec3::from(meet::planes(pi, pj).e_bivector);
+                if dir.norm() < EPSILON {
+                    continue;
+                }
+                let t = |v: &Point| dir.dot(&na::Vec3::from(v.eucl()));
+                let on: Vec<&Point> = vs
+                    .iter()
+                    .filter(|v| pi.distance(v).abs() <= EPSILON && pj.distance(v).abs() <= EPSILON)
+                    .collect();
+                let first = on.iter().min_by(|a, b| t(a).total_cmp(&t(b)));
+                let last = on.iter().max_by(|a, b| t(a).total_cmp(&t(b)));
+                if let (Some(a), Some(b)) = (first, last) {
+                    if !a.is_similar_to(EPSILON, b) {
+                        edges.push((**a, **b));
+                    }
+                }
+            }
+        }
+        edges
+    }
+
+    /// Both half-space sets together. Planes that do not carry a face of
+    /// the result are dropped, unless it has no vertices at all (empty or
+    /// unbounded).
+    pub fn intersection(&self, other: &Self) -> Self {
+        let all = Self {
+            planes: self
+                .planes
+                .iter()
+                .chain(other.planes.iter())
+                .map(|p| p.normalize())
+                .collect(),
+        };
+        let vs = all.vertices();
+        if vs.is_empty() {
+            return all;
+        }
+        let mut planes: Vec<Plane> = Vec::new();
+        for p in all.planes.iter() {
+            let on = vs.iter().filter(|v| p.distance(v).abs() <= EPSILON).count();
+            if on >= 3 && !planes.iter().any(|q| similar(q, p)) {
+                planes.push(p.normalize());
+            }
+        }
+        Self { planes }
+    }
+
+    /// The part of the segment from a to b that is inside, None if it
+    /// misses. Cyrus-Beck, the segment enters and leaves every half-space
+    /// where its distance changes sign.
+    pub fn clip_segment(&self, a: &Point, b: &Point) -> Option<(Point, Point)> {
+        let (mut t0, mut t1) = (0.0f32, 1.0f32);
+        for plane in self.planes.iter() {
+            let (da, db) = (plane.distance(a), plane.distance(b));
+            if da > EPSILON && db > EPSILON {
+                return None;
+            }
+            if da > EPSILON {
+                t0 = t0.max(da / (da - db));
+            } else if db > EPSILON {
+                t1 = t1.min(da / (da - db));
+            }
+        }
+        if t0 > t1 {
+            return None;
+        }
+        let (xa, xb) = (
+            na::Vec3::from(a.normalize().eucl()),
+            na::Vec3::from(b.normalize().eucl()),
+        );
+        let at = |t: f32| Point::new(&(xa + (xb - xa) * t).into());
+        Some((at(t0), at(t1)))
+    }
+}
+
+impl Applicable for ConvexPolyhedron {
+    fn apply(&self, m: &Motor) -> Self {
+        Self {
+            planes: self.planes.iter().map(|p| m.apply_to_plane(p)).collect(),
+        }
+    }
+}
+
+fn similar(a: &Plane, b: &Plane) -> bool {
+    a.vector
+        .iter()
+        .zip(b.vector.iter())
+        .all(|(a, b)| (a - b).abs() <= EPSILON)
+}
+
+/// Four points that span a volume: the first one, the farthest from it,
+/// the farthest from the line through both and the farthest from their
+/// plane.
+fn initial_tetrahedron(xs: &[na::Vec3]) -> Option<[usize; 4]> {
+    let argmax = |f: &dyn Fn(&na::Vec3) -> f32| {
+        (0..xs.len()).max_by(|i, j| f(&xs[*i]).total_cmp(&f(&xs[*j])))
+    };
+    let a = 0;
+    let b = argmax(&|x| (x - xs[a]).norm())?;
+    let ab = (xs[b] - xs[a]).try_normalize(EPSILON)?;
+    let c = argmax(&|x| (x - xs[a]).cross(&ab).norm())?;
+    let n = ab.cross(&(xs[c] - xs[a])).try_normalize(EPSILON)?;
+    let d = argmax(&|x| (x - xs[a]).dot(&n).abs())?;
+    if (xs[d] - xs[a]).dot(&n).abs() < EPSILON {
+        return None;
+    }
+    Some([a, b, c, d])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::polyhedron::*;
+    use crate::*;
+
+    fn unit_cube() -> ConvexPolyhedron {
+        ConvexPolyhedron::cuboid(&[0., 0., 0.], &[1., 1., 1.])
+    }
+
+    #[test]
+    fn contains() {
+        let c = unit_cube();
+        assert!(c.contains(&Point::new(&[0.5, 0.2, 0.9])));
+        assert!(c.contains(&Point::new(&[1., 1., 1.])));
+        assert!(!c.contains(&Point::new(&[0.5, 1.2, 0.9])));
+        assert!(!c.contains(&Point::new(&[-0.1, 0.5, 0.5])));
+    }
+
+    #[test]
+    fn vertices_and_edges() {
+        let c = unit_cube();
+        let vs = c.vertices();
+        assert_eq!(vs.len(), 8);
+        assert!(vs.contains(&Point::new(&[1., 0., 1.])));
+        let edges = c.edges();
+        assert_eq!(edges.len(), 12);
+        for (a, b) in edges.iter() {
+            let d = na::Vec3::from(a.eucl()) - na::Vec3::from(b.eucl());
+            assert!((d.norm() - 1.).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn apply() {
+        let m = Translator::new(&[1., 2., 3.]).mul_rotor(&Rotor::new(0.7, &[0., 0., 1.]));
+        let c = unit_cube();
+        let moved = m.apply_to(&c);
+        let p = Point::new(&[0.3, 0.4, 0.5]);
+        assert!(moved.contains(&m.apply_to_point(&p)));
+        assert_eq!(moved.vertices().len(), 8);
+    }
+
+    #[test]
+    fn intersection() {
+        let a = unit_cube();
+        let b = ConvexPolyhedron::cuboid(&[0.5, 0.5, -1.], &[2., 2., 0.5]);
+        let i = a.intersection(&b);
+        assert_eq!(i.planes.len(), 6);
+        let vs = i.vertices();
+        assert_eq!(vs.len(), 8);
+        assert!(vs.contains(&Point::new(&[0.5, 0.5, 0.])));
+        assert!(vs.contains(&Point::new(&[1., 1., 0.5])));
+    }
+
+    #[test]
+    fn clip_segment() {
+        let c = unit_cube();
+        let (a, b) = c
+            .clip_segment(&Point::new(&[-1., 0.5, 0.5]), &Point::new(&[3., 0.5, 0.5]))
+            .unwrap();
+        assert_eq!(a, Point::new(&[0., 0.5, 0.5]));
+        assert_eq!(b, Point::new(&[1., 0.5, 0.5]));
+        assert!(c
+            .clip_segment(&Point::new(&[-1., 2., 0.5]), &Point::new(&[3., 2., 0.5]))
+            .is_none());
+        let (a, b) = c
+            .clip_segment(&Point::new(&[0.2, 0.2, 0.2]), &Point::new(&[0.2, 0.2, 0.8]))
+            .unwrap();
+        assert_eq!(a, Point::new(&[0.2, 0.2, 0.2]));
+        assert_eq!(b, Point::new(&[0.2, 0.2, 0.8]));
+    }
+
+    #[test]
+    fn hull() {
+        let mut points: Vec<Point> = (0..200)
+            .map(|_| Point::new(&(na::Vec3::new_random() * 0.8 + na::Vec3::repeat(0.1)).into()))
+            .collect();
+        for x in [0., 1.].iter() {
+            for y in [0., 1.].iter() {
+                for z in [0., 1.].iter() {
+                    points.push(Point::new(&[*x, *y, *z]));
+                }
+            }
+        }
+        points.push(Point::inf(&[1., 0., 0.]));
+        let h = ConvexPolyhedron::from_points(&points).unwrap();
+        assert_eq!(h.planes.len(), 6);
+        assert_eq!(h.vertices().len(), 8);
+        assert!(points.iter().filter(|p| !p.is_inf()).all(|p| h.contains(p)));
+
+        let flat: Vec<Point> = (0..10)
+            .map(|i| Point::new(&[i as f32, (i * i) as f32, 0.]))
+            .collect();
+        assert!(ConvexPolyhedron::from_points(&flat).is_none());
+    }
+}
@@ -0,0 +1,493 @@
+//! Gauss-Newton optimization over motors, e.g., for SLAM style pose graphs.
+//!
+//! Every motor is updated on the manifold with a left perturbation
+//!     m <- exp(d) * m,
+//! where d is a line given by its six tangent coordinates (see
+//! Line::from_tangent). Residuals are linearized with Motor::jacobian_point,
+//! Motor::jacobian_plane and Motor::jacobian_line, and the damped normal
+//! equations are solved with the block-sparse solver in the sparse module.
+
+use super::sparse::{Block, BlockSparse, BlockVector};
+use super::{Line, Motor, Plane, Point};
+
+/// Robust loss applied to the norm s of a residual.
+#[derive(Debug, Copy, Clone)]
+pub enum Loss {
+    /// Plain least squares, s^2 / 2.
+    Trivial,
+    /// Quadratic up to k, linear after that.
+    Huber(f32),
+    /// k^2 / 2 * ln(1 + (s / k)^2)
+    Cauchy(f32),
+}
+
+impl Loss {
+    pub fn cost(&self, s: f32) -> f32 {
+        match *self {
+            Loss::Trivial => 0.5 * s * s,
+            Loss::Huber(k) => {
+                if s <= k {
+                    0.5 * s * s
+                } else {
+                    k * (s - 0.5 * k)
+                }
+            }
+            Loss::Cauchy(k) => 0.5 * k * k * (1. + (s / k) * (s / k)).ln(),
+        }
+    }
+
+    /// Weight used in iteratively reweighted least squares,
+    /// i.e., cost'(s) / s.
+    pub fn weight(&self, s: f32) -> f32 {
+        match *self {
+            Loss::Trivial => 1.,
+            Loss::Huber(k) => {
+                if s <= k {
+                    1.
+                } else {
+                    k / s
+                }
+            }
+            Loss::Cauchy(k) => 1. / (1. + (s / k) * (s / k)),
+        }
+    }
+}
+
+/// A measurement relating an element in the frame of motor i
+/// to an element in the frame of motor j.
+#[derive(Debug)]
+pub enum Residual {
+    /// Both points should coincide after applying their motors.
+    PointToPoint(Point, Point),
+    /// The point should lie on the plane. The plane should be normalized.
+    PointToPlane(Point, Plane),
+    /// Both lines should coincide. The lines should be normalized and
+    /// have the same orientation.
+    LineToLine(Line, Line),
+}
+
+#[derive(Debug)]
+pub struct Factor {
+    pub i: usize,
+    pub j: usize,
+    pub residual: Residual,
+    pub loss: Loss,
+}
+
+/// Residual vector with the jacobians with respect to motor i and j.
+struct Linearized {
+    r: Vec<f32>,
+    ji: Vec<[f32; 6]>,
+    jj: Vec<[f32; 6]>,
+}
+
+impl Factor {
+    fn linearize(&self, mi: &Motor, mj: &Motor) -> Linearized {
+        match &self.residual {
+            Residual::PointToPoint(a, b) => {
+                let (xa, ja) = eucl_with_jacobian(mi, a);
+                let (xb, jb) = eucl_with_jacobian(mj, b);
+                Linearized {
+                    r: (0..3).map(|k| xa[k] - xb[k]).collect(),
+                    ji: ja.to_vec(),
+                    jj: jb.iter().map(neg6).collect(),
+                }
+            }
+            Residual::PointToPlane(a, p) => {
+                let (x, jx) = eucl_with_jacobian(mi, a);
+                let p_ = mj.apply_to_plane(p).vector;
+                let jp = mj.jacobian_plane(p);
+                // r = n . x - d, the point moves with motor i, the plane with j.
+                let mut ri = [0.0; 6];
+                let mut rj = [0.0; 6];
+                for k in 0..6 {
+                    let dp = jp[k].vector;
+                    ri[k] = p_[1] * jx[0][k] + p_[2] * jx[1][k] + p_[3] * jx[2][k];
+                    rj[k] = dp[1] * x[0] + dp[2] * x[1] + dp[3] * x[2] - dp[0];
+                }
+                Linearized {
+                    r: vec![p_[1] * x[0] + p_[2] * x[1] + p_[3] * x[2] - p_[0]],
+                    ji: vec![ri],
+                    jj: vec![rj],
+                }
+            }
+            Residual::LineToLine(a, b) => {
+                let la = mi.apply_to_line(a).tangent();
+                let lb = mj.apply_to_line(b).tangent();
+                let ja = mi.jacobian_line(a);
+                let jb = mj.jacobian_line(b);
+                let mut ji = vec![[0.0; 6]; 6];
+                let mut jj = vec![[0.0; 6]; 6];
+                for k in 0..6 {
+                    let da = ja[k].tangent();
+                    let db = jb[k].tangent();
+                    for row in 0..6 {
+                        ji[row][k] = da[row];
+                        jj[row][k] = -db[row];
+                    }
+                }
+                Linearized {
+                    r: (0..6).map(|k| la[k] - lb[k]).collect(),
+                    ji,
+                    jj,
+                }
+            }
+        }
+    }
+
+    fn cost(&self, mi: &Motor, mj: &Motor) -> f32 {
+        let r = self.linearize(mi, mj).r;
+        self.loss.cost(norm(&r))
+    }
+}
+
+/// Euclidean coordinates of the transformed point, and their
+/// jacobian (3 rows, one column per tangent coordinate).
+pub(crate) fn eucl_with_jacobian(m: &Motor, p: &Point) -> ([f32; 3], [[f32; 6]; 3]) {
+    let t = m.apply_to_point(p).trivector;
+    let dt = m.jacobian_point(p);
+    let w = t[0];
+    let mut j = [[0.0; 6]; 3];
+    for (row, jrow) in j.iter_mut().enumerate() {
+        for (k, d) in dt.iter().enumerate() {
+            let d = d.trivector;
+            jrow[k] = -(d[row + 1] * w - t[row + 1] * d[0]) / (w * w);
+        }
+    }
+    ([-t[1] / w, -t[2] / w, -t[3] / w], j)
+}
+
+/// The update m <- exp(d) * m, kept rigid.
+pub(crate) fn retract(m: &Motor, d: &[f32; 6]) -> Motor {
+    Line::from_tangent(d).exp().mul(m).rigid_normalize()
+}
+
+fn neg6(x: &[f32; 6]) -> [f32; 6] {
+    [-x[0], -x[1], -x[2], -x[3], -x[4], -x[5]]
+}
+
+fn norm(r: &[f32]) -> f32 {
+    r.iter().map(|x| x * x).sum::<f32>().sqrt()
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Settings {
+    pub max_iterations: usize,
+    /// Stop when the relative decrease of the cost, or the largest
+    /// update, falls below this.
+    pub tolerance: f32,
+    /// Initial Levenberg-Marquardt damping.
+    pub damping: f32,
+    pub solver_iterations: usize,
+    pub solver_tolerance: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            max_iterations: 50,
+            tolerance: 1e-6,
+            damping: 1e-4,
+            solver_iterations: 500,
+            solver_tolerance: 1e-6,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Report {
+    pub iterations: usize,
+    pub initial_cost: f32,
+    pub final_cost: f32,
+    /// False when the iterations ran out or the damping blew up.
+    pub converged: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct PoseGraph {
+    pub motors: Vec<Motor>,
+    pub fixed: Vec<bool>,
+    pub factors: Vec<Factor>,
+}
+
+impl PoseGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a motor and returns its index.
+    pub fn add_motor(&mut self, m: Motor) -> usize {
+        self.motors.push(m);
+        self.fixed.push(false);
+        self.motors.len() - 1
+    }
+
+    /// A fixed motor is not optimized, e.g., to remove the gauge freedom.
+    pub fn fix(&mut self, i: usize) {
+        self.fixed[i] = true;
+    }
+
+    pub fn add_factor(&mut self, i: usize, j: usize, residual: Residual, loss: Loss) {
+        self.factors.push(Factor {
+            i,
+            j,
+            residual,
+            loss,
+        });
+    }
+
+    pub fn cost(&self) -> f32 {
+        Self::cost_of(&self.factors, &self.motors)
+    }
+
+    fn cost_of(factors: &[Factor], motors: &[Motor]) -> f32 {
+        factors
+            .iter()
+            .map(|f| f.cost(&motors[f.i], &motors[f.j]))
+            .sum()
+    }
+
+    /// Builds the normal equations H d = -g of the reweighted problem.
+    fn normal_equations(&self) -> (BlockSparse, Vec<BlockVector>) {
+        let n = self.motors.len();
+        let mut h = BlockSparse::new(n);
+        let mut g = vec![BlockVector::zeros(); n];
+        for f in self.factors.iter() {
+            let lin = f.linearize(&self.motors[f.i], &self.motors[f.j]);
+            let w = f.loss.weight(norm(&lin.r));
+            let r = na::DVector::from_column_slice(&lin.r);
+            let to_matrix =
+                |rows: &[[f32; 6]]| na::DMatrix::from_fn(rows.len(), 6, |row, col| rows[row][col]);
+            let mut blocks = vec![(f.i, to_matrix(&lin.ji))];
+            if f.j == f.i {
+                blocks[0].1 += to_matrix(&lin.jj);
+            } else {
+                blocks.push((f.j, to_matrix(&lin.jj)));
+            }
+            for (a, ja) in blocks.iter() {
+                if self.fixed[*a] {
+                    continue;
+                }
+                let ga = ja.transpose() * &r * w;
+                g[*a] += BlockVector::from_column_slice(ga.as_slice());
+                for (b, jb) in blocks.iter() {
+                    if self.fixed[*b] {
+                        continue;
+                    }
+                    let hab = ja.transpose() * jb * w;
+                    h.add(*a, *b, &Block::from_column_slice(hab.as_slice()));
+                }
+            }
+        }
+        for (i, fixed) in self.fixed.iter().enumerate() {
+            if *fixed {
+                h.add(i, i, &Block::identity());
+            }
+        }
+        (h, g)
+    }
+
+    /// Levenberg-Marquardt damped Gauss-Newton. Robust losses are handled
+    /// with iteratively reweighted least squares.
+    pub fn optimize(&mut self, settings: &Settings) -> Report {
+        let initial_cost = self.cost();
+        let mut cost = initial_cost;
+        let mut lambda = settings.damping;
+        let mut converged = false;
+        let mut iterations = 0;
+
+        while iterations < settings.max_iterations {
+            iterations += 1;
+            let (mut h, g) = self.normal_equations();
+            h.add_diagonal(lambda);
+            let rhs: Vec<BlockVector> = g.iter().map(|g| -g).collect();
+            let (d, _) = h.solve(&rhs, settings.solver_iterations, settings.solver_tolerance);
+
+            let updated: Vec<Motor> = self
+                .motors
+                .iter()
+                .zip(d.iter())
+                .zip(self.fixed.iter())
+                .map(|((m, d), fixed)| {
+                    if *fixed {
+                        *m
+                    } else {
+                        retract(m, &[d[0], d[1], d[2], d[3], d[4], d[5]])
+                    }
+                })
+                .collect();
+            let new_cost = Self::cost_of(&self.factors, &updated);
+
+            if new_cost <= cost {
+                self.motors = updated;
+                let decrease = (cost - new_cost) / cost.max(f32::MIN_POSITIVE);
+                cost = new_cost;
+                lambda = (lambda * 0.1).max(1e-10);
+                let step = d.iter().map(|d| d.amax()).fold(0.0, f32::max);
+                if decrease < settings.tolerance || step < settings.tolerance {
+                    converged = true;
+                    break;
+                }
+            } else {
+                // No step decreases the cost any more, give up.
+                lambda *= 10.;
+                if lambda > 1e10 {
+                    break;
+                }
+            }
+        }
+
+        Report {
+            iterations,
+            initial_cost,
+            final_cost: cost,
+            converged,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pose_graph::*;
+    use crate::*;
+
+    fn ground_truth(n: usize) -> Vec<Motor> {
+        (0..n)
+            .map(|i| {
+                let a = i as f32 * 0.3;
+                let t = Translator::new(&[a.cos() * 5., a.sin() * 5., 0.2 * a]);
+                let r = Rotor::new(a, &[0., 0., 1.]);
+                t.mul_rotor(&r).normalize()
+            })
+            .collect()
+    }
+
+    fn perturbed(m: &Motor, s: f32) -> Motor {
+        let d = Line::random().mul_scalar(s);
+        d.exp().mul(m).normalize()
+    }
+
+    fn local(m: &Motor, x: &[f32; 3]) -> Point {
+        m.reverse().apply_to_point(&Point::new(x))
+    }
+
+    #[test]
+    fn point_to_point() {
+        let truth = ground_truth(20);
+        let landmarks: Vec<[f32; 3]> = (0..60)
+            .map(|i| {
+                let a = i as f32 * 0.1;
+                [a.cos() * 7., a.sin() * 7., (i % 5) as f32 - 2.]
+            })
+            .collect();
+
+        let mut graph = PoseGraph::new();
+        for (i, m) in truth.iter().enumerate() {
+            if i == 0 {
+                let k = graph.add_motor(*m);
+                graph.fix(k);
+            } else {
+                graph.add_motor(perturbed(m, 0.05));
+            }
+        }
+        for i in 0..truth.len() - 1 {
+            for j in [i + 1, (i + 2).min(truth.len() - 1)].iter() {
+                for x in landmarks.iter().skip(i * 2).take(12) {
+                    let a = local(&truth[i], x);
+                    let b = local(&truth[*j], x);
+                    graph.add_factor(i, *j, Residual::PointToPoint(a, b), Loss::Trivial);
+                }
+            }
+        }
+
+        let report = graph.optimize(&Settings::default());
+        assert!(report.final_cost < report.initial_cost);
+        assert!(report.final_cost < 1e-4);
+        let p = Point::new(&[1., -2., 0.5]);
+        for (m, t) in graph.motors.iter().zip(truth.iter()) {
+            assert_eq!(m.apply_to_point(&p), t.apply_to_point(&p));
+        }
+    }
+
+    #[test]
+    fn planes_and_lines() {
+        let truth = ground_truth(2);
+        let mut graph = PoseGraph::new();
+        let k = graph.add_motor(truth[0]);
+        graph.fix(k);
+        graph.add_motor(perturbed(&truth[1], 0.05));
+
+        let planes = [
+            Plane::new(1., &[1., 0., 0.]),
+            Plane::new(-2., &[0., 1., 0.]),
+            Plane::new(0.5, &[0., 0., 1.]),
+            Plane::new(3., &na::Vec3::new(1., 1., 1.).normalize().into()),
+        ];
+        for p in planes.iter() {
+            for x in [[1., 2., 3.], [-1., 0.5, 2.], [0., -2., 1.]].iter() {
+                // Project x onto the world plane, then express both in the local frames.
+                let n = [p.vector[1], p.vector[2], p.vector[3]];
+                let s = p.vector[0] - (n[0] * x[0] + n[1] * x[1] + n[2] * x[2]);
+                let on = [x[0] + s * n[0], x[1] + s * n[1], x[2] + s * n[2]];
+                let a = local(&truth[1], &on);
+                let b = truth[0].reverse().apply_to_plane(p);
+                graph.add_factor(1, 0, Residual::PointToPlane(a, b), Loss::Trivial);
+            }
+        }
+        for (x, d) in [([0., 0., 0.], [1., 0., 0.]), ([1., 2., 0.], [0., 0.6, 0.8])].iter() {
+            let l = Line::new(x, d);
+            let a = truth[1].reverse().apply_to_line(&l);
+            let b = truth[0].reverse().apply_to_line(&l);
+            graph.add_factor(1, 0, Residual::LineToLine(a, b), Loss::Trivial);
+        }
+
+        let report = graph.optimize(&Settings::default());
+        assert!(report.final_cost < 1e-4);
+        let p = Point::new(&[1., -2., 0.5]);
+        assert_eq!(
+            graph.motors[1].apply_to_point(&p),
+            truth[1].apply_to_point(&p)
+        );
+    }
+
+    #[test]
+    fn robust_loss() {
+        let truth = ground_truth(2);
+        let mut graph = PoseGraph::new();
+        let k = graph.add_motor(truth[0]);
+        graph.fix(k);
+        graph.add_motor(perturbed(&truth[1], 0.05));
+        for i in 0..30 {
+            let a = i as f32;
+            let x = [a.cos() * 3., a.sin() * 2., (a * 0.7).cos()];
+            let mut b = local(&truth[0], &x);
+            if i % 10 == 0 {
+                // outlier
+                b = local(&truth[0], &[x[0] + 4., x[1] - 3., x[2]]);
+            }
+            graph.add_factor(
+                1,
+                0,
+                Residual::PointToPoint(local(&truth[1], &x), b),
+                Loss::Cauchy(0.1),
+            );
+        }
+        graph.optimize(&Settings::default());
+        let p = Point::new(&[1., -2., 0.5]);
+        assert_eq!(
+            graph.motors[1].apply_to_point(&p),
+            truth[1].apply_to_point(&p)
+        );
+    }
+
+    #[test]
+    fn losses() {
+        for loss in [Loss::Trivial, Loss::Huber(0.5), Loss::Cauchy(0.5)].iter() {
+            // The weight is cost'(s) / s.
+            let s = 1.3;
+            let h = 1e-3;
+            let d = (loss.cost(s + h) - loss.cost(s - h)) / (2. * h);
+            assert!((d / s - loss.weight(s)).abs() < 1e-2);
+        }
+    }
+}
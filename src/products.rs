@@ -0,0 +1,210 @@
+//! The products of the types with each other, generated by the build
+//! script from the Cayley table (see codegen). The methods on the types,
+//! meet, join and inner call these.
+
+use crate::{Line, Motor, Plane, Point, PseudoScalar, Rotor, Translator};
+
+include!(concat!(env!("OUT_DIR"), "/products.rs"));
+
+#[cfg(test)]
+mod reference;
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    // Small integers keep every operation exact, so the generated and the
+    // hand-written functions agree bit for bit whatever order they
+    // evaluate in, exactly when they are the same polynomials.
+    fn int() -> f32 {
+        (rand::random::<u8>() % 17) as f32 - 8.
+    }
+    fn ints<const N: usize>() -> [f32; N] {
+        let mut x = [0.; N];
+        x.iter_mut().for_each(|x| *x = int());
+        x
+    }
+
+    fn motor() -> Motor {
+        Motor {
+            scalar: int(),
+            pseudo: int(),
+            e_bivector: ints(),
+            v_bivector: ints(),
+        }
+    }
+    fn rotor() -> Rotor {
+        Rotor {
+            scalar: int(),
+            e_bivector: ints(),
+        }
+    }
+    fn translator() -> Translator {
+        Translator {
+            scalar: int(),
+            v_bivector: ints(),
+        }
+    }
+    fn line() -> Line {
+        Line::from_tangent(&ints())
+    }
+    fn point() -> Point {
+        Point { trivector: ints() }
+    }
+    fn plane() -> Plane {
+        Plane { vector: ints() }
+    }
+
+    fn same<A, B, R: std::fmt::Debug>(
+        f: fn(&A, &B) -> R,
+        g: fn(&A, &B) -> R,
+        a: fn() -> A,
+        b: fn() -> B,
+        parts: fn(&R) -> Vec<f32>,
+    ) {
+        for _ in 0..1000 {
+            let (a, b) = (a(), b());
+            let (r, s) = (f(&a, &b), g(&a, &b));
+            assert_eq!(parts(&r), parts(&s), "{:?} != {:?}", r, s);
+        }
+    }
+
+    fn motor_parts(m: &Motor) -> Vec<f32> {
+        let k = m.into_klein();
+        k[0].iter().chain(k[1].iter()).copied().collect()
+    }
+    fn translator_parts(t: &Translator) -> Vec<f32> {
+        vec![t.scalar, t.v_bivector[0], t.v_bivector[1], t.v_bivector[2]]
+    }
+
+    #[test]
+    fn identical() {
+        use crate::products::*;
+        same(
+            geometric_motor_motor,
+            reference::geometric_motor_motor,
+            motor,
+            motor,
+            motor_parts,
+        );
+        same(
+            geometric_motor_translator,
+            reference::geometric_motor_translator,
+            motor,
+            translator,
+            motor_parts,
+        );
+        same(
+            geometric_rotor_translator,
+            reference::geometric_rotor_translator,
+            rotor,
+            translator,
+            motor_parts,
+        );
+        same(
+            geometric_translator_rotor,
+            reference::geometric_translator_rotor,
+            translator,
+            rotor,
+            motor_parts,
+        );
+        same(
+            geometric_line_line,
+            reference::geometric_line_line,
+            line,
+            line,
+            motor_parts,
+        );
+        same(
+            geometric_plane_plane,
+            reference::geometric_plane_plane,
+            plane,
+            plane,
+            motor_parts,
+        );
+        same(
+            geometric_point_point,
+            reference::geometric_point_point,
+            point,
+            point,
+            translator_parts,
+        );
+        same(
+            sandwich_motor_point,
+            reference::sandwich_motor_point,
+            motor,
+            point,
+            |p| p.trivector.to_vec(),
+        );
+        same(
+            sandwich_motor_plane,
+            reference::sandwich_motor_plane,
+            motor,
+            plane,
+            |p| p.vector.to_vec(),
+        );
+        same(
+            sandwich_motor_line,
+            reference::sandwich_motor_line,
+            motor,
+            line,
+            |l| l.tangent().to_vec(),
+        );
+        same(
+            sandwich_rotor_point,
+            reference::sandwich_rotor_point,
+            rotor,
+            point,
+            |p| p.trivector.to_vec(),
+        );
+        same(
+            sandwich_translator_point,
+            reference::sandwich_translator_point,
+            translator,
+            point,
+            |p| p.trivector.to_vec(),
+        );
+        same(
+            outer_plane_plane,
+            reference::outer_plane_plane,
+            plane,
+            plane,
+            |l| l.tangent().to_vec(),
+        );
+        same(
+            outer_plane_line,
+            reference::outer_plane_line,
+            plane,
+            line,
+            |p| p.trivector.to_vec(),
+        );
+        same(
+            outer_line_line,
+            reference::outer_line_line,
+            line,
+            line,
+            |p| vec![p.0],
+        );
+        same(
+            regressive_point_point,
+            reference::regressive_point_point,
+            point,
+            point,
+            |l| l.tangent().to_vec(),
+        );
+        same(
+            regressive_line_point,
+            reference::regressive_line_point,
+            line,
+            point,
+            |p| p.vector.to_vec(),
+        );
+        same(
+            inner_line_line,
+            reference::inner_line_line,
+            line,
+            line,
+            |x| vec![*x],
+        );
+    }
+}
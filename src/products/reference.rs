@@ -0,0 +1,458 @@
+//! The hand-written products the generated ones replaced, as produced
+//! by generate/*.py, to test that the generated ones are identical.
+
+use crate::*;
+
+pub fn geometric_motor_motor(a: &Motor, other: &Motor) -> Motor {
+    let s1 = a.scalar;
+    let s2 = other.scalar;
+    let ps1 = a.pseudo;
+    let ps2 = other.pseudo;
+    let v1 = a.v_bivector;
+    let v2 = other.v_bivector;
+    let e1 = a.e_bivector;
+    let e2 = other.e_bivector;
+    Motor {
+        scalar: -e1[0] * e2[0] - e1[1] * e2[1] - e1[2] * e2[2] + s1 * s2,
+        pseudo: e1[0] * v2[0]
+            + e1[1] * v2[1]
+            + e1[2] * v2[2]
+            + e2[0] * v1[0]
+            + e2[1] * v1[1]
+            + e2[2] * v1[2]
+            + ps1 * s2
+            + ps2 * s1,
+        v_bivector: [
+            -e1[0] * ps2 - e1[1] * v2[2] + e1[2] * v2[1] - e2[0] * ps1 + e2[1] * v1[2]
+                - e2[2] * v1[1]
+                + s1 * v2[0]
+                + s2 * v1[0],
+            e1[0] * v2[2] - e1[1] * ps2 - e1[2] * v2[0] - e2[0] * v1[2] - e2[1] * ps1
+                + e2[2] * v1[0]
+                + s1 * v2[1]
+                + s2 * v1[1],
+            -e1[0] * v2[1] + e1[1] * v2[0] - e1[2] * ps2 + e2[0] * v1[1]
+                - e2[1] * v1[0]
+                - e2[2] * ps1
+                + s1 * v2[2]
+                + s2 * v1[2],
+        ],
+        e_bivector: [
+            e1[0] * s2 - e1[1] * e2[2] + e1[2] * e2[1] + e2[0] * s1,
+            e1[0] * e2[2] + e1[1] * s2 - e1[2] * e2[0] + e2[1] * s1,
+            -e1[0] * e2[1] + e1[1] * e2[0] + e1[2] * s2 + e2[2] * s1,
+        ],
+    }
+}
+
+pub fn geometric_motor_translator(a: &Motor, t: &Translator) -> Motor {
+    let ts = t.scalar;
+    let tv = t.v_bivector;
+    let ms = a.scalar;
+    let mps = a.pseudo;
+    let mv = a.v_bivector;
+    let me = a.e_bivector;
+    Motor {
+        scalar: ms * ts,
+        pseudo: me[0] * tv[0] + me[1] * tv[1] + me[2] * tv[2] + mps * ts,
+        v_bivector: [
+            -me[1] * tv[2] + me[2] * tv[1] + ms * tv[0] + mv[0] * ts,
+            me[0] * tv[2] - me[2] * tv[0] + ms * tv[1] + mv[1] * ts,
+            -me[0] * tv[1] + me[1] * tv[0] + ms * tv[2] + mv[2] * ts,
+        ],
+        e_bivector: [me[0] * ts, me[1] * ts, me[2] * ts],
+    }
+}
+
+pub fn geometric_rotor_translator(a: &Rotor, t: &Translator) -> Motor {
+    let ts = t.scalar;
+    let tv = t.v_bivector;
+    let rs = a.scalar;
+    let re = a.e_bivector;
+    Motor {
+        scalar: rs * ts,
+        pseudo: re[0] * tv[0] + re[1] * tv[1] + re[2] * tv[2],
+        e_bivector: [re[0] * ts, re[1] * ts, re[2] * ts],
+        v_bivector: [
+            -re[1] * tv[2] + re[2] * tv[1] + rs * tv[0],
+            re[0] * tv[2] - re[2] * tv[0] + rs * tv[1],
+            -re[0] * tv[1] + re[1] * tv[0] + rs * tv[2],
+        ],
+    }
+}
+
+pub fn geometric_translator_rotor(a: &Translator, r: &Rotor) -> Motor {
+    let v = a.v_bivector;
+    let e = r.e_bivector;
+    let ts = a.scalar;
+    let rs = r.scalar;
+    Motor {
+        scalar: rs * ts,
+        pseudo: e[0] * v[0] + e[1] * v[1] + e[2] * v[2],
+        e_bivector: [e[0] * ts, e[1] * ts, e[2] * ts],
+        v_bivector: [
+            e[1] * v[2] - e[2] * v[1] + rs * v[0],
+            -e[0] * v[2] + e[2] * v[0] + rs * v[1],
+            e[0] * v[1] - e[1] * v[0] + rs * v[2],
+        ],
+    }
+}
+
+pub fn geometric_line_line(a: &Line, other: &Line) -> Motor {
+    let e1 = a.e_bivector;
+    let v1 = a.v_bivector;
+    let e2 = other.e_bivector;
+    let v2 = other.v_bivector;
+    Motor {
+        scalar: -e1[0] * e2[0] - e1[1] * e2[1] - e1[2] * e2[2],
+        v_bivector: [
+            -e1[1] * v2[2] + e1[2] * v2[1] + e2[1] * v1[2] - e2[2] * v1[1],
+            e1[0] * v2[2] - e1[2] * v2[0] - e2[0] * v1[2] + e2[2] * v1[0],
+            -e1[0] * v2[1] + e1[1] * v2[0] + e2[0] * v1[1] - e2[1] * v1[0],
+        ],
+        e_bivector: [
+            -e1[1] * e2[2] + e1[2] * e2[1],
+            e1[0] * e2[2] - e1[2] * e2[0],
+            -e1[0] * e2[1] + e1[1] * e2[0],
+        ],
+        pseudo: e1[0] * v2[0]
+            + e1[1] * v2[1]
+            + e1[2] * v2[2]
+            + e2[0] * v1[0]
+            + e2[1] * v1[1]
+            + e2[2] * v1[2],
+    }
+}
+
+pub fn geometric_plane_plane(a: &Plane, other: &Plane) -> Motor {
+    let v1 = a.vector;
+    let v2 = other.vector;
+    Motor {
+        scalar: v1[1] * v2[1] + v1[2] * v2[2] + v1[3] * v2[3],
+        v_bivector: [
+            v1[0] * v2[1] - v1[1] * v2[0],
+            v1[0] * v2[2] - v1[2] * v2[0],
+            v1[0] * v2[3] - v1[3] * v2[0],
+        ],
+        e_bivector: [
+            v1[2] * v2[3] - v1[3] * v2[2],
+            -v1[1] * v2[3] + v1[3] * v2[1],
+            v1[1] * v2[2] - v1[2] * v2[1],
+        ],
+        pseudo: 0.,
+    }
+}
+
+pub fn geometric_point_point(a: &Point, other: &Point) -> Translator {
+    let p1 = a.trivector;
+    let p2 = other.trivector;
+    Translator {
+        scalar: -p1[0] * p2[0],
+        v_bivector: [
+            -p1[0] * p2[1] + p1[1] * p2[0],
+            -p1[0] * p2[2] + p1[2] * p2[0],
+            -p1[0] * p2[3] + p1[3] * p2[0],
+        ],
+    }
+}
+
+pub fn sandwich_motor_point(a: &Motor, p: &Point) -> Point {
+    let ms = a.scalar;
+    let mps = a.pseudo;
+    let mv = a.v_bivector;
+    let me = a.e_bivector;
+    let p = p.trivector;
+    Point {
+        trivector: [
+            p[0] * (me[0] * me[0] + me[1] * me[1] + me[2] * me[2] + ms * ms),
+            me[0] * me[0] * p[1] + 2. * me[0] * me[1] * p[2] + 2. * me[0] * me[2] * p[3]
+                - 2. * me[0] * mps * p[0]
+                - me[1] * me[1] * p[1]
+                - 2. * me[1] * ms * p[3]
+                + 2. * me[1] * mv[2] * p[0]
+                - me[2] * me[2] * p[1]
+                + 2. * me[2] * ms * p[2]
+                - 2. * me[2] * mv[1] * p[0]
+                + ms * ms * p[1]
+                - 2. * ms * mv[0] * p[0],
+            -me[0] * me[0] * p[2] + 2. * me[0] * me[1] * p[1] + 2. * me[0] * ms * p[3]
+                - 2. * me[0] * mv[2] * p[0]
+                + me[1] * me[1] * p[2]
+                + 2. * me[1] * me[2] * p[3]
+                - 2. * me[1] * mps * p[0]
+                - me[2] * me[2] * p[2]
+                - 2. * me[2] * ms * p[1]
+                + 2. * me[2] * mv[0] * p[0]
+                + ms * ms * p[2]
+                - 2. * ms * mv[1] * p[0],
+            -me[0] * me[0] * p[3] + 2. * me[0] * me[2] * p[1] - 2. * me[0] * ms * p[2]
+                + 2. * me[0] * mv[1] * p[0]
+                - me[1] * me[1] * p[3]
+                + 2. * me[1] * me[2] * p[2]
+                + 2. * me[1] * ms * p[1]
+                - 2. * me[1] * mv[0] * p[0]
+                + me[2] * me[2] * p[3]
+                - 2. * me[2] * mps * p[0]
+                + ms * ms * p[3]
+                - 2. * ms * mv[2] * p[0],
+        ],
+    }
+}
+
+pub fn sandwich_motor_plane(a: &Motor, p: &Plane) -> Plane {
+    let pvec = p.vector;
+    let ms = a.scalar;
+    let mps = a.pseudo;
+    let mv = a.v_bivector;
+    let me = a.e_bivector;
+    Plane {
+        vector: [
+            me[0] * me[0] * pvec[0] + 2. * me[0] * mps * pvec[1] + 2. * me[0] * mv[1] * pvec[3]
+                - 2. * me[0] * mv[2] * pvec[2]
+                + me[1] * me[1] * pvec[0]
+                + 2. * me[1] * mps * pvec[2]
+                - 2. * me[1] * mv[0] * pvec[3]
+                + 2. * me[1] * mv[2] * pvec[1]
+                + me[2] * me[2] * pvec[0]
+                + 2. * me[2] * mps * pvec[3]
+                + 2. * me[2] * mv[0] * pvec[2]
+                - 2. * me[2] * mv[1] * pvec[1]
+                + ms * ms * pvec[0]
+                + 2. * ms * mv[0] * pvec[1]
+                + 2. * ms * mv[1] * pvec[2]
+                + 2. * ms * mv[2] * pvec[3],
+            me[0] * me[0] * pvec[1] + 2. * me[0] * me[1] * pvec[2] + 2. * me[0] * me[2] * pvec[3]
+                - me[1] * me[1] * pvec[1]
+                - 2. * me[1] * ms * pvec[3]
+                - me[2] * me[2] * pvec[1]
+                + 2. * me[2] * ms * pvec[2]
+                + ms * ms * pvec[1],
+            -me[0] * me[0] * pvec[2]
+                + 2. * me[0] * me[1] * pvec[1]
+                + 2. * me[0] * ms * pvec[3]
+                + me[1] * me[1] * pvec[2]
+                + 2. * me[1] * me[2] * pvec[3]
+                - me[2] * me[2] * pvec[2]
+                - 2. * me[2] * ms * pvec[1]
+                + ms * ms * pvec[2],
+            -me[0] * me[0] * pvec[3] + 2. * me[0] * me[2] * pvec[1]
+                - 2. * me[0] * ms * pvec[2]
+                - me[1] * me[1] * pvec[3]
+                + 2. * me[1] * me[2] * pvec[2]
+                + 2. * me[1] * ms * pvec[1]
+                + me[2] * me[2] * pvec[3]
+                + ms * ms * pvec[3],
+        ],
+    }
+}
+
+pub fn sandwich_motor_line(a: &Motor, l: &Line) -> Line {
+    let ms = a.scalar;
+    let mps = a.pseudo;
+    let mv = a.v_bivector;
+    let me = a.e_bivector;
+    let le = l.e_bivector;
+    let lv = l.v_bivector;
+    Line {
+        e_bivector: [
+            le[0] * me[0] * me[0] - le[0] * me[1] * me[1] - le[0] * me[2] * me[2]
+                + le[0] * ms * ms
+                + 2. * le[1] * me[0] * me[1]
+                + 2. * le[1] * me[2] * ms
+                + 2. * le[2] * me[0] * me[2]
+                - 2. * le[2] * me[1] * ms,
+            2. * le[0] * me[0] * me[1] - 2. * le[0] * me[2] * ms - le[1] * me[0] * me[0]
+                + le[1] * me[1] * me[1]
+                - le[1] * me[2] * me[2]
+                + le[1] * ms * ms
+                + 2. * le[2] * me[0] * ms
+                + 2. * le[2] * me[1] * me[2],
+            2. * le[0] * me[0] * me[2] + 2. * le[0] * me[1] * ms - 2. * le[1] * me[0] * ms
+                + 2. * le[1] * me[1] * me[2]
+                - le[2] * me[0] * me[0]
+                - le[2] * me[1] * me[1]
+                + le[2] * me[2] * me[2]
+                + le[2] * ms * ms,
+        ],
+        v_bivector: [
+            2. * le[0] * me[0] * mv[0]
+                - 2. * le[0] * me[1] * mv[1]
+                - 2. * le[0] * me[2] * mv[2]
+                - 2. * le[0] * mps * ms
+                + 2. * le[1] * me[0] * mv[1]
+                + 2. * le[1] * me[1] * mv[0]
+                - 2. * le[1] * me[2] * mps
+                + 2. * le[1] * ms * mv[2]
+                + 2. * le[2] * me[0] * mv[2]
+                + 2. * le[2] * me[1] * mps
+                + 2. * le[2] * me[2] * mv[0]
+                - 2. * le[2] * ms * mv[1]
+                + lv[0] * me[0] * me[0]
+                - lv[0] * me[1] * me[1]
+                - lv[0] * me[2] * me[2]
+                + lv[0] * ms * ms
+                + 2. * lv[1] * me[0] * me[1]
+                + 2. * lv[1] * me[2] * ms
+                + 2. * lv[2] * me[0] * me[2]
+                - 2. * lv[2] * me[1] * ms,
+            2. * le[0] * me[0] * mv[1] + 2. * le[0] * me[1] * mv[0] + 2. * le[0] * me[2] * mps
+                - 2. * le[0] * ms * mv[2]
+                - 2. * le[1] * me[0] * mv[0]
+                + 2. * le[1] * me[1] * mv[1]
+                - 2. * le[1] * me[2] * mv[2]
+                - 2. * le[1] * mps * ms
+                - 2. * le[2] * me[0] * mps
+                + 2. * le[2] * me[1] * mv[2]
+                + 2. * le[2] * me[2] * mv[1]
+                + 2. * le[2] * ms * mv[0]
+                + 2. * lv[0] * me[0] * me[1]
+                - 2. * lv[0] * me[2] * ms
+                - lv[1] * me[0] * me[0]
+                + lv[1] * me[1] * me[1]
+                - lv[1] * me[2] * me[2]
+                + lv[1] * ms * ms
+                + 2. * lv[2] * me[0] * ms
+                + 2. * lv[2] * me[1] * me[2],
+            2. * le[0] * me[0] * mv[2] - 2. * le[0] * me[1] * mps
+                + 2. * le[0] * me[2] * mv[0]
+                + 2. * le[0] * ms * mv[1]
+                + 2. * le[1] * me[0] * mps
+                + 2. * le[1] * me[1] * mv[2]
+                + 2. * le[1] * me[2] * mv[1]
+                - 2. * le[1] * ms * mv[0]
+                - 2. * le[2] * me[0] * mv[0]
+                - 2. * le[2] * me[1] * mv[1]
+                + 2. * le[2] * me[2] * mv[2]
+                - 2. * le[2] * mps * ms
+                + 2. * lv[0] * me[0] * me[2]
+                + 2. * lv[0] * me[1] * ms
+                - 2. * lv[1] * me[0] * ms
+                + 2. * lv[1] * me[1] * me[2]
+                - lv[2] * me[0] * me[0]
+                - lv[2] * me[1] * me[1]
+                + lv[2] * me[2] * me[2]
+                + lv[2] * ms * ms,
+        ],
+    }
+}
+
+pub fn sandwich_rotor_point(a: &Rotor, p: &Point) -> Point {
+    let p = p.trivector;
+    let e = a.e_bivector;
+    let s = a.scalar;
+
+    Point {
+        trivector: [
+            e[0] * e[0] * p[0] + e[1] * e[1] * p[0] + e[2] * e[2] * p[0] + p[0] * s * s,
+            e[0] * (e[0] * p[1] + e[1] * p[2] + e[2] * p[3])
+                - e[1] * (-e[0] * p[2] + e[1] * p[1] + p[3] * s)
+                + e[2] * (e[0] * p[3] - e[2] * p[1] + p[2] * s)
+                + s * (-e[1] * p[3] + e[2] * p[2] + p[1] * s),
+            e[0] * (-e[0] * p[2] + e[1] * p[1] + p[3] * s)
+                + e[1] * (e[0] * p[1] + e[1] * p[2] + e[2] * p[3])
+                - e[2] * (-e[1] * p[3] + e[2] * p[2] + p[1] * s)
+                + s * (e[0] * p[3] - e[2] * p[1] + p[2] * s),
+            -e[0] * (e[0] * p[3] - e[2] * p[1] + p[2] * s)
+                + e[1] * (-e[1] * p[3] + e[2] * p[2] + p[1] * s)
+                + e[2] * (e[0] * p[1] + e[1] * p[2] + e[2] * p[3])
+                + s * (-e[0] * p[2] + e[1] * p[1] + p[3] * s),
+        ],
+    }
+}
+
+pub fn sandwich_translator_point(a: &Translator, p: &Point) -> Point {
+    let v = a.v_bivector;
+    let s = a.scalar;
+    let p = p.trivector;
+    Point {
+        trivector: [
+            p[0] * s * s,
+            s * (-2. * p[0] * v[0] + p[1] * s),
+            s * (-2. * p[0] * v[1] + p[2] * s),
+            s * (-2. * p[0] * v[2] + p[3] * s),
+        ],
+    }
+}
+
+pub fn outer_plane_plane(p1: &Plane, p2: &Plane) -> Line {
+    Line {
+        v_bivector: [
+            p1.vector[0] * p2.vector[1] - p1.vector[1] * p2.vector[0],
+            p1.vector[0] * p2.vector[2] - p1.vector[2] * p2.vector[0],
+            p1.vector[0] * p2.vector[3] - p1.vector[3] * p2.vector[0],
+        ],
+        e_bivector: [
+            p1.vector[2] * p2.vector[3] - p1.vector[3] * p2.vector[2],
+            p1.vector[3] * p2.vector[1] - p1.vector[1] * p2.vector[3],
+            p1.vector[1] * p2.vector[2] - p1.vector[2] * p2.vector[1],
+        ],
+    }
+}
+
+pub fn outer_plane_line(p: &Plane, l: &Line) -> Point {
+    Point {
+        trivector: [
+            p.vector[1] * l.e_bivector[0]
+                + p.vector[2] * l.e_bivector[1]
+                + p.vector[3] * l.e_bivector[2],
+            -p.vector[0] * l.e_bivector[0] + p.vector[2] * l.v_bivector[2]
+                - p.vector[3] * l.v_bivector[1],
+            -p.vector[0] * l.e_bivector[1] - p.vector[1] * l.v_bivector[2]
+                + p.vector[3] * l.v_bivector[0],
+            -p.vector[0] * l.e_bivector[2] + p.vector[1] * l.v_bivector[1]
+                - p.vector[2] * l.v_bivector[0],
+        ],
+    }
+}
+
+pub fn outer_line_line(b1: &Line, b2: &Line) -> PseudoScalar {
+    let e1 = b1.e_bivector;
+    let e2 = b2.e_bivector;
+    let v1 = b1.v_bivector;
+    let v2 = b2.v_bivector;
+    PseudoScalar(
+        e1[0] * v2[0]
+            + e1[1] * v2[1]
+            + e1[2] * v2[2]
+            + e2[0] * v1[0]
+            + e2[1] * v1[1]
+            + e2[2] * v1[2],
+    )
+}
+
+pub fn regressive_point_point(p1: &Point, p2: &Point) -> Line {
+    let t1 = p1.trivector;
+    let t2 = p2.trivector;
+    Line {
+        v_bivector: [
+            -t1[2] * t2[3] + t1[3] * t2[2],
+            t1[1] * t2[3] - t1[3] * t2[1],
+            -t1[1] * t2[2] + t1[2] * t2[1],
+        ],
+        e_bivector: [
+            -t1[0] * t2[1] + t1[1] * t2[0],
+            -t1[0] * t2[2] + t1[2] * t2[0],
+            -t1[0] * t2[3] + t1[3] * t2[0],
+        ],
+    }
+}
+
+pub fn regressive_line_point(l: &Line, p: &Point) -> Plane {
+    let t1 = p.trivector;
+    let vb = l.v_bivector;
+    let eb = l.e_bivector;
+    Plane {
+        vector: [
+            -t1[1] * vb[0] - t1[2] * vb[1] - t1[3] * vb[2],
+            eb[1] * t1[3] - eb[2] * t1[2] + t1[0] * vb[0],
+            -eb[0] * t1[3] + eb[2] * t1[1] + t1[0] * vb[1],
+            eb[0] * t1[2] - eb[1] * t1[1] + t1[0] * vb[2],
+        ],
+    }
+}
+
+pub fn inner_line_line(l1: &Line, l2: &Line) -> f32 {
+    let e1 = l1.e_bivector;
+    let e2 = l2.e_bivector;
+    -e1[0] * e2[0] - e1[1] * e2[1] - e1[2] * e2[2]
+}
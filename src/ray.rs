@@ -0,0 +1,284 @@
+//! Rays for picking and collision probes. A ray is an origin and a
+//! direction, the point at infinity it runs to. Hits with anything flat
+//! are the meet of the plane with the ray's line.
+
+use super::clip::Mesh;
+use super::polyhedron::ConvexPolyhedron;
+use super::{join, meet, Line, Plane, Point};
+
+pub const EPSILON: f32 = 1e-6;
+
+#[derive(Debug)]
+pub struct Ray {
+    pub origin: Point,
+    /// Normalized, i.e., a Point::inf with unit euclidean part.
+    pub direction: Point,
+}
+
+#[derive(Debug)]
+pub struct Hit {
+    pub point: Point,
+    /// Distance from the origin along the direction.
+    pub t: f32,
+    /// The plane touching the surface at the hit, with outward normal.
+    pub normal: Plane,
+}
+
+impl Ray {
+    pub fn new(origin: &[f32; 3], direction: &[f32; 3]) -> Self {
+        let d = na::Vec3::from(*direction).normalize();
+        Self {
+            origin: Point::new(origin),
+            direction: Point::inf(&d.into()),
+        }
+    }
+
+    /// Along the line, starting at start from the point closest to the
+    /// world origin.
+    pub fn from_line(l: &Line, start: f32) -> Self {
+        let n = na::Vec3::from(l.e_bivector).norm();
+        let d = na::Vec3::from(l.e_bivector) / n;
+        let v = na::Vec3::from(l.v_bivector) / n;
+        Self::new(&(v.cross(&d) + d * start).into(), &d.into())
+    }
+
+    pub fn dir(&self) -> na::Vec3 {
+        na::Vec3::from(self.direction.eucl())
+    }
+    fn start(&self) -> na::Vec3 {
+        na::Vec3::from(self.origin.normalize().eucl())
+    }
+
+    /// The line through the origin in the direction, see Line::new.
+    pub fn line(&self) -> Line {
+        join::points(&self.origin.normalize(), &self.direction)
+    }
+
+    pub fn at(&self, t: f32) -> Point {
+        Point::new(&(self.start() + self.dir() * t).into())
+    }
+
+    fn param(&self, p: &Point) -> f32 {
+        (na::Vec3::from(p.eucl()) - self.start()).dot(&self.dir())
+    }
+
+    /// The meet with the plane and its distance, None when parallel.
+    fn meet(&self, plane: &Plane) -> Option<(Point, f32)> {
+        let p = meet::plane_with_line(plane, &self.line());
+        if p.trivector[0].abs() < EPSILON * plane.norm() {
+            return None;
+        }
+        let p = p.normalize();
+        let t = self.param(&p);
+        Some((p, t))
+    }
+
+    /// Hits from both sides, the normal faces the ray.
+    pub fn plane(&self, plane: &Plane) -> Option<Hit> {
+        let (point, t) = self.meet(plane)?;
+        if t < 0.0 {
+            return None;
+        }
+        let plane = plane.normalize();
+        let normal = if plane.distance(&self.origin) < 0.0 {
+            plane.neg()
+        } else {
+            plane
+        };
+        Some(Hit { point, t, normal })
+    }
+
+    /// Two sided. The ray passes through the triangle if it winds the same
+    /// way around all three edges, i.e., the meets of its line with the
+    /// edge lines have the same sign.
+    pub fn triangle(&self, a: &Point, b: &Point, c: &Point) -> Option<Hit> {
+        let l = self.line();
+        let s = [
+            meet::lines(&l, &join::points(a, b)).0,
+            meet::lines(&l, &join::points(b, c)).0,
+            meet::lines(&l, &join::points(c, a)).0,
+        ];
+        let positive = s.iter().all(|s| *s >= 0.0);
+        let negative = s.iter().all(|s| *s <= 0.0);
+        if !positive && !negative {
+            return None;
+        }
+        self.plane(&join::three_points(a, b, c))
+    }
+
+    pub fn sphere(&self, center: &Point, radius: f32) -> Option<Hit> {
+        let c = na::Vec3::from(center.normalize().eucl());
+        let o = self.start() - c;
+        let d = self.dir();
+        let b = o.dot(&d);
+        let disc = b * b - (o.dot(&o) - radius * radius);
+        if disc < 0.0 {
+            return None;
+        }
+        let sq = disc.sqrt();
+        let t = if -b - sq >= 0.0 { -b - sq } else { -b + sq };
+        if t < 0.0 {
+            return None;
+        }
+        let point = self.at(t);
+        let n = (na::Vec3::from(point.eucl()) - c) / radius;
+        let normal = Plane::new(n.dot(&(c + n * radius)), &n.into());
+        Some(Hit { point, t, normal })
+    }
+
+    /// The axis aligned box between min and max.
+    pub fn aabb(&self, min: &[f32; 3], max: &[f32; 3]) -> Option<Hit> {
+        self.polyhedron(&ConvexPolyhedron::cuboid(min, max))
+    }
+
+    /// The first hit with the convex volume bounded by the planes (with
+    /// outward normals, as in polyhedron), or where the ray leaves it if
+    /// it starts inside. Cyrus-Beck on the meets with every plane.
+    pub fn planes(&self, planes: &[Plane]) -> Option<Hit> {
+        let d = self.dir();
+        let (mut enter, mut leave) = ((f32::NEG_INFINITY, 0), (f32::INFINITY, 0));
+        for (i, plane) in planes.iter().enumerate() {
+            let n = na::Vec3::new(plane.vector[1], plane.vector[2], plane.vector[3]);
+            match self.meet(plane) {
+                None if plane.distance(&self.origin) > 0.0 => return None,
+                None => {}
+                Some((_, t)) if n.dot(&d) < 0.0 && t > enter.0 => enter = (t, i),
+                Some((_, t)) if n.dot(&d) >= 0.0 && t < leave.0 => leave = (t, i),
+                Some(_) => {}
+            }
+        }
+        if enter.0 > leave.0 {
+            return None;
+        }
+        let (t, i) = if enter.0 >= 0.0 { enter } else { leave };
+        if !(0.0..f32::INFINITY).contains(&t) {
+            return None;
+        }
+        Some(Hit {
+            point: self.at(t),
+            t,
+            normal: planes[i].normalize(),
+        })
+    }
+
+    pub fn polyhedron(&self, p: &ConvexPolyhedron) -> Option<Hit> {
+        self.planes(&p.planes)
+    }
+
+    /// The closest hit with any of the triangles and its index. Degenerate
+    /// triangles, whose hits are NaN, are skipped.
+    pub fn mesh(&self, mesh: &Mesh) -> Option<(usize, Hit)> {
+        let v = &mesh.vertices;
+        mesh.triangles
+            .iter()
+            .enumerate()
+            .filter_map(|(i, t)| self.triangle(&v[t[0]], &v[t[1]], &v[t[2]]).map(|h| (i, h)))
+            .filter(|(_, h)| !h.t.is_nan())
+            .min_by(|a, b| a.1.t.total_cmp(&b.1.t))
+    }
+}
+
+/// Casts every ray against the mesh.
+pub fn cast(rays: &[Ray], mesh: &Mesh) -> Vec<Option<(usize, Hit)>> {
+    rays.iter().map(|r| r.mesh(mesh)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clip::Mesh;
+    use crate::ray::*;
+
+    fn close(a: &Point, b: &[f32; 3]) -> bool {
+        let a = a.normalize().eucl();
+        a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 1e-4)
+    }
+
+    #[test]
+    fn line() {
+        let r = Ray::new(&[1., 2., 3.], &[0., 0., 2.]);
+        assert_eq!(r.line(), Line::new(&[1., 2., 3.], &[0., 0., 1.]));
+        let r_ = Ray::from_line(&r.line(), 3.);
+        assert!(close(&r_.origin, &[1., 2., 3.]));
+        assert!(close(&r.at(2.), &[1., 2., 5.]));
+    }
+
+    #[test]
+    fn plane() {
+        let r = Ray::new(&[0., 0., 5.], &[0., 0., -1.]);
+        let h = r.plane(&Plane::new(1., &[0., 0., 1.])).unwrap();
+        assert!(close(&h.point, &[0., 0., 1.]));
+        assert!((h.t - 4.).abs() < 1e-5);
+        assert_eq!(h.normal, Plane::new(1., &[0., 0., 1.]));
+        let h = r.plane(&Plane::new(-1., &[0., 0., -1.])).unwrap();
+        assert_eq!(h.normal, Plane::new(1., &[0., 0., 1.]));
+        assert!(r.plane(&Plane::new(6., &[0., 0., 1.])).is_none());
+        assert!(r.plane(&Plane::new(0., &[1., 0., 0.])).is_none());
+    }
+
+    #[test]
+    fn triangle() {
+        let (a, b, c) = (
+            Point::new(&[0., 0., 0.]),
+            Point::new(&[1., 0., 0.]),
+            Point::new(&[0., 1., 0.]),
+        );
+        let down = Ray::new(&[0.2, 0.2, 1.], &[0., 0., -1.]);
+        let h = down.triangle(&a, &b, &c).unwrap();
+        assert!(close(&h.point, &[0.2, 0.2, 0.]));
+        assert!((h.t - 1.).abs() < 1e-5);
+        assert!(down.triangle(&a, &c, &b).is_some());
+        let miss = Ray::new(&[0.6, 0.6, 1.], &[0., 0., -1.]);
+        assert!(miss.triangle(&a, &b, &c).is_none());
+    }
+
+    #[test]
+    fn sphere() {
+        let r = Ray::new(&[-5., 0., 0.], &[1., 0., 0.]);
+        let h = r.sphere(&Point::new(&[0., 0., 0.]), 2.).unwrap();
+        assert!(close(&h.point, &[-2., 0., 0.]));
+        assert!((h.t - 3.).abs() < 1e-5);
+        assert_eq!(h.normal, Plane::new(2., &[-1., 0., 0.]));
+        let inside = Ray::new(&[0., 0., 0.], &[1., 0., 0.]);
+        assert!((inside.sphere(&Point::new(&[0., 0., 0.]), 2.).unwrap().t - 2.).abs() < 1e-5);
+        assert!(r.sphere(&Point::new(&[0., 3., 0.]), 2.).is_none());
+    }
+
+    #[test]
+    fn boxes() {
+        let r = Ray::new(&[-5., 0.5, 0.5], &[1., 0., 0.]);
+        let h = r.aabb(&[0., 0., 0.], &[1., 1., 1.]).unwrap();
+        assert!(close(&h.point, &[0., 0.5, 0.5]));
+        assert_eq!(h.normal, Plane::new(0., &[-1., 0., 0.]));
+        let inside = Ray::new(&[0.5, 0.5, 0.5], &[0., 1., 0.]);
+        let h = inside.aabb(&[0., 0., 0.], &[1., 1., 1.]).unwrap();
+        assert!(close(&h.point, &[0.5, 1., 0.5]));
+        assert!(r.aabb(&[0., 1., 0.], &[1., 2., 1.]).is_none());
+        let away = Ray::new(&[-5., 0.5, 0.5], &[-1., 0., 0.]);
+        assert!(away.aabb(&[0., 0., 0.], &[1., 1., 1.]).is_none());
+    }
+
+    #[test]
+    fn mesh() {
+        let mesh = Mesh {
+            vertices: vec![
+                Point::new(&[0., 0., 0.]),
+                Point::new(&[1., 0., 0.]),
+                Point::new(&[0., 1., 0.]),
+                Point::new(&[0., 0., 2.]),
+                Point::new(&[1., 0., 2.]),
+                Point::new(&[0., 1., 2.]),
+            ],
+            // The last one is degenerate and hits nowhere (NaN).
+            triangles: vec![[0, 1, 2], [3, 4, 5], [0, 0, 0]],
+        };
+        let rays = [
+            Ray::new(&[0.2, 0.2, 3.], &[0., 0., -1.]),
+            Ray::new(&[0.2, 0.2, -1.], &[0., 0., 1.]),
+            Ray::new(&[2., 2., 3.], &[0., 0., -1.]),
+        ];
+        let hits = cast(&rays, &mesh);
+        assert_eq!(hits[0].as_ref().unwrap().0, 1);
+        assert_eq!(hits[1].as_ref().unwrap().0, 0);
+        assert!(hits[2].is_none());
+    }
+}
@@ -116,44 +116,11 @@ impl Rotor {
     }
 
     pub fn apply_to_point(&self, p: &super::Point) -> super::Point {
-        let p = p.trivector;
-        let e = self.e_bivector;
-        let s = self.scalar;
-
-        super::Point {
-            trivector: [
-                e[0] * e[0] * p[0] + e[1] * e[1] * p[0] + e[2] * e[2] * p[0] + p[0] * s * s,
-                e[0] * (e[0] * p[1] + e[1] * p[2] + e[2] * p[3])
-                    - e[1] * (-e[0] * p[2] + e[1] * p[1] + p[3] * s)
-                    + e[2] * (e[0] * p[3] - e[2] * p[1] + p[2] * s)
-                    + s * (-e[1] * p[3] + e[2] * p[2] + p[1] * s),
-                e[0] * (-e[0] * p[2] + e[1] * p[1] + p[3] * s)
-                    + e[1] * (e[0] * p[1] + e[1] * p[2] + e[2] * p[3])
-                    - e[2] * (-e[1] * p[3] + e[2] * p[2] + p[1] * s)
-                    + s * (e[0] * p[3] - e[2] * p[1] + p[2] * s),
-                -e[0] * (e[0] * p[3] - e[2] * p[1] + p[2] * s)
-                    + e[1] * (-e[1] * p[3] + e[2] * p[2] + p[1] * s)
-                    + e[2] * (e[0] * p[1] + e[1] * p[2] + e[2] * p[3])
-                    + s * (-e[0] * p[2] + e[1] * p[1] + p[3] * s),
-            ],
-        }
+        super::products::sandwich_rotor_point(self, p)
     }
 
     pub fn mul_translator(&self, t: &super::Translator) -> super::Motor {
-        let ts = t.scalar;
-        let tv = t.v_bivector;
-        let rs = self.scalar;
-        let re = self.e_bivector;
-        super::Motor {
-            scalar: rs * ts,
-            pseudo: re[0] * tv[0] + re[1] * tv[1] + re[2] * tv[2],
-            e_bivector: [re[0] * ts, re[1] * ts, re[2] * ts],
-            v_bivector: [
-                -re[1] * tv[2] + re[2] * tv[1] + rs * tv[0],
-                re[0] * tv[2] - re[2] * tv[0] + rs * tv[1],
-                -re[0] * tv[1] + re[1] * tv[0] + rs * tv[2],
-            ],
-        }
+        super::products::geometric_rotor_translator(self, t)
     }
 }
 
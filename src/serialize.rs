@@ -0,0 +1,341 @@
+//! serde support, behind the serde feature.
+//!
+//! Every type is encoded as a struct with one f32 field per component,
+//! named after the basis element (see vector, v_bivector, e_bivector and
+//! trivector in the crate root):
+//!     Point        {"e123", "e032", "e013", "e021"}
+//!     Plane        {"e0", "e1", "e2", "e3"}
+//!     Line         {"e23", "e31", "e12", "e01", "e02", "e03"}
+//!     Motor        {"scalar", "e23", "e31", "e12", "e01", "e02", "e03", "e0123"}
+//!     Rotor        {"scalar", "e23", "e31", "e12"}
+//!     Translator   {"scalar", "e01", "e02", "e03"}
+//!     PseudoScalar {"e0123"}
+//! e.g., Point::new(&[1., 2., 3.]) is {"e123":1.0,"e032":-1.0,"e013":-2.0,"e021":-3.0}
+//! in JSON. Formats without field names get the components in this order.
+//!
+//! The compact module encodes the same components as a plain array instead,
+//! e.g., [1.0,-1.0,-2.0,-3.0] for the point above.
+
+use super::{e_bivector, trivector, v_bivector, vector};
+use super::{Line, Motor, Plane, Point, PseudoScalar, Rotor, Translator};
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeStruct, SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A type that is a fixed list of named f32 components.
+pub trait Components: Sized {
+    const NAME: &'static str;
+    const FIELDS: &'static [&'static str];
+    fn components(&self) -> Vec<f32>;
+    /// c has FIELDS.len() entries.
+    fn from_components(c: &[f32]) -> Self;
+}
+
+impl Components for Point {
+    const NAME: &'static str = "Point";
+    const FIELDS: &'static [&'static str] = &trivector;
+    fn components(&self) -> Vec<f32> {
+        self.trivector.to_vec()
+    }
+    fn from_components(c: &[f32]) -> Self {
+        Self {
+            trivector: [c[0], c[1], c[2], c[3]],
+        }
+    }
+}
+
+impl Components for Plane {
+    const NAME: &'static str = "Plane";
+    const FIELDS: &'static [&'static str] = &vector;
+    fn components(&self) -> Vec<f32> {
+        self.vector.to_vec()
+    }
+    fn from_components(c: &[f32]) -> Self {
+        Self {
+            vector: [c[0], c[1], c[2], c[3]],
+        }
+    }
+}
+
+impl Components for Line {
+    const NAME: &'static str = "Line";
+    const FIELDS: &'static [&'static str] = &[
+        e_bivector[0],
+        e_bivector[1],
+        e_bivector[2],
+        v_bivector[0],
+        v_bivector[1],
+        v_bivector[2],
+    ];
+    fn components(&self) -> Vec<f32> {
+        self.tangent().to_vec()
+    }
+    fn from_components(c: &[f32]) -> Self {
+        Self::from_tangent(&[c[0], c[1], c[2], c[3], c[4], c[5]])
+    }
+}
+
+impl Components for Motor {
+    const NAME: &'static str = "Motor";
+    const FIELDS: &'static [&'static str] = &[
+        "scalar",
+        e_bivector[0],
+        e_bivector[1],
+        e_bivector[2],
+        v_bivector[0],
+        v_bivector[1],
+        v_bivector[2],
+        "e0123",
+    ];
+    fn components(&self) -> Vec<f32> {
+        let (e, v) = (self.e_bivector, self.v_bivector);
+        vec![self.scalar, e[0], e[1], e[2], v[0], v[1], v[2], self.pseudo]
+    }
+    fn from_components(c: &[f32]) -> Self {
+        Self {
+            scalar: c[0],
+            e_bivector: [c[1], c[2], c[3]],
+            v_bivector: [c[4], c[5], c[6]],
+            pseudo: c[7],
+        }
+    }
+}
+
+impl Components for Rotor {
+    const NAME: &'static str = "Rotor";
+    const FIELDS: &'static [&'static str] =
+        &["scalar", e_bivector[0], e_bivector[1], e_bivector[2]];
+    fn components(&self) -> Vec<f32> {
+        let e = self.e_bivector;
+        vec![self.scalar, e[0], e[1], e[2]]
+    }
+    fn from_components(c: &[f32]) -> Self {
+        Self {
+            scalar: c[0],
+            e_bivector: [c[1], c[2], c[3]],
+        }
+    }
+}
+
+impl Components for Translator {
+    const NAME: &'static str = "Translator";
+    const FIELDS: &'static [&'static str] =
+        &["scalar", v_bivector[0], v_bivector[1], v_bivector[2]];
+    fn components(&self) -> Vec<f32> {
+        let v = self.v_bivector;
+        vec![self.scalar, v[0], v[1], v[2]]
+    }
+    fn from_components(c: &[f32]) -> Self {
+        Self {
+            scalar: c[0],
+            v_bivector: [c[1], c[2], c[3]],
+        }
+    }
+}
+
+impl Components for PseudoScalar {
+    const NAME: &'static str = "PseudoScalar";
+    const FIELDS: &'static [&'static str] = &["e0123"];
+    fn components(&self) -> Vec<f32> {
+        vec![self.0]
+    }
+    fn from_components(c: &[f32]) -> Self {
+        Self(c[0])
+    }
+}
+
+macro_rules! impl_serde {
+    ($($t:ty),*) => {$(
+        impl Serialize for $t {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let c = self.components();
+                let mut s = serializer.serialize_struct(Self::NAME, c.len())?;
+                for (name, x) in Self::FIELDS.iter().zip(c.iter()) {
+                    s.serialize_field(name, x)?;
+                }
+                s.end()
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_struct(
+                    Self::NAME,
+                    Self::FIELDS,
+                    ComponentsVisitor(PhantomData),
+                )
+            }
+        }
+    )*};
+}
+
+impl_serde!(Point, Plane, Line, Motor, Rotor, Translator, PseudoScalar);
+
+/// Reads the named form from a map, or the components in order
+/// from a sequence.
+struct ComponentsVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Components> Visitor<'de> for ComponentsVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} components of a {}", T::FIELDS.len(), T::NAME)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let mut c = Vec::with_capacity(T::FIELDS.len());
+        for i in 0..T::FIELDS.len() {
+            match seq.next_element()? {
+                Some(x) => c.push(x),
+                None => return Err(de::Error::invalid_length(i, &self)),
+            }
+        }
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(T::FIELDS.len() + 1, &self));
+        }
+        Ok(T::from_components(&c))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<T, A::Error> {
+        let mut c: Vec<Option<f32>> = vec![None; T::FIELDS.len()];
+        while let Some(key) = map.next_key::<String>()? {
+            let i = match T::FIELDS.iter().position(|name| *name == key) {
+                Some(i) => i,
+                None => return Err(de::Error::unknown_field(&key, T::FIELDS)),
+            };
+            if c[i].is_some() {
+                return Err(de::Error::duplicate_field(T::FIELDS[i]));
+            }
+            c[i] = Some(map.next_value()?);
+        }
+        let mut values = Vec::with_capacity(c.len());
+        for (x, name) in c.iter().zip(T::FIELDS.iter()) {
+            match x {
+                Some(x) => values.push(*x),
+                None => return Err(de::Error::missing_field(name)),
+            }
+        }
+        Ok(T::from_components(&values))
+    }
+}
+
+/// The compact array form. Use it for a field with
+///     #[serde(with = "ppga::serialize::compact")]
+/// or wrap a value in Compact.
+pub mod compact {
+    use super::*;
+
+    pub fn serialize<T: Components, S: Serializer>(
+        x: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let c = x.components();
+        let mut s = serializer.serialize_tuple(c.len())?;
+        for x in c.iter() {
+            s.serialize_element(x)?;
+        }
+        s.end()
+    }
+
+    pub fn deserialize<'de, T: Components, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        deserializer.deserialize_tuple(T::FIELDS.len(), ComponentsVisitor(PhantomData))
+    }
+
+    /// Serializes the wrapped value in the compact form, e.g.,
+    /// Vec<Compact<Motor>> for a list of arrays.
+    #[derive(Debug, Copy, Clone)]
+    pub struct Compact<T>(pub T);
+
+    impl<T: Components> Serialize for Compact<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serialize(&self.0, serializer)
+        }
+    }
+
+    impl<'de, T: Components> Deserialize<'de> for Compact<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserialize(deserializer).map(Compact)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serialize::compact::Compact;
+    use crate::*;
+
+    #[test]
+    fn format() {
+        let p = Point::new(&[1., 2., 3.]);
+        let json = r#"{"e123":1.0,"e032":-1.0,"e013":-2.0,"e021":-3.0}"#;
+        assert_eq!(serde_json::to_string(&p).unwrap(), json);
+        assert_eq!(serde_json::from_str::<Point>(json).unwrap(), p);
+
+        let plane = Plane::new(2., &[0., 1., 0.]);
+        let json = r#"{"e0":2.0,"e1":0.0,"e2":1.0,"e3":0.0}"#;
+        assert_eq!(serde_json::to_string(&plane).unwrap(), json);
+
+        let l = Line::from_tangent(&[1., 2., 3., 4., 5., 6.]);
+        let json = r#"{"e23":1.0,"e31":2.0,"e12":3.0,"e01":4.0,"e02":5.0,"e03":6.0}"#;
+        assert_eq!(serde_json::to_string(&l).unwrap(), json);
+
+        let m = Motor {
+            scalar: 1.,
+            e_bivector: [2., 3., 4.],
+            v_bivector: [5., 6., 7.],
+            pseudo: 8.,
+        };
+        let json = r#"{"scalar":1.0,"e23":2.0,"e31":3.0,"e12":4.0,"e01":5.0,"e02":6.0,"e03":7.0,"e0123":8.0}"#;
+        assert_eq!(serde_json::to_string(&m).unwrap(), json);
+        assert_eq!(
+            serde_json::to_string(&Compact(m)).unwrap(),
+            "[1.0,2.0,3.0,4.0,5.0,6.0,7.0,8.0]"
+        );
+
+        let r = Rotor {
+            scalar: 1.,
+            e_bivector: [2., 3., 4.],
+        };
+        let json = r#"{"scalar":1.0,"e23":2.0,"e31":3.0,"e12":4.0}"#;
+        assert_eq!(serde_json::to_string(&r).unwrap(), json);
+
+        let t = Translator::new(&[2., 4., 6.]);
+        let json = r#"{"scalar":1.0,"e01":1.0,"e02":2.0,"e03":3.0}"#;
+        assert_eq!(serde_json::to_string(&t).unwrap(), json);
+
+        let json = r#"{"e0123":0.5}"#;
+        assert_eq!(serde_json::to_string(&PseudoScalar(0.5)).unwrap(), json);
+    }
+
+    #[test]
+    fn round_trip() {
+        let m = Motor::random();
+        let named: Motor = serde_json::from_str(&serde_json::to_string(&m).unwrap()).unwrap();
+        let compact: Compact<Motor> =
+            serde_json::from_str(&serde_json::to_string(&Compact(m)).unwrap()).unwrap();
+        assert_eq!(named.into_klein(), m.into_klein());
+        assert_eq!(compact.0.into_klein(), m.into_klein());
+
+        let l = Line::random();
+        let l_: Line = serde_json::from_str(&serde_json::to_string(&l).unwrap()).unwrap();
+        assert_eq!(l.tangent(), l_.tangent());
+
+        // Field order does not matter, a sequence is read in order.
+        let r: Rotor =
+            serde_json::from_str(r#"{"e12":4.0,"e23":2.0,"e31":3.0,"scalar":1.0}"#).unwrap();
+        assert_eq!((r.scalar, r.e_bivector), (1., [2., 3., 4.]));
+        let p: Plane = serde_json::from_str("[2.0,0.0,1.0,0.0]").unwrap();
+        assert_eq!(p.vector, [2., 0., 1., 0.]);
+
+        assert!(serde_json::from_str::<Rotor>(r#"{"scalar":1.0,"e23":2.0,"e31":3.0}"#).is_err());
+        assert!(
+            serde_json::from_str::<Plane>(r#"{"e0":1.0,"e1":0.0,"e2":1.0,"e3":0.0,"e4":0.0}"#)
+                .is_err()
+        );
+        assert!(serde_json::from_str::<Compact<Point>>("[1.0,2.0,3.0]").is_err());
+    }
+}
@@ -0,0 +1,73 @@
+//! Shader versions of the core operations, for moving things on the GPU.
+//! glsl/ppga.glsl is the source, the WGSL and HLSL libraries are generated
+//! from it by the build script (see transpile). All three use the PGA4CS
+//! blocks-of-four layout, so a motor is two vec4s [s, e23, e31, e12] and
+//! [e0123, e01, e02, e03] as returned by Motor::into_klein.
+//!
+//! The eval module runs the library on the CPU, its tests check every
+//! shader function against the Rust implementation.
+
+pub mod eval;
+pub mod transpile;
+
+pub const GLSL: &str = include_str!("../glsl/ppga.glsl");
+pub const WGSL: &str = include_str!(concat!(env!("OUT_DIR"), "/ppga.wgsl"));
+pub const HLSL: &str = include_str!(concat!(env!("OUT_DIR"), "/ppga.hlsl"));
+
+#[cfg(test)]
+mod tests {
+    use crate::shader::transpile::{emit, parse, Language};
+    use crate::shader::*;
+
+    #[test]
+    fn round_trip() {
+        let lib = parse(GLSL).unwrap();
+        let glsl = emit(&lib, Language::Glsl);
+        assert_eq!(parse(&glsl).unwrap(), lib);
+        assert_eq!(lib.structs().count(), 3);
+        assert!(lib.function("ppga_ln", 1).is_some());
+        assert!(lib.function("ppga_cayley_exp", 2).is_some());
+        assert!(lib.function("ppga_cayley_exp", 1).is_some());
+    }
+
+    #[test]
+    fn generated() {
+        let lib = parse(GLSL).unwrap();
+        assert_eq!(WGSL, emit(&lib, Language::Wgsl));
+        assert_eq!(HLSL, emit(&lib, Language::Hlsl));
+
+        assert!(WGSL.contains("fn ppga_motor_mul(a: ppga_motor, b: ppga_motor) -> ppga_motor {"));
+        assert!(
+            WGSL.contains("fn ppga_cayley_exp(eucl: vec3<f32>, vanish: vec3<f32>) -> ppga_motor {")
+        );
+        assert!(WGSL.contains("fn ppga_cayley_exp_rotor(eucl: vec3<f32>) -> ppga_rotor {"));
+        assert!(WGSL.contains("let u: f32 = atan2(s2, s1);"));
+        assert!(WGSL.contains("if (s2 >= 0.01 * abs(s1)) {"));
+        assert!(HLSL.contains("ppga_line ppga_apply_motor_to_line(ppga_motor m, ppga_line l) {"));
+        assert!(HLSL.contains("return make_ppga_rotor(r.p1 / length(r.p1));"));
+
+        // WGSL has no overloading.
+        let mut names: Vec<&str> = WGSL
+            .lines()
+            .filter(|l| l.starts_with("fn "))
+            .map(|l| l[3..].split('(').next().unwrap())
+            .collect();
+        let n = names.len();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), n);
+        assert!(!WGSL.contains("vec3(") && !HLSL.contains("vec"));
+    }
+
+    #[test]
+    fn parenthesis() {
+        let lib =
+            parse("float f(float a, float b) { return -(a - b) * (a / (b * a)) - (a - b).x; }")
+                .unwrap();
+        assert_eq!(
+            emit(&lib, Language::Glsl),
+            "float f(float a, float b) {\n    return -(a - b) * (a / (b * a)) - (a - b).x;\n}\n"
+        );
+        assert!(parse("float f() { return 1 +; }").is_err());
+    }
+}
@@ -0,0 +1,562 @@
+//! Runs the shader library on the CPU, so it can be tested against the
+//! Rust implementation without a GPU. Interprets the parsed GLSL subset
+//! (see transpile) with f32 arithmetic and the handful of builtins the
+//! library uses.
+
+use super::transpile::{BinOp, Expr, Function, Library, Stmt, Type};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Float(f32),
+    Bool(bool),
+    Vec(Vec<f32>),
+    /// The struct name and its fields in declaration order.
+    Struct(String, Vec<Value>),
+}
+
+impl Value {
+    pub fn float(&self) -> Result<f32, EvalError> {
+        match self {
+            Value::Float(x) => Ok(*x),
+            v => error(format!("expected a float, got {:?}", v)),
+        }
+    }
+
+    pub fn vec(&self) -> Result<&[f32], EvalError> {
+        match self {
+            Value::Vec(x) => Ok(x),
+            v => error(format!("expected a vector, got {:?}", v)),
+        }
+    }
+
+    pub fn fields(&self) -> Result<&[Value], EvalError> {
+        match self {
+            Value::Struct(_, fields) => Ok(fields),
+            v => error(format!("expected a struct, got {:?}", v)),
+        }
+    }
+
+    fn has_type(&self, t: &Type) -> bool {
+        match (self, t) {
+            (Value::Float(_), Type::Float) => true,
+            (Value::Vec(x), Type::Vec(n)) => x.len() == *n as usize,
+            (Value::Struct(name, _), Type::Struct(s)) => name == s,
+            _ => false,
+        }
+    }
+}
+
+impl From<f32> for Value {
+    fn from(x: f32) -> Self {
+        Value::Float(x)
+    }
+}
+
+impl From<[f32; 3]> for Value {
+    fn from(x: [f32; 3]) -> Self {
+        Value::Vec(x.to_vec())
+    }
+}
+
+impl From<[f32; 4]> for Value {
+    fn from(x: [f32; 4]) -> Self {
+        Value::Vec(x.to_vec())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvalError {
+    pub message: String,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+fn error<T>(message: String) -> Result<T, EvalError> {
+    Err(EvalError { message })
+}
+
+/// Calls the library function name, or a builtin.
+pub fn call(lib: &Library, name: &str, args: Vec<Value>) -> Result<Value, EvalError> {
+    if let Some(f) = lib.function(name, args.len()) {
+        return run(lib, f, args);
+    }
+    if let Some(s) = lib.structs().find(|s| s.name == name) {
+        if args.len() != s.fields.len()
+            || !args
+                .iter()
+                .zip(s.fields.iter())
+                .all(|(a, (t, _))| a.has_type(t))
+        {
+            return error(format!("wrong arguments for {}: {:?}", name, args));
+        }
+        return Ok(Value::Struct(name.into(), args));
+    }
+    builtin(name, args)
+}
+
+fn run(lib: &Library, f: &Function, args: Vec<Value>) -> Result<Value, EvalError> {
+    for (a, (t, p)) in args.iter().zip(f.params.iter()) {
+        if !a.has_type(t) {
+            return error(format!("{}: {} is not a {:?}: {:?}", f.name, p, t, a));
+        }
+    }
+    let mut vars: HashMap<String, Value> =
+        f.params.iter().map(|(_, p)| p.clone()).zip(args).collect();
+    match block(lib, &f.body, &mut vars)? {
+        Some(r) if r.has_type(&f.ret) => Ok(r),
+        Some(r) => error(format!("{} returned {:?}", f.name, r)),
+        None => error(format!("{} did not return", f.name)),
+    }
+}
+
+/// The returned value, if any.
+fn block(
+    lib: &Library,
+    body: &[Stmt],
+    vars: &mut HashMap<String, Value>,
+) -> Result<Option<Value>, EvalError> {
+    for stmt in body {
+        match stmt {
+            Stmt::Decl(t, name, e) => {
+                let v = expr(lib, e, vars)?;
+                if !v.has_type(t) {
+                    return error(format!("{} is not a {:?}: {:?}", name, t, v));
+                }
+                vars.insert(name.clone(), v);
+            }
+            Stmt::Assign(name, op, e) => {
+                let mut v = expr(lib, e, vars)?;
+                let old = match vars.get(name) {
+                    Some(old) => old.clone(),
+                    None => return error(format!("unknown variable {}", name)),
+                };
+                if let Some(op) = op {
+                    v = binary(*op, old.clone(), v)?;
+                }
+                if std::mem::discriminant(&v) != std::mem::discriminant(&old) {
+                    return error(format!("cannot assign {:?} to {}", v, name));
+                }
+                vars.insert(name.clone(), v);
+            }
+            Stmt::If(cond, then, otherwise) => {
+                let taken = match expr(lib, cond, vars)? {
+                    Value::Bool(b) => b,
+                    v => return error(format!("condition is not a bool: {:?}", v)),
+                };
+                let r = block(lib, if taken { then } else { otherwise }, vars)?;
+                if r.is_some() {
+                    return Ok(r);
+                }
+            }
+            Stmt::Return(e) => return Ok(Some(expr(lib, e, vars)?)),
+        }
+    }
+    Ok(None)
+}
+
+fn expr(lib: &Library, e: &Expr, vars: &HashMap<String, Value>) -> Result<Value, EvalError> {
+    match e {
+        Expr::Number(n) => match n.parse::<f32>() {
+            Ok(x) => Ok(Value::Float(x)),
+            Err(_) => error(format!("bad number {}", n)),
+        },
+        Expr::Var(name) => match vars.get(name) {
+            Some(v) => Ok(v.clone()),
+            None => error(format!("unknown variable {}", name)),
+        },
+        Expr::Member(e, m) => match expr(lib, e, vars)? {
+            Value::Struct(name, fields) => {
+                let s = lib.structs().find(|s| s.name == name).unwrap();
+                match s.fields.iter().position(|(_, f)| f == m) {
+                    Some(i) => Ok(fields[i].clone()),
+                    None => error(format!("{} has no field {}", name, m)),
+                }
+            }
+            Value::Vec(x) => swizzle(&x, m),
+            v => error(format!("no member {} on {:?}", m, v)),
+        },
+        Expr::Call(name, args) => {
+            let args = args
+                .iter()
+                .map(|a| expr(lib, a, vars))
+                .collect::<Result<Vec<_>, _>>()?;
+            call(lib, name, args)
+        }
+        Expr::Neg(e) => binary(BinOp::Mul, Value::Float(-1.0), expr(lib, e, vars)?),
+        Expr::Binary(op, a, b) => binary(*op, expr(lib, a, vars)?, expr(lib, b, vars)?),
+    }
+}
+
+fn swizzle(x: &[f32], m: &str) -> Result<Value, EvalError> {
+    let mut r = Vec::new();
+    for c in m.chars() {
+        let i = match c {
+            'x' | 'r' => 0,
+            'y' | 'g' => 1,
+            'z' | 'b' => 2,
+            'w' | 'a' => 3,
+            _ => return error(format!("bad swizzle {}", m)),
+        };
+        match x.get(i) {
+            Some(x) => r.push(*x),
+            None => return error(format!("swizzle {} out of range", m)),
+        }
+    }
+    match r.len() {
+        1 => Ok(Value::Float(r[0])),
+        2..=4 => Ok(Value::Vec(r)),
+        _ => error(format!("bad swizzle {}", m)),
+    }
+}
+
+fn binary(op: BinOp, a: Value, b: Value) -> Result<Value, EvalError> {
+    let f: fn(f32, f32) -> f32 = match op {
+        BinOp::Add => |a, b| a + b,
+        BinOp::Sub => |a, b| a - b,
+        BinOp::Mul => |a, b| a * b,
+        BinOp::Div => |a, b| a / b,
+        _ => {
+            let (a, b) = (a.float()?, b.float()?);
+            return Ok(Value::Bool(match op {
+                BinOp::Lt => a < b,
+                BinOp::Gt => a > b,
+                BinOp::Le => a <= b,
+                BinOp::Ge => a >= b,
+                BinOp::Eq => a == b,
+                _ => a != b,
+            }));
+        }
+    };
+    // Componentwise, a float is used for every component.
+    match (a, b) {
+        (Value::Float(a), Value::Float(b)) => Ok(Value::Float(f(a, b))),
+        (Value::Float(a), Value::Vec(b)) => Ok(Value::Vec(b.iter().map(|b| f(a, *b)).collect())),
+        (Value::Vec(a), Value::Float(b)) => Ok(Value::Vec(a.iter().map(|a| f(*a, b)).collect())),
+        (Value::Vec(a), Value::Vec(b)) if a.len() == b.len() => Ok(Value::Vec(
+            a.iter().zip(b.iter()).map(|(a, b)| f(*a, *b)).collect(),
+        )),
+        (a, b) => error(format!("cannot apply {:?} to {:?} and {:?}", op, a, b)),
+    }
+}
+
+fn builtin(name: &str, args: Vec<Value>) -> Result<Value, EvalError> {
+    let unary: Option<fn(f32) -> f32> = match name {
+        "abs" => Some(f32::abs),
+        "sqrt" => Some(f32::sqrt),
+        "sin" => Some(f32::sin),
+        "cos" => Some(f32::cos),
+        "atan" if args.len() == 1 => Some(f32::atan),
+        _ => None,
+    };
+    if let Some(f) = unary {
+        return match args.as_slice() {
+            [Value::Float(x)] => Ok(Value::Float(f(*x))),
+            [Value::Vec(x)] => Ok(Value::Vec(x.iter().map(|x| f(*x)).collect())),
+            _ => error(format!("wrong arguments for {}: {:?}", name, args)),
+        };
+    }
+    match (name, args.as_slice()) {
+        ("float", [Value::Float(x)]) => Ok(Value::Float(*x)),
+        ("vec2", _) | ("vec3", _) | ("vec4", _) => {
+            let n = name[3..].parse::<usize>().unwrap();
+            let mut r = Vec::new();
+            for a in args.iter() {
+                match a {
+                    Value::Float(x) => r.push(*x),
+                    Value::Vec(x) => r.extend_from_slice(x),
+                    _ => return error(format!("wrong arguments for {}: {:?}", name, args)),
+                }
+            }
+            if let [x] = r.as_slice() {
+                r = vec![*x; n];
+            }
+            if r.len() != n {
+                return error(format!("wrong arguments for {}: {:?}", name, args));
+            }
+            Ok(Value::Vec(r))
+        }
+        ("atan", [Value::Float(y), Value::Float(x)]) => Ok(Value::Float(y.atan2(*x))),
+        ("dot", [Value::Vec(a), Value::Vec(b)]) if a.len() == b.len() => Ok(Value::Float(
+            a.iter().zip(b.iter()).map(|(a, b)| a * b).sum(),
+        )),
+        ("length", [Value::Float(x)]) => Ok(Value::Float(x.abs())),
+        ("length", [Value::Vec(x)]) => {
+            Ok(Value::Float(x.iter().map(|x| x * x).sum::<f32>().sqrt()))
+        }
+        ("cross", [Value::Vec(a), Value::Vec(b)]) if a.len() == 3 && b.len() == 3 => {
+            Ok(Value::Vec(vec![
+                a[1] * b[2] - a[2] * b[1],
+                a[2] * b[0] - a[0] * b[2],
+                a[0] * b[1] - a[1] * b[0],
+            ]))
+        }
+        _ => error(format!("unknown function {} for {:?}", name, args)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shader::eval::{call, Value};
+    use crate::shader::transpile::{parse, Library};
+    use crate::*;
+
+    fn lib() -> Library {
+        parse(shader::GLSL).unwrap()
+    }
+
+    fn motor(m: &Motor) -> Value {
+        let k = m.into_klein();
+        Value::Struct("ppga_motor".into(), vec![k[0].into(), k[1].into()])
+    }
+
+    fn rotor(r: &Rotor) -> Value {
+        let p1: [f32; 4] = r.into();
+        Value::Struct("ppga_rotor".into(), vec![p1.into()])
+    }
+
+    fn line(l: &Line) -> Value {
+        Value::Struct(
+            "ppga_line".into(),
+            vec![l.e_bivector.into(), l.v_bivector.into()],
+        )
+    }
+
+    /// Componentwise relative comparison of everything in a and b.
+    fn assert_close(name: &str, a: &Value, b: &Value) {
+        let close = match (a, b) {
+            (Value::Float(a), Value::Float(b)) => (a - b).abs() <= 1e-4 * (1. + b.abs()),
+            (Value::Vec(a), Value::Vec(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b.iter())
+                        .all(|(a, b)| (a - b).abs() <= 1e-4 * (1. + b.abs()))
+            }
+            (Value::Struct(n, a), Value::Struct(m, b)) => {
+                for (a, b) in a.iter().zip(b.iter()) {
+                    assert_close(name, a, b);
+                }
+                n == m && a.len() == b.len()
+            }
+            _ => false,
+        };
+        assert!(close, "{}: {:?} != {:?}", name, a, b);
+    }
+
+    fn test_motor() -> Motor {
+        let axis = na::Vec3::new_random().normalize().into();
+        let r = Rotor::new(rand::random::<f32>() * 6., &axis);
+        Translator::new(&na::Vec3::new_random().into()).mul_rotor(&r)
+    }
+
+    /// The sum of l^n/n!, independent of Line::exp.
+    fn series(l: &Line) -> Motor {
+        let mut term = Motor::one();
+        let mut sum = Motor::one();
+        for n in 1..30 {
+            term = term.mul(&Motor::from(l)).mul_scalar(1. / n as f32);
+            sum = sum.add(&term);
+        }
+        sum
+    }
+
+    #[test]
+    fn exp() {
+        let lib = lib();
+        for _ in 0..100 {
+            // Random lines are screws, with e.v != 0.
+            let l = Line::random();
+            for l in [l, l.mul_scalar(0.0001)].iter() {
+                let (e, v) = (l.e_bivector.into(), l.v_bivector.into());
+                let r = call(&lib, "ppga_exp", vec![e, v]).unwrap();
+                assert_close("exp", &r, &motor(&series(l)));
+                assert_close("exp", &r, &motor(&l.exp()));
+            }
+        }
+    }
+
+    #[test]
+    fn other_exps() {
+        let lib = lib();
+        for _ in 0..100 {
+            let l = Line::random();
+            let e: [f32; 3] = l.e_bivector;
+            let ee = na::Vec3::from(e).norm_squared();
+            let scale = |m: Motor| Motor {
+                scalar: m.scalar * (1. + ee) / 2.,
+                pseudo: m.pseudo * (1. + ee) / 2.,
+                e_bivector: (na::Vec3::from(m.e_bivector) * (1. + ee) / 2.).into(),
+                v_bivector: (na::Vec3::from(m.v_bivector) * (1. + ee) / 2.).into(),
+            };
+            let args = vec![e.into(), l.v_bivector.into()];
+            let r = call(&lib, "ppga_cayley_exp", args.clone()).unwrap();
+            assert_close("cayley_exp", &r, &motor(&scale(l.cayley_exp())));
+            let r = call(&lib, "ppga_outer_exp", args).unwrap();
+            assert_close("outer_exp", &r, &motor(&l.outer_exp()));
+
+            // A line through the origin gives a rotor.
+            let o = Line {
+                e_bivector: e,
+                v_bivector: [0.; 3],
+            };
+            let k = motor(&scale(o.cayley_exp()));
+            let r = call(&lib, "ppga_cayley_exp", vec![e.into()]).unwrap();
+            assert_close(
+                "cayley_exp",
+                &r.fields().unwrap()[0],
+                &k.fields().unwrap()[0],
+            );
+            let k = motor(&o.outer_exp());
+            let r = call(&lib, "ppga_outer_exp", vec![e.into()]).unwrap();
+            assert_close(
+                "outer_exp",
+                &r.fields().unwrap()[0],
+                &k.fields().unwrap()[0],
+            );
+
+            let q: [f32; 3] = (na::Vec3::from(e).normalize() * rand::random::<f32>()).into();
+            let r = call(&lib, "ppga_qtangent_exp", vec![q.into()]).unwrap();
+            assert_close("qtangent_exp", &r, &rotor(&Rotor::qtangent_exp(&q)));
+        }
+    }
+
+    #[test]
+    fn ln() {
+        let lib = lib();
+        for _ in 0..100 {
+            let m = test_motor();
+            let r = call(&lib, "ppga_ln", vec![motor(&m)]).unwrap();
+            assert_close("ln", &r, &line(&m.ln()));
+            // The inverse of exp on screws, for angles below pi.
+            let l = Line::random();
+            let r = call(&lib, "ppga_ln", vec![motor(&series(&l))]).unwrap();
+            assert_close("ln", &r, &line(&l));
+        }
+        let t = Translator::new(&[1., 2., 3.]);
+        let m = Motor::from(&t);
+        let r = call(&lib, "ppga_ln", vec![motor(&m)]).unwrap();
+        assert_close("ln", &r, &line(&m.ln()));
+        // The same motion, so the same log.
+        let r = call(&lib, "ppga_ln", vec![motor(&m.neg())]).unwrap();
+        assert_close("ln", &r, &line(&m.ln()));
+        let r = call(&lib, "ppga_ln", vec![motor(&Motor::one().neg())]).unwrap();
+        assert_close("ln", &r, &line(&Line::zero()));
+    }
+
+    #[test]
+    fn apply() {
+        let lib = lib();
+        for _ in 0..100 {
+            let m = test_motor();
+            let mv = motor(&m);
+            let p = Point::random();
+            let r = call(
+                &lib,
+                "ppga_apply_motor_to_point",
+                vec![mv.clone(), p.trivector.into()],
+            );
+            assert_close(
+                "apply_motor_to_point",
+                &r.unwrap(),
+                &m.apply_to_point(&p).trivector.into(),
+            );
+            let p = Plane::random();
+            let r = call(
+                &lib,
+                "ppga_apply_motor_to_plane",
+                vec![mv.clone(), p.vector.into()],
+            );
+            assert_close(
+                "apply_motor_to_plane",
+                &r.unwrap(),
+                &m.apply_to_plane(&p).vector.into(),
+            );
+            let l = Line::random();
+            let r = call(&lib, "ppga_apply_motor_to_line", vec![mv.clone(), line(&l)]);
+            assert_close(
+                "apply_motor_to_line",
+                &r.unwrap(),
+                &line(&m.apply_to_line(&l)),
+            );
+
+            let o: Value = m.apply_to_point(&Point::origin()).eucl().into();
+            let r = call(&lib, "ppga_apply_motor_to_origin", vec![mv.clone()]);
+            assert_close("apply_motor_to_origin", &r.unwrap(), &o);
+
+            // A direction is a point at infinity.
+            let dir = Point::random_dir().trivector;
+            let moved = m.apply_to_point(&Point { trivector: dir }).trivector;
+            let r = call(
+                &lib,
+                "ppga_apply_motor_to_direction",
+                vec![mv, Value::Vec(dir[1..].to_vec())],
+            );
+            assert_close(
+                "apply_motor_to_direction",
+                &r.unwrap(),
+                &Value::Vec(moved[1..].to_vec()),
+            );
+            let axis = na::Vec3::new_random().normalize().into();
+            let rot = Rotor::new(rand::random::<f32>() * 6., &axis);
+            let moved = rot.apply_to_point(&Point { trivector: dir }).trivector;
+            let r = call(
+                &lib,
+                "ppga_apply_rotor_to_direction",
+                vec![rotor(&rot), Value::Vec(dir[1..].to_vec())],
+            );
+            assert_close(
+                "apply_rotor_to_direction",
+                &r.unwrap(),
+                &Value::Vec(moved[1..].to_vec()),
+            );
+        }
+    }
+
+    #[test]
+    fn algebra() {
+        let lib = lib();
+        for _ in 0..100 {
+            let (a, b) = (Motor::random(), Motor::random());
+            let r = call(&lib, "ppga_motor_mul", vec![motor(&a), motor(&b)]).unwrap();
+            assert_close("motor_mul", &r, &motor(&a.mul(&b)));
+            let r = call(&lib, "ppga_motor_reverse", vec![motor(&a)]).unwrap();
+            assert_close("motor_reverse", &r, &motor(&a.reverse()));
+            let r = call(&lib, "ppga_motor_normalize", vec![motor(&a)]).unwrap();
+            assert_close("motor_normalize", &r, &motor(&a.normalize()));
+
+            let (a, b) = (Rotor::random(), Rotor::random());
+            let ab = Motor::from(&a).mul(&Motor::from(&b));
+            let r = call(&lib, "ppga_rotor_mul", vec![rotor(&a), rotor(&b)]).unwrap();
+            assert_close(
+                "rotor_mul",
+                &r.fields().unwrap()[0],
+                &motor(&ab).fields().unwrap()[0],
+            );
+            let r = call(&lib, "ppga_rotor_normalize", vec![rotor(&a)]).unwrap();
+            assert_close("rotor_normalize", &r, &rotor(&a.normalize()));
+
+            let p = Point::random();
+            let r = call(&lib, "ppga_point_normalize", vec![p.trivector.into()]).unwrap();
+            assert_close("point_normalize", &r, &p.normalize().trivector.into());
+            let p = Plane::random();
+            let r = call(&lib, "ppga_plane_normalize", vec![p.vector.into()]).unwrap();
+            assert_close("plane_normalize", &r, &p.normalize().vector.into());
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let lib = lib();
+        let e: Value = [1., 2., 3.].into();
+        assert!(call(&lib, "ppga_exp", vec![e.clone(), 1.0.into()]).is_err());
+        assert!(call(&lib, "ppga_exp", vec![e.clone()]).is_err());
+        assert!(call(&lib, "ppga_motor_mul", vec![e.clone(), e]).is_err());
+    }
+}
@@ -0,0 +1,814 @@
+//! Parses the GLSL subset glsl/ppga.glsl is written in and prints it as
+//! GLSL, WGSL or HLSL. The build script uses this to generate the WGSL and
+//! HLSL libraries, so this file must not depend on the rest of the crate
+//! except error.rs, which the build script includes as well.
+//!
+//! The subset: structs of floats and vectors, functions, declarations,
+//! (compound) assignments, if/else, return, arithmetic, comparisons, calls,
+//! constructors, member access and swizzles. Preprocessor lines are
+//! skipped. Comments directly above a struct or function are kept with it,
+//! other comments are kept in place.
+
+use crate::error::ParseError;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    Float,
+    /// vec2, vec3 or vec4.
+    Vec(u8),
+    Struct(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    Eq,
+    Ne,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// The literal as written.
+    Number(String),
+    Var(String),
+    /// A struct field or a swizzle.
+    Member(Box<Expr>, String),
+    /// A function, builtin or constructor call.
+    Call(String, Vec<Expr>),
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Decl(Type, String, Expr),
+    /// name op= expr, a plain assignment without op.
+    Assign(String, Option<BinOp>, Expr),
+    If(Expr, Vec<Stmt>, Vec<Stmt>),
+    Return(Expr),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Struct {
+    pub comment: Vec<String>,
+    pub name: String,
+    pub fields: Vec<(Type, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub comment: Vec<String>,
+    pub ret: Type,
+    pub name: String,
+    pub params: Vec<(Type, String)>,
+    pub body: Vec<Stmt>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Comment(Vec<String>),
+    Struct(Struct),
+    Function(Function),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Library {
+    pub items: Vec<Item>,
+}
+
+impl Library {
+    pub fn structs(&self) -> impl Iterator<Item = &Struct> {
+        self.items.iter().filter_map(|i| match i {
+            Item::Struct(s) => Some(s),
+            _ => None,
+        })
+    }
+
+    pub fn functions(&self) -> impl Iterator<Item = &Function> {
+        self.items.iter().filter_map(|i| match i {
+            Item::Function(f) => Some(f),
+            _ => None,
+        })
+    }
+
+    /// The function a call with these arguments resolves to. Overloads
+    /// are told apart by their number of arguments.
+    pub fn function(&self, name: &str, args: usize) -> Option<&Function> {
+        self.functions()
+            .find(|f| f.name == name && f.params.len() == args)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    Punct(&'static str),
+    /// The text after //.
+    Comment(String),
+}
+
+const PUNCTS: [&str; 22] = [
+    "+=", "-=", "*=", "/=", "<=", ">=", "==", "!=", "+", "-", "*", "/", "<", ">", "=", "(", ")",
+    "{", "}", ",", ";", ".",
+];
+
+/// Tokens with the line they start on.
+fn tokenize(src: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let mut tokens = Vec::new();
+    for (n, line) in src.lines().enumerate() {
+        let n = n + 1;
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        let mut rest = line;
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+            if let Some(comment) = rest.strip_prefix("//") {
+                tokens.push((Token::Comment(comment.trim_end().to_string()), n));
+                break;
+            }
+            let c = rest.chars().next().unwrap();
+            let len = if c.is_ascii_alphabetic() || c == '_' {
+                let len = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                tokens.push((Token::Ident(rest[..len].to_string()), n));
+                len
+            } else if c.is_ascii_digit()
+                || (c == '.' && rest[1..].starts_with(|c: char| c.is_ascii_digit()))
+            {
+                let len = number_len(rest);
+                tokens.push((Token::Number(rest[..len].to_string()), n));
+                len
+            } else if let Some(p) = PUNCTS.iter().find(|p| rest.starts_with(*p)) {
+                tokens.push((Token::Punct(p), n));
+                p.len()
+            } else {
+                return Err(ParseError {
+                    line: n,
+                    message: format!("unexpected character {:?}", c),
+                });
+            };
+            rest = &rest[len..];
+        }
+    }
+    Ok(tokens)
+}
+
+fn number_len(s: &str) -> usize {
+    let b = s.as_bytes();
+    let mut i = 0;
+    while i < b.len() && (b[i].is_ascii_digit() || b[i] == b'.') {
+        i += 1;
+    }
+    if i < b.len() && (b[i] == b'e' || b[i] == b'E') {
+        let mut j = i + 1;
+        if j < b.len() && (b[j] == b'+' || b[j] == b'-') {
+            j += 1;
+        }
+        if j < b.len() && b[j].is_ascii_digit() {
+            i = j;
+            while i < b.len() && b[i].is_ascii_digit() {
+                i += 1;
+            }
+        }
+    }
+    i
+}
+
+pub fn parse(src: &str) -> Result<Library, ParseError> {
+    let tokens = tokenize(src)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let mut items = Vec::new();
+    loop {
+        let (comment, last) = parser.comments();
+        if parser.peek().is_none() {
+            if !comment.is_empty() {
+                items.push(Item::Comment(comment));
+            }
+            break;
+        }
+        // A comment belongs to the item right below it.
+        let separate = matches!(parser.peek(), Some(Token::Comment(_))) || last + 1 < parser.line();
+        if !comment.is_empty() && separate {
+            items.push(Item::Comment(comment));
+            continue;
+        }
+        if parser.eat_ident("struct") {
+            items.push(Item::Struct(parser.parse_struct(comment)?));
+        } else {
+            items.push(Item::Function(parser.parse_function(comment)?));
+        }
+    }
+    Ok(Library { items })
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|t| &t.0)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map_or(0, |t| t.1)
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, ParseError> {
+        Err(ParseError {
+            line: self.line(),
+            message,
+        })
+    }
+
+    /// Consecutive comment lines at the current position, and the line
+    /// of the last one.
+    fn comments(&mut self) -> (Vec<String>, usize) {
+        let mut lines = Vec::new();
+        let mut last = 0;
+        while let Some((Token::Comment(c), n)) = self.tokens.get(self.pos) {
+            if !lines.is_empty() && *n > last + 1 {
+                break;
+            }
+            lines.push(c.clone());
+            last = *n;
+            self.pos += 1;
+        }
+        (lines, last)
+    }
+
+    fn next(&mut self) -> Result<Token, ParseError> {
+        // Comments inside structs and functions are dropped.
+        while let Some(Token::Comment(_)) = self.peek() {
+            self.pos += 1;
+        }
+        match self.tokens.get(self.pos) {
+            Some((t, _)) => {
+                self.pos += 1;
+                Ok(t.clone())
+            }
+            None => self.error("unexpected end of file".to_string()),
+        }
+    }
+
+    /// The n-th token after the current one, skipping comments.
+    fn peek_code_n(&self, n: usize) -> Option<&Token> {
+        self.tokens[self.pos..]
+            .iter()
+            .map(|t| &t.0)
+            .filter(|t| !matches!(t, Token::Comment(_)))
+            .nth(n)
+    }
+
+    fn peek_code(&self) -> Option<&Token> {
+        self.peek_code_n(0)
+    }
+
+    fn is_punct(&self, p: &str) -> bool {
+        matches!(self.peek_code(), Some(Token::Punct(q)) if *q == p)
+    }
+
+    fn eat_punct(&mut self, p: &str) -> bool {
+        if self.is_punct(p) {
+            self.next().unwrap();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_ident(&mut self, name: &str) -> bool {
+        if matches!(self.peek_code(), Some(Token::Ident(i)) if i == name) {
+            self.next().unwrap();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, p: &str) -> Result<(), ParseError> {
+        match self.next()? {
+            Token::Punct(q) if q == p => Ok(()),
+            t => self.error(format!("expected {:?}, found {:?}", p, t)),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, ParseError> {
+        match self.next()? {
+            Token::Ident(i) => Ok(i),
+            t => self.error(format!("expected a name, found {:?}", t)),
+        }
+    }
+
+    fn ty(&mut self) -> Result<Type, ParseError> {
+        let name = self.ident()?;
+        Ok(match name.as_str() {
+            "float" => Type::Float,
+            "vec2" => Type::Vec(2),
+            "vec3" => Type::Vec(3),
+            "vec4" => Type::Vec(4),
+            _ => Type::Struct(name),
+        })
+    }
+
+    fn parse_struct(&mut self, comment: Vec<String>) -> Result<Struct, ParseError> {
+        let name = self.ident()?;
+        self.expect("{")?;
+        let mut fields = Vec::new();
+        while !self.eat_punct("}") {
+            let ty = self.ty()?;
+            fields.push((ty, self.ident()?));
+            self.expect(";")?;
+        }
+        self.expect(";")?;
+        Ok(Struct {
+            comment,
+            name,
+            fields,
+        })
+    }
+
+    fn parse_function(&mut self, comment: Vec<String>) -> Result<Function, ParseError> {
+        let ret = self.ty()?;
+        let name = self.ident()?;
+        self.expect("(")?;
+        let mut params = Vec::new();
+        while !self.eat_punct(")") {
+            if !params.is_empty() {
+                self.expect(",")?;
+            }
+            let ty = self.ty()?;
+            params.push((ty, self.ident()?));
+        }
+        self.expect("{")?;
+        let body = self.block()?;
+        Ok(Function {
+            comment,
+            ret,
+            name,
+            params,
+            body,
+        })
+    }
+
+    /// Statements up to and including the closing brace.
+    fn block(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        let mut body = Vec::new();
+        while !self.eat_punct("}") {
+            body.push(self.stmt()?);
+        }
+        Ok(body)
+    }
+
+    /// A braced block or a single statement.
+    fn branch(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        if self.eat_punct("{") {
+            self.block()
+        } else {
+            Ok(vec![self.stmt()?])
+        }
+    }
+
+    fn stmt(&mut self) -> Result<Stmt, ParseError> {
+        if self.eat_ident("return") {
+            let e = self.expr()?;
+            self.expect(";")?;
+            return Ok(Stmt::Return(e));
+        }
+        if self.eat_ident("if") {
+            self.expect("(")?;
+            let cond = self.expr()?;
+            self.expect(")")?;
+            let then = self.branch()?;
+            let otherwise = if self.eat_ident("else") {
+                self.branch()?
+            } else {
+                Vec::new()
+            };
+            return Ok(Stmt::If(cond, then, otherwise));
+        }
+        // Two names in a row start a declaration.
+        if let Some(Token::Ident(_)) = self.peek_code_n(1) {
+            let ty = self.ty()?;
+            let name = self.ident()?;
+            self.expect("=")?;
+            let e = self.expr()?;
+            self.expect(";")?;
+            return Ok(Stmt::Decl(ty, name, e));
+        }
+        let name = self.ident()?;
+        let op = match self.next()? {
+            Token::Punct("=") => None,
+            Token::Punct("+=") => Some(BinOp::Add),
+            Token::Punct("-=") => Some(BinOp::Sub),
+            Token::Punct("*=") => Some(BinOp::Mul),
+            Token::Punct("/=") => Some(BinOp::Div),
+            t => return self.error(format!("expected a statement, found {:?}", t)),
+        };
+        let e = self.expr()?;
+        self.expect(";")?;
+        Ok(Stmt::Assign(name, op, e))
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        let lhs = self.additive()?;
+        let op = match self.peek_code() {
+            Some(Token::Punct("<")) => BinOp::Lt,
+            Some(Token::Punct(">")) => BinOp::Gt,
+            Some(Token::Punct("<=")) => BinOp::Le,
+            Some(Token::Punct(">=")) => BinOp::Ge,
+            Some(Token::Punct("==")) => BinOp::Eq,
+            Some(Token::Punct("!=")) => BinOp::Ne,
+            _ => return Ok(lhs),
+        };
+        self.next()?;
+        let rhs = self.additive()?;
+        Ok(Expr::Binary(op, Box::new(lhs), Box::new(rhs)))
+    }
+
+    fn additive(&mut self) -> Result<Expr, ParseError> {
+        let mut e = self.multiplicative()?;
+        loop {
+            let op = if self.eat_punct("+") {
+                BinOp::Add
+            } else if self.eat_punct("-") {
+                BinOp::Sub
+            } else {
+                return Ok(e);
+            };
+            e = Expr::Binary(op, Box::new(e), Box::new(self.multiplicative()?));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, ParseError> {
+        let mut e = self.unary()?;
+        loop {
+            let op = if self.eat_punct("*") {
+                BinOp::Mul
+            } else if self.eat_punct("/") {
+                BinOp::Div
+            } else {
+                return Ok(e);
+            };
+            e = Expr::Binary(op, Box::new(e), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.eat_punct("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        if self.eat_punct("+") {
+            return self.unary();
+        }
+        let mut e = self.primary()?;
+        while self.eat_punct(".") {
+            e = Expr::Member(Box::new(e), self.ident()?);
+        }
+        Ok(e)
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        match self.next()? {
+            Token::Number(n) => Ok(Expr::Number(n)),
+            Token::Punct("(") => {
+                let e = self.expr()?;
+                self.expect(")")?;
+                Ok(e)
+            }
+            Token::Ident(name) => {
+                if !self.eat_punct("(") {
+                    return Ok(Expr::Var(name));
+                }
+                let mut args = Vec::new();
+                while !self.eat_punct(")") {
+                    if !args.is_empty() {
+                        self.expect(",")?;
+                    }
+                    args.push(self.expr()?);
+                }
+                Ok(Expr::Call(name, args))
+            }
+            t => self.error(format!("expected an expression, found {:?}", t)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    Glsl,
+    Wgsl,
+    Hlsl,
+}
+
+/// Prints the library in the given language. Only the GLSL output keeps
+/// overloaded names, in WGSL an overload gets the name of its return type
+/// appended, e.g., ppga_cayley_exp_rotor.
+pub fn emit(lib: &Library, lang: Language) -> String {
+    let names = function_names(lib, lang);
+    let e = Emitter { lib, lang, names };
+    let mut out = String::new();
+    if lang != Language::Glsl {
+        out.push_str("// Generated from glsl/ppga.glsl, do not edit.\n\n");
+    }
+    for item in lib.items.iter() {
+        match item {
+            Item::Comment(c) => {
+                comment(&mut out, c);
+                out.push('\n');
+            }
+            Item::Struct(s) => {
+                comment(&mut out, &s.comment);
+                e.structure(&mut out, s);
+                out.push('\n');
+            }
+            Item::Function(f) => {
+                comment(&mut out, &f.comment);
+                e.function(&mut out, f);
+                out.push('\n');
+            }
+        }
+    }
+    out.pop();
+    out
+}
+
+fn comment(out: &mut String, lines: &[String]) {
+    for l in lines {
+        out.push_str("//");
+        out.push_str(l);
+        out.push('\n');
+    }
+}
+
+/// The name every function is emitted with, by index in lib.functions().
+fn function_names(lib: &Library, lang: Language) -> Vec<String> {
+    let mut count: HashMap<&str, usize> = HashMap::new();
+    lib.functions()
+        .map(|f| {
+            let n = count.entry(&f.name).or_insert(0);
+            *n += 1;
+            match (lang, &f.ret) {
+                (Language::Wgsl, Type::Struct(s)) if *n > 1 => {
+                    format!("{}_{}", f.name, s.trim_start_matches("ppga_"))
+                }
+                (Language::Wgsl, _) if *n > 1 => format!("{}_{}", f.name, *n),
+                _ => f.name.clone(),
+            }
+        })
+        .collect()
+}
+
+struct Emitter<'a> {
+    lib: &'a Library,
+    lang: Language,
+    names: Vec<String>,
+}
+
+impl<'a> Emitter<'a> {
+    fn ty(&self, t: &Type) -> String {
+        match (self.lang, t) {
+            (Language::Glsl, Type::Float) => "float".into(),
+            (Language::Glsl, Type::Vec(n)) => format!("vec{}", n),
+            (Language::Wgsl, Type::Float) => "f32".into(),
+            (Language::Wgsl, Type::Vec(n)) => format!("vec{}<f32>", n),
+            (Language::Hlsl, Type::Float) => "float".into(),
+            (Language::Hlsl, Type::Vec(n)) => format!("float{}", n),
+            (_, Type::Struct(s)) => s.clone(),
+        }
+    }
+
+    fn structure(&self, out: &mut String, s: &Struct) {
+        match self.lang {
+            Language::Wgsl => {
+                out.push_str(&format!("struct {} {{\n", s.name));
+                for (t, name) in s.fields.iter() {
+                    out.push_str(&format!("    {}: {},\n", name, self.ty(t)));
+                }
+                out.push_str("}\n");
+            }
+            Language::Glsl | Language::Hlsl => {
+                out.push_str(&format!("struct {}\n{{\n", s.name));
+                for (t, name) in s.fields.iter() {
+                    out.push_str(&format!("    {} {};\n", self.ty(t), name));
+                }
+                out.push_str("};\n");
+            }
+        }
+        if self.lang == Language::Hlsl {
+            // HLSL has no struct constructors, see expr.
+            let params: Vec<String> = s
+                .fields
+                .iter()
+                .map(|(t, n)| format!("{} {}", self.ty(t), n))
+                .collect();
+            out.push_str(&format!(
+                "\n{} make_{}({}) {{\n    {} r;\n",
+                s.name,
+                s.name,
+                params.join(", "),
+                s.name
+            ));
+            for (_, n) in s.fields.iter() {
+                out.push_str(&format!("    r.{} = {};\n", n, n));
+            }
+            out.push_str("    return r;\n}\n");
+        }
+    }
+
+    fn function(&self, out: &mut String, f: &Function) {
+        let index = self
+            .lib
+            .functions()
+            .position(|g| std::ptr::eq(f, g))
+            .unwrap();
+        let name = &self.names[index];
+        match self.lang {
+            Language::Wgsl => {
+                let params: Vec<String> = f
+                    .params
+                    .iter()
+                    .map(|(t, n)| format!("{}: {}", n, self.ty(t)))
+                    .collect();
+                out.push_str(&format!(
+                    "fn {}({}) -> {} {{\n",
+                    name,
+                    params.join(", "),
+                    self.ty(&f.ret)
+                ));
+            }
+            Language::Glsl | Language::Hlsl => {
+                let params: Vec<String> = f
+                    .params
+                    .iter()
+                    .map(|(t, n)| format!("{} {}", self.ty(t), n))
+                    .collect();
+                out.push_str(&format!(
+                    "{} {}({}) {{\n",
+                    self.ty(&f.ret),
+                    name,
+                    params.join(", ")
+                ));
+            }
+        }
+        let mut assigned = Vec::new();
+        collect_assigned(&f.body, &mut assigned);
+        self.block(out, &f.body, 1, &assigned);
+        out.push_str("}\n");
+    }
+
+    fn block(&self, out: &mut String, body: &[Stmt], depth: usize, assigned: &[String]) {
+        let indent = "    ".repeat(depth);
+        for stmt in body {
+            out.push_str(&indent);
+            match stmt {
+                Stmt::Decl(t, name, e) => {
+                    let e = self.expr(e, 0);
+                    match self.lang {
+                        Language::Wgsl => {
+                            // Only variables that change are var.
+                            let kind = if assigned.contains(name) {
+                                "var"
+                            } else {
+                                "let"
+                            };
+                            out.push_str(&format!("{} {}: {} = {};\n", kind, name, self.ty(t), e));
+                        }
+                        Language::Glsl | Language::Hlsl => {
+                            out.push_str(&format!("{} {} = {};\n", self.ty(t), name, e));
+                        }
+                    }
+                }
+                Stmt::Assign(name, op, e) => {
+                    let op = op.map_or("", op_str);
+                    out.push_str(&format!("{} {}= {};\n", name, op, self.expr(e, 0)));
+                }
+                Stmt::Return(e) => out.push_str(&format!("return {};\n", self.expr(e, 0))),
+                Stmt::If(cond, then, otherwise) => {
+                    out.push_str(&format!("if ({}) {{\n", self.expr(cond, 0)));
+                    self.block(out, then, depth + 1, assigned);
+                    if !otherwise.is_empty() {
+                        out.push_str(&format!("{}}} else {{\n", indent));
+                        self.block(out, otherwise, depth + 1, assigned);
+                    }
+                    out.push_str(&format!("{}}}\n", indent));
+                }
+            }
+        }
+    }
+
+    /// Prints e, in parentheses if it binds weaker than the context.
+    fn expr(&self, e: &Expr, context: u8) -> String {
+        match e {
+            Expr::Number(n) => match self.lang {
+                Language::Glsl => n.clone(),
+                // Integer literals would not convert implicitly everywhere.
+                _ if n.contains(&['.', 'e', 'E'][..]) => n.clone(),
+                _ => format!("{}.0", n),
+            },
+            Expr::Var(v) => v.clone(),
+            Expr::Member(e, m) => format!("{}.{}", self.expr(e, POSTFIX), m),
+            Expr::Neg(e) => format!("-{}", self.expr(e, UNARY)),
+            Expr::Call(name, args) => {
+                let args: Vec<String> = args.iter().map(|a| self.expr(a, 0)).collect();
+                format!("{}({})", self.callee(name, args.len()), args.join(", "))
+            }
+            Expr::Binary(op, a, b) => {
+                let p = precedence(*op);
+                // Left associative, so the right operand needs parentheses
+                // at the same precedence.
+                let s = format!(
+                    "{} {} {}",
+                    self.expr(a, p),
+                    op_str(*op),
+                    self.expr(b, p + 1)
+                );
+                if p < context {
+                    format!("({})", s)
+                } else {
+                    s
+                }
+            }
+        }
+    }
+
+    fn callee(&self, name: &str, args: usize) -> String {
+        if let Some(i) = self
+            .lib
+            .functions()
+            .position(|f| f.name == name && f.params.len() == args)
+        {
+            return self.names[i].clone();
+        }
+        let is_struct = self.lib.structs().any(|s| s.name == name);
+        match (self.lang, name) {
+            (Language::Hlsl, _) if is_struct => format!("make_{}", name),
+            (_, "atan") if args == 2 && self.lang != Language::Glsl => "atan2".into(),
+            (Language::Wgsl, "vec2") | (Language::Wgsl, "vec3") | (Language::Wgsl, "vec4") => {
+                format!("{}<f32>", name)
+            }
+            (Language::Hlsl, "vec2") | (Language::Hlsl, "vec3") | (Language::Hlsl, "vec4") => {
+                format!("float{}", &name[3..])
+            }
+            (Language::Wgsl, "float") => "f32".into(),
+            _ => name.into(),
+        }
+    }
+}
+
+const UNARY: u8 = 4;
+const POSTFIX: u8 = 5;
+
+fn precedence(op: BinOp) -> u8 {
+    match op {
+        BinOp::Lt | BinOp::Gt | BinOp::Le | BinOp::Ge | BinOp::Eq | BinOp::Ne => 1,
+        BinOp::Add | BinOp::Sub => 2,
+        BinOp::Mul | BinOp::Div => 3,
+    }
+}
+
+fn op_str(op: BinOp) -> &'static str {
+    match op {
+        BinOp::Add => "+",
+        BinOp::Sub => "-",
+        BinOp::Mul => "*",
+        BinOp::Div => "/",
+        BinOp::Lt => "<",
+        BinOp::Gt => ">",
+        BinOp::Le => "<=",
+        BinOp::Ge => ">=",
+        BinOp::Eq => "==",
+        BinOp::Ne => "!=",
+    }
+}
+
+fn collect_assigned(body: &[Stmt], names: &mut Vec<String>) {
+    for stmt in body {
+        match stmt {
+            Stmt::Assign(name, _, _) => names.push(name.clone()),
+            Stmt::If(_, then, otherwise) => {
+                collect_assigned(then, names);
+                collect_assigned(otherwise, names);
+            }
+            _ => {}
+        }
+    }
+}
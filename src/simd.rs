@@ -0,0 +1,618 @@
+//! SIMD versions of the motor product and the sandwich products. They use
+//! the PGA4CS blocks-of-four layout of glsl/ppga.glsl, i.e.,
+//!     p0 -> (e0, e1, e2, e3)
+//!     p1 -> (1, e23, e31, e12)
+//!     p2 -> (e0123, e01, e02, e03)
+//!     p3 -> (e123, e032, e013, e021)
+//! so that a motor is the two registers (p1, p2).
+//!
+//! Every kernel is written once, generic over F32x4, and instantiated with
+//! SSE on x86_64 (selected at compile time, SSE is always available there)
+//! and with plain [f32; 4] everywhere else. All instantiations do the
+//! same operations in the same order, so their results are bit-for-bit
+//! identical. The portable module always uses the [f32; 4] instantiation,
+//! which makes it possible to check the other paths against it.
+//!
+//! These are opt-in, Motor::mul and Motor::apply_to_* stay on the
+//! expressions generated into products. Both are the same polynomials, so
+//! they agree bit for bit whenever the arithmetic is exact, e.g., on small
+//! integers, and otherwise up to rounding, since they sum their terms in a
+//! different order.
+//!
+//! Motor::apply_to_points_mut multiplies by the point matrix of
+//! MotorMatrix, which uses AVX for two points at a time when the CPU has
+//! it (selected at run time), in the same order as the scalar matrix
+//! product.
+
+use super::{Line, Motor, Plane, Point};
+
+#[cfg(target_arch = "x86_64")]
+pub const BACKEND: &str = "sse";
+#[cfg(not(target_arch = "x86_64"))]
+pub const BACKEND: &str = "portable";
+
+#[cfg(target_arch = "x86_64")]
+type Native = sse::Sse;
+#[cfg(not(target_arch = "x86_64"))]
+type Native = Portable;
+
+pub fn mul(a: &Motor, b: &Motor) -> Motor {
+    mul_with::<Native>(a, b)
+}
+pub fn apply_to_point(m: &Motor, p: &Point) -> Point {
+    apply_to_point_with::<Native>(m, p)
+}
+pub fn apply_to_plane(m: &Motor, p: &Plane) -> Plane {
+    apply_to_plane_with::<Native>(m, p)
+}
+pub fn apply_to_line(m: &Motor, l: &Line) -> Line {
+    apply_to_line_with::<Native>(m, l)
+}
+
+/// The backend of Motor::apply_to_points_mut, "avx" when the CPU has it.
+pub fn batch_backend() -> &'static str {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        return "avx";
+    }
+    BACKEND
+}
+
+/// Multiplies all points by the 4x4 matrix m, see MotorMatrix.
+pub(crate) fn mul4_points(m: &[[f32; 4]; 4], ps: &mut [Point]) {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        // Safe, the CPU has AVX.
+        return unsafe { avx::mul4_points(m, ps) };
+    }
+    let cols = columns::<Native>(m);
+    for p in ps.iter_mut() {
+        p.trivector = mul4(&cols, Native::new(p.trivector)).get();
+    }
+}
+
+/// The same kernels, but always on [f32; 4].
+pub mod portable {
+    use super::*;
+
+    pub fn mul(a: &Motor, b: &Motor) -> Motor {
+        mul_with::<Portable>(a, b)
+    }
+    pub fn apply_to_point(m: &Motor, p: &Point) -> Point {
+        apply_to_point_with::<Portable>(m, p)
+    }
+    pub fn apply_to_plane(m: &Motor, p: &Plane) -> Plane {
+        apply_to_plane_with::<Portable>(m, p)
+    }
+    pub fn apply_to_line(m: &Motor, l: &Line) -> Line {
+        apply_to_line_with::<Portable>(m, l)
+    }
+}
+
+trait F32x4: Copy {
+    fn new(a: [f32; 4]) -> Self;
+    fn get(self) -> [f32; 4];
+    fn splat(x: f32) -> Self;
+    fn add(self, o: Self) -> Self;
+    fn sub(self, o: Self) -> Self;
+    fn mul(self, o: Self) -> Self;
+    /// Lane k of the result is lane (M >> 2k) & 3 of self, see sh.
+    fn swizzle<const M: i32>(self) -> Self;
+    /// Replaces lane 0 with lane 0 of o.
+    fn with_x(self, o: Self) -> Self;
+}
+
+/// Swizzle mask, the same encoding as _MM_SHUFFLE but in lane order.
+const fn sh(x: i32, y: i32, z: i32, w: i32) -> i32 {
+    x | (y << 2) | (z << 4) | (w << 6)
+}
+
+#[derive(Copy, Clone)]
+struct Portable([f32; 4]);
+
+impl F32x4 for Portable {
+    fn new(a: [f32; 4]) -> Self {
+        Self(a)
+    }
+    fn get(self) -> [f32; 4] {
+        self.0
+    }
+    fn splat(x: f32) -> Self {
+        Self([x; 4])
+    }
+    fn add(self, o: Self) -> Self {
+        let (a, b) = (self.0, o.0);
+        Self([a[0] + b[0], a[1] + b[1], a[2] + b[2], a[3] + b[3]])
+    }
+    fn sub(self, o: Self) -> Self {
+        let (a, b) = (self.0, o.0);
+        Self([a[0] - b[0], a[1] - b[1], a[2] - b[2], a[3] - b[3]])
+    }
+    fn mul(self, o: Self) -> Self {
+        let (a, b) = (self.0, o.0);
+        Self([a[0] * b[0], a[1] * b[1], a[2] * b[2], a[3] * b[3]])
+    }
+    fn swizzle<const M: i32>(self) -> Self {
+        let a = self.0;
+        let lane = |k: i32| a[((M >> (2 * k)) & 3) as usize];
+        Self([lane(0), lane(1), lane(2), lane(3)])
+    }
+    fn with_x(self, o: Self) -> Self {
+        let mut a = self.0;
+        a[0] = o.0[0];
+        Self(a)
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod sse {
+    // The intrinsics are only unsafe on older compilers,
+    // SSE itself is always available on x86_64.
+    #![allow(unused_unsafe)]
+    use std::arch::x86_64::*;
+
+    #[derive(Copy, Clone)]
+    pub struct Sse(__m128);
+
+    impl super::F32x4 for Sse {
+        #[inline(always)]
+        fn new(a: [f32; 4]) -> Self {
+            Self(unsafe { _mm_loadu_ps(a.as_ptr()) })
+        }
+        #[inline(always)]
+        fn get(self) -> [f32; 4] {
+            let mut a = [0.0; 4];
+            unsafe { _mm_storeu_ps(a.as_mut_ptr(), self.0) };
+            a
+        }
+        #[inline(always)]
+        fn splat(x: f32) -> Self {
+            Self(unsafe { _mm_set1_ps(x) })
+        }
+        #[inline(always)]
+        fn add(self, o: Self) -> Self {
+            Self(unsafe { _mm_add_ps(self.0, o.0) })
+        }
+        #[inline(always)]
+        fn sub(self, o: Self) -> Self {
+            Self(unsafe { _mm_sub_ps(self.0, o.0) })
+        }
+        #[inline(always)]
+        fn mul(self, o: Self) -> Self {
+            Self(unsafe { _mm_mul_ps(self.0, o.0) })
+        }
+        #[inline(always)]
+        fn swizzle<const M: i32>(self) -> Self {
+            Self(unsafe { _mm_shuffle_ps::<M>(self.0, self.0) })
+        }
+        #[inline(always)]
+        fn with_x(self, o: Self) -> Self {
+            Self(unsafe { _mm_move_ss(self.0, o.0) })
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx {
+    #![allow(unused_unsafe)]
+    use super::{columns, mul4, F32x4};
+    use crate::Point;
+    use std::arch::x86_64::*;
+
+    /// Two independent F32x4, one in each 128 bit half, so that every
+    /// kernel does exactly what it does with Sse, on two elements at once.
+    #[derive(Copy, Clone)]
+    pub struct Avx(__m256);
+
+    impl Avx {
+        #[inline(always)]
+        fn pair(a: [f32; 4], b: [f32; 4]) -> Self {
+            Self(unsafe { _mm256_set_m128(_mm_loadu_ps(b.as_ptr()), _mm_loadu_ps(a.as_ptr())) })
+        }
+        #[inline(always)]
+        fn split(self) -> ([f32; 4], [f32; 4]) {
+            let mut x = [0.0; 8];
+            unsafe { _mm256_storeu_ps(x.as_mut_ptr(), self.0) };
+            ([x[0], x[1], x[2], x[3]], [x[4], x[5], x[6], x[7]])
+        }
+    }
+
+    impl F32x4 for Avx {
+        #[inline(always)]
+        fn new(a: [f32; 4]) -> Self {
+            Self::pair(a, a)
+        }
+        #[inline(always)]
+        fn get(self) -> [f32; 4] {
+            self.split().0
+        }
+        #[inline(always)]
+        fn splat(x: f32) -> Self {
+            Self(unsafe { _mm256_set1_ps(x) })
+        }
+        #[inline(always)]
+        fn add(self, o: Self) -> Self {
+            Self(unsafe { _mm256_add_ps(self.0, o.0) })
+        }
+        #[inline(always)]
+        fn sub(self, o: Self) -> Self {
+            Self(unsafe { _mm256_sub_ps(self.0, o.0) })
+        }
+        #[inline(always)]
+        fn mul(self, o: Self) -> Self {
+            Self(unsafe { _mm256_mul_ps(self.0, o.0) })
+        }
+        #[inline(always)]
+        fn swizzle<const M: i32>(self) -> Self {
+            // Shuffles within each half, like _mm_shuffle_ps.
+            Self(unsafe { _mm256_permute_ps::<M>(self.0) })
+        }
+        #[inline(always)]
+        fn with_x(self, o: Self) -> Self {
+            Self(unsafe { _mm256_blend_ps::<0b0001_0001>(self.0, o.0) })
+        }
+    }
+
+    /// The CPU must have AVX.
+    #[target_feature(enable = "avx")]
+    pub unsafe fn mul4_points(m: &[[f32; 4]; 4], ps: &mut [Point]) {
+        let cols = columns::<Avx>(m);
+        let mut pairs = ps.chunks_exact_mut(2);
+        for pair in &mut pairs {
+            let p = Avx::pair(pair[0].trivector, pair[1].trivector);
+            let (a, b) = mul4(&cols, p).split();
+            pair[0].trivector = a;
+            pair[1].trivector = b;
+        }
+        for p in pairs.into_remainder() {
+            p.trivector = mul4(&cols, Avx::new(p.trivector)).get();
+        }
+    }
+}
+
+#[inline(always)]
+fn columns<V: F32x4>(m: &[[f32; 4]; 4]) -> [V; 4] {
+    let col = |k: usize| V::new([m[0][k], m[1][k], m[2][k], m[3][k]]);
+    [col(0), col(1), col(2), col(3)]
+}
+
+/// The matrix with columns cols times x, summed left to right like the
+/// scalar product in batch, so that both are the same bit for bit.
+#[inline(always)]
+fn mul4<V: F32x4>(cols: &[V; 4], x: V) -> V {
+    cols[0]
+        .mul(xxxx(x))
+        .add(cols[1].mul(x.swizzle::<{ sh(1, 1, 1, 1) }>()))
+        .add(cols[2].mul(x.swizzle::<{ sh(2, 2, 2, 2) }>()))
+        .add(cols[3].mul(x.swizzle::<{ sh(3, 3, 3, 3) }>()))
+}
+
+/// Sum of lanes 1..3, splatted.
+#[inline(always)]
+fn dot3<V: F32x4>(a: V, b: V) -> V {
+    let ab = a.mul(b);
+    ab.swizzle::<{ sh(1, 1, 1, 1) }>()
+        .add(ab.swizzle::<{ sh(2, 2, 2, 2) }>())
+        .add(ab.swizzle::<{ sh(3, 3, 3, 3) }>())
+}
+
+/// Cross product of lanes 1..3, lane 0 becomes zero.
+#[inline(always)]
+fn cross<V: F32x4>(a: V, b: V) -> V {
+    a.swizzle::<{ sh(0, 2, 3, 1) }>()
+        .mul(b.swizzle::<{ sh(0, 3, 1, 2) }>())
+        .sub(
+            a.swizzle::<{ sh(0, 3, 1, 2) }>()
+                .mul(b.swizzle::<{ sh(0, 2, 3, 1) }>()),
+        )
+}
+
+#[inline(always)]
+fn xxxx<V: F32x4>(a: V) -> V {
+    a.swizzle::<{ sh(0, 0, 0, 0) }>()
+}
+
+/// With (s, e) = a1 and (p, v) = a2:
+///     s = s1 s2 - e1.e2
+///     e = s1 e2 + s2 e1 - e1 x e2
+///     p = s1 p2 + s2 p1 + e1.v2 + v1.e2
+///     v = s1 v2 + s2 v1 - p2 e1 - p1 e2 - e1 x v2 - v1 x e2
+#[inline(always)]
+fn motor<V: F32x4>(a1: V, a2: V, b1: V, b2: V) -> (V, V) {
+    let neg_x = V::new([-1., 1., 1., 1.]);
+    let neg_yzw = V::new([1., -1., -1., -1.]);
+
+    let p1 = xxxx(a1)
+        .mul(b1)
+        .add(
+            a1.swizzle::<{ sh(1, 1, 2, 3) }>()
+                .mul(b1.swizzle::<{ sh(1, 0, 0, 0) }>())
+                .mul(neg_x),
+        )
+        .add(
+            a1.swizzle::<{ sh(2, 3, 1, 2) }>()
+                .mul(b1.swizzle::<{ sh(2, 2, 3, 1) }>())
+                .mul(neg_x),
+        )
+        .sub(
+            a1.swizzle::<{ sh(3, 2, 3, 1) }>()
+                .mul(b1.swizzle::<{ sh(3, 3, 1, 2) }>()),
+        );
+
+    let p2 = xxxx(a1)
+        .mul(b2)
+        .add(a2.mul(xxxx(b1)))
+        .add(
+            a1.swizzle::<{ sh(1, 1, 2, 3) }>()
+                .mul(b2.swizzle::<{ sh(1, 0, 0, 0) }>())
+                .mul(neg_yzw),
+        )
+        .add(
+            a2.swizzle::<{ sh(1, 0, 0, 0) }>()
+                .mul(b1.swizzle::<{ sh(1, 1, 2, 3) }>())
+                .mul(neg_yzw),
+        )
+        .add(
+            a1.swizzle::<{ sh(2, 3, 1, 2) }>()
+                .mul(b2.swizzle::<{ sh(2, 2, 3, 1) }>()),
+        )
+        .add(
+            a1.swizzle::<{ sh(3, 2, 3, 1) }>()
+                .mul(b2.swizzle::<{ sh(3, 3, 1, 2) }>())
+                .mul(neg_yzw),
+        )
+        .add(
+            a2.swizzle::<{ sh(2, 3, 1, 2) }>()
+                .mul(b1.swizzle::<{ sh(2, 2, 3, 1) }>()),
+        )
+        .add(
+            a2.swizzle::<{ sh(3, 2, 3, 1) }>()
+                .mul(b1.swizzle::<{ sh(3, 3, 1, 2) }>())
+                .mul(neg_yzw),
+        );
+    (p1, p2)
+}
+
+/// Rotation part of the sandwich, shared by all elements:
+///     (s^2 - e.e) x + 2 (e.x) e + 2 s (x cross e)
+#[inline(always)]
+fn rotate<V: F32x4>(m1: V, ss_ee: V, x: V) -> V {
+    let two = V::splat(2.);
+    let s = xxxx(m1);
+    ss_ee
+        .mul(x)
+        .add(two.mul(dot3(m1, x).mul(m1)))
+        .add(two.mul(s.mul(cross(x, m1))))
+}
+
+/// p3 = (w, x), lanes 1..3 become
+///     rotate(x) + 2 w (e x v - s v - p e)
+/// and the weight becomes w (s^2 + e.e).
+#[inline(always)]
+fn point<V: F32x4>(m1: V, m2: V, p3: V) -> V {
+    let two = V::splat(2.);
+    let s = xxxx(m1);
+    let ps = xxxx(m2);
+    let w = xxxx(p3);
+    let ss = s.mul(s);
+    let ee = dot3(m1, m1);
+    let t = cross(m1, m2).sub(s.mul(m2)).sub(ps.mul(m1));
+    rotate(m1, ss.sub(ee), p3)
+        .add(two.mul(w.mul(t)))
+        .with_x(w.mul(ss.add(ee)))
+}
+
+/// p0 = (d, n), lanes 1..3 become rotate(n) and
+///     d' = d (s^2 + e.e) + 2 (p (e.n) + s (v.n) + e.(v x n))
+#[inline(always)]
+fn plane<V: F32x4>(m1: V, m2: V, p0: V) -> V {
+    let two = V::splat(2.);
+    let s = xxxx(m1);
+    let ps = xxxx(m2);
+    let d = xxxx(p0);
+    let ss = s.mul(s);
+    let ee = dot3(m1, m1);
+    let d_ = d.mul(ss.add(ee)).add(
+        two.mul(
+            ps.mul(dot3(m1, p0))
+                .add(s.mul(dot3(m2, p0)))
+                .add(dot3(m1, cross(m2, p0))),
+        ),
+    );
+    rotate(m1, ss.sub(ee), p0).with_x(d_)
+}
+
+/// le = (0, e_bivector), lv = (0, v_bivector) of the line:
+///     e' = rotate(le)
+///     v' = rotate(lv) + 2 (v (e.le) + e (v.le) - le (e.v)
+///                          - p s le - p (le x e) + s (le x v))
+#[inline(always)]
+fn line<V: F32x4>(m1: V, m2: V, le: V, lv: V) -> (V, V) {
+    let two = V::splat(2.);
+    let zero = V::splat(0.);
+    let s = xxxx(m1);
+    let ps = xxxx(m2);
+    let ss_ee = s.mul(s).sub(dot3(m1, m1));
+    let t = m2
+        .mul(dot3(m1, le))
+        .add(m1.mul(dot3(m2, le)))
+        .sub(le.mul(dot3(m1, m2)))
+        .sub(ps.mul(s).mul(le))
+        .sub(ps.mul(cross(le, m1)))
+        .add(s.mul(cross(le, m2)));
+    let e = rotate(m1, ss_ee, le).with_x(zero);
+    let v = rotate(m1, ss_ee, lv).add(two.mul(t)).with_x(zero);
+    (e, v)
+}
+
+fn from_klein(p1: [f32; 4], p2: [f32; 4]) -> Motor {
+    Motor {
+        scalar: p1[0],
+        e_bivector: [p1[1], p1[2], p1[3]],
+        pseudo: p2[0],
+        v_bivector: [p2[1], p2[2], p2[3]],
+    }
+}
+
+#[inline(always)]
+fn mul_with<V: F32x4>(a: &Motor, b: &Motor) -> Motor {
+    let [a1, a2] = a.into_klein();
+    let [b1, b2] = b.into_klein();
+    let (p1, p2) = motor(V::new(a1), V::new(a2), V::new(b1), V::new(b2));
+    from_klein(p1.get(), p2.get())
+}
+
+#[inline(always)]
+fn apply_to_point_with<V: F32x4>(m: &Motor, p: &Point) -> Point {
+    let [m1, m2] = m.into_klein();
+    Point {
+        trivector: point(V::new(m1), V::new(m2), V::new(p.trivector)).get(),
+    }
+}
+
+#[inline(always)]
+fn apply_to_plane_with<V: F32x4>(m: &Motor, p: &Plane) -> Plane {
+    let [m1, m2] = m.into_klein();
+    Plane {
+        vector: plane(V::new(m1), V::new(m2), V::new(p.vector)).get(),
+    }
+}
+
+#[inline(always)]
+fn apply_to_line_with<V: F32x4>(m: &Motor, l: &Line) -> Line {
+    let [m1, m2] = m.into_klein();
+    let [e0, e1, e2] = l.e_bivector;
+    let [v0, v1, v2] = l.v_bivector;
+    let (e, v) = line(
+        V::new(m1),
+        V::new(m2),
+        V::new([0., e0, e1, e2]),
+        V::new([0., v0, v1, v2]),
+    );
+    let (e, v) = (e.get(), v.get());
+    Line {
+        e_bivector: [e[1], e[2], e[3]],
+        v_bivector: [v[1], v[2], v[3]],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn close(a: &[f32], b: &[f32]) -> bool {
+        a.iter()
+            .zip(b.iter())
+            .all(|(a, b)| (a - b).abs() <= 1e-4 * (1. + a.abs().max(b.abs())))
+    }
+    fn bits(a: &[f32]) -> Vec<u32> {
+        // Adding zero turns -0 into 0, the sign of a zero sum depends on
+        // the order of the terms.
+        a.iter().map(|x| (x + 0.).to_bits()).collect()
+    }
+    fn motor_coords(m: &Motor) -> Vec<f32> {
+        m.into_klein().iter().flatten().copied().collect()
+    }
+
+    // Small integers keep every operation exact, see the products tests.
+    fn int() -> f32 {
+        (rand::random::<u8>() % 17) as f32 - 8.
+    }
+    fn ints<const N: usize>() -> [f32; N] {
+        let mut x = [0.; N];
+        x.iter_mut().for_each(|x| *x = int());
+        x
+    }
+
+    #[test]
+    fn same_as_scalar() {
+        for _ in 0..1000 {
+            let a = Motor {
+                scalar: int(),
+                pseudo: int(),
+                e_bivector: ints(),
+                v_bivector: ints(),
+            };
+            let b = Motor {
+                scalar: int(),
+                pseudo: int(),
+                e_bivector: ints(),
+                v_bivector: ints(),
+            };
+            let p = Point { trivector: ints() };
+            let pl = Plane { vector: ints() };
+            let l = Line::from_tangent(&ints());
+
+            let m = simd::mul(&a, &b);
+            let scalar = products::geometric_motor_motor(&a, &b);
+            assert_eq!(bits(&motor_coords(&m)), bits(&motor_coords(&scalar)));
+
+            let p_ = simd::apply_to_point(&a, &p);
+            let scalar = products::sandwich_motor_point(&a, &p);
+            assert_eq!(bits(&p_.trivector), bits(&scalar.trivector));
+
+            let pl_ = simd::apply_to_plane(&a, &pl);
+            let scalar = products::sandwich_motor_plane(&a, &pl);
+            assert_eq!(bits(&pl_.vector), bits(&scalar.vector));
+
+            let l_ = simd::apply_to_line(&a, &l).tangent();
+            let scalar = products::sandwich_motor_line(&a, &l).tangent();
+            assert_eq!(bits(&l_), bits(&scalar));
+        }
+    }
+
+    #[test]
+    fn rounding() {
+        for _ in 0..1000 {
+            let a = Motor::random();
+            let b = Motor::random();
+            let p = Point::random();
+            let pl = Plane::random();
+            let l = Line::random();
+
+            // The native backend is the portable one bit for bit and the
+            // scalar one up to rounding.
+            let m = simd::mul(&a, &b);
+            assert_eq!(
+                bits(&motor_coords(&m)),
+                bits(&motor_coords(&simd::portable::mul(&a, &b)))
+            );
+            assert!(close(&motor_coords(&m), &motor_coords(&a.mul(&b))));
+
+            let p_ = simd::apply_to_point(&a, &p);
+            let p__ = simd::portable::apply_to_point(&a, &p);
+            assert_eq!(bits(&p_.trivector), bits(&p__.trivector));
+            assert!(close(&p_.trivector, &a.apply_to_point(&p).trivector));
+
+            let pl_ = simd::apply_to_plane(&a, &pl);
+            let pl__ = simd::portable::apply_to_plane(&a, &pl);
+            assert_eq!(bits(&pl_.vector), bits(&pl__.vector));
+            assert!(close(&pl_.vector, &a.apply_to_plane(&pl).vector));
+
+            let l_ = simd::apply_to_line(&a, &l).tangent();
+            let l__ = simd::portable::apply_to_line(&a, &l).tangent();
+            assert_eq!(bits(&l_), bits(&l__));
+            assert!(close(&l_, &a.apply_to_line(&l).tangent()));
+        }
+    }
+
+    #[test]
+    fn batch() {
+        let m = Motor::random();
+        // Odd, so that AVX also moves a single point.
+        let mut ps: Vec<Point> = (0..101).map(|_| Point::random()).collect();
+        let mat = m.matrix().point;
+        let moved: Vec<[f32; 4]> = ps
+            .iter()
+            .map(|p| {
+                let x = p.trivector;
+                let row = |r: [f32; 4]| r[0] * x[0] + r[1] * x[1] + r[2] * x[2] + r[3] * x[3];
+                [row(mat[0]), row(mat[1]), row(mat[2]), row(mat[3])]
+            })
+            .collect();
+        m.apply_to_points_mut(&mut ps);
+        for (p, p_) in ps.iter().zip(moved.iter()) {
+            assert_eq!(bits(&p.trivector), bits(p_));
+        }
+    }
+}
@@ -0,0 +1,324 @@
+//! Joint hierarchies and their animation. Joints hold motors relative to
+//! their parent, and the world motors follow by composition from the root,
+//! world[i] = world[parent] * local[i].
+//!
+//! Clips interpolate keyframes along the screw between them,
+//! a * exp(s ln(a^-1 b)), and additive layers are deltas in the log domain
+//! that are scaled by their weight before being added.
+
+use super::{Line, Motor};
+
+#[derive(Debug, Clone)]
+pub struct Joint {
+    pub name: String,
+    /// Always before the joint itself, the root has none.
+    pub parent: Option<usize>,
+    /// The rest pose, relative to the parent.
+    pub local: Motor,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Skeleton {
+    pub joints: Vec<Joint>,
+}
+
+impl Skeleton {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the index of the joint. Parents have to be added first.
+    pub fn add_joint(&mut self, name: &str, parent: Option<usize>, local: &Motor) -> usize {
+        if let Some(p) = parent {
+            assert!(p < self.joints.len(), "parent {} not added yet", p);
+        }
+        self.joints.push(Joint {
+            name: name.to_string(),
+            parent,
+            local: *local,
+        });
+        self.joints.len() - 1
+    }
+
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.joints.iter().position(|j| j.name == name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.joints.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.joints.is_empty()
+    }
+
+    /// The local motors of the rest pose.
+    pub fn rest(&self) -> Vec<Motor> {
+        self.joints.iter().map(|j| j.local).collect()
+    }
+
+    /// The world motors of the rest pose, e.g., the bind pose for skinning.
+    pub fn world(&self) -> Vec<Motor> {
+        self.world_from(&self.rest())
+    }
+
+    /// The world motors for the given local motors, one per joint.
+    pub fn world_from(&self, local: &[Motor]) -> Vec<Motor> {
+        assert_eq!(local.len(), self.joints.len());
+        let mut world: Vec<Motor> = Vec::with_capacity(local.len());
+        for (j, m) in self.joints.iter().zip(local.iter()) {
+            let w = match j.parent {
+                Some(p) => world[p].mul(m),
+                None => *m,
+            };
+            world.push(w);
+        }
+        world
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    pub time: f32,
+    pub motor: Motor,
+}
+
+/// The keyframes of one joint, sorted by time.
+#[derive(Debug, Clone, Default)]
+pub struct Track {
+    pub keys: Vec<Keyframe>,
+}
+
+impl Track {
+    /// Clamped to the first and last key. None without keys.
+    pub fn sample(&self, t: f32) -> Option<Motor> {
+        let i = self.keys.partition_point(|k| k.time <= t);
+        if i == 0 {
+            return self.keys.first().map(|k| k.motor);
+        }
+        if i == self.keys.len() {
+            return self.keys.last().map(|k| k.motor);
+        }
+        let (a, b) = (&self.keys[i - 1], &self.keys[i]);
+        Some(interpolate(
+            &a.motor,
+            &b.motor,
+            (t - a.time) / (b.time - a.time),
+        ))
+    }
+}
+
+/// Local motors over time, one track per joint. Joints with empty tracks
+/// keep the motor they are sampled with, e.g., the rest pose.
+#[derive(Debug, Clone, Default)]
+pub struct AnimationClip {
+    pub tracks: Vec<Track>,
+}
+
+impl AnimationClip {
+    pub fn new(joints: usize) -> Self {
+        Self {
+            tracks: vec![Track::default(); joints],
+        }
+    }
+
+    /// Keeps the track sorted.
+    pub fn add_key(&mut self, joint: usize, time: f32, motor: &Motor) {
+        let keys = &mut self.tracks[joint].keys;
+        let i = keys.partition_point(|k| k.time <= time);
+        keys.insert(
+            i,
+            Keyframe {
+                time,
+                motor: *motor,
+            },
+        );
+    }
+
+    /// The time of the last key.
+    pub fn duration(&self) -> f32 {
+        self.tracks
+            .iter()
+            .filter_map(|t| t.keys.last())
+            .map(|k| k.time)
+            .fold(0.0, f32::max)
+    }
+
+    pub fn sample(&self, t: f32, rest: &[Motor]) -> Vec<Motor> {
+        assert_eq!(rest.len(), self.tracks.len());
+        self.tracks
+            .iter()
+            .zip(rest.iter())
+            .map(|(track, r)| track.sample(t).unwrap_or(*r))
+            .collect()
+    }
+
+    /// Fades from self into other, which starts at start and takes over
+    /// completely after length. A length of 0 cuts over at start.
+    pub fn crossfade(
+        &self,
+        other: &Self,
+        t: f32,
+        start: f32,
+        length: f32,
+        rest: &[Motor],
+    ) -> Vec<Motor> {
+        let s = if length > 0.0 {
+            ((t - start) / length).clamp(0.0, 1.0)
+        } else if t < start {
+            0.0
+        } else {
+            1.0
+        };
+        mix(&self.sample(t, rest), &other.sample(t - start, rest), s)
+    }
+
+    /// Self as an additive layer, its deltas to the reference pose.
+    pub fn difference(&self, t: f32, reference: &[Motor]) -> Vec<Line> {
+        difference(&self.sample(t, reference), reference)
+    }
+}
+
+/// Along the screw from a to b, taking the short way around.
+pub fn interpolate(a: &Motor, b: &Motor, s: f32) -> Motor {
+    let b = if a.rotor_dot(b) < 0.0 { b.neg() } else { *b };
+    let d = a.reverse().mul(&b).normalize().make_scalar_positive();
+    a.mul(&d.ln().mul_scalar(s).exp())
+}
+
+/// Interpolates every joint, 0 gives a and 1 gives b.
+pub fn mix(a: &[Motor], b: &[Motor], s: f32) -> Vec<Motor> {
+    assert_eq!(a.len(), b.len());
+    a.iter()
+        .zip(b.iter())
+        .map(|(a, b)| interpolate(a, b, s))
+        .collect()
+}
+
+/// The local deltas that take the reference to the pose, ln(r^-1 p).
+pub fn difference(pose: &[Motor], reference: &[Motor]) -> Vec<Line> {
+    assert_eq!(pose.len(), reference.len());
+    pose.iter()
+        .zip(reference.iter())
+        .map(|(p, r)| r.reverse().mul(p).normalize().make_scalar_positive().ln())
+        .collect()
+}
+
+/// Adds the weighted deltas onto the pose, p exp(w d).
+pub fn add(pose: &[Motor], deltas: &[Line], weight: f32) -> Vec<Motor> {
+    assert_eq!(pose.len(), deltas.len());
+    pose.iter()
+        .zip(deltas.iter())
+        .map(|(p, d)| p.mul(&d.mul_scalar(weight).exp()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::skeleton::*;
+    use crate::*;
+
+    fn arm() -> Skeleton {
+        let mut s = Skeleton::new();
+        let root = s.add_joint("root", None, &Motor::from(&Translator::new(&[0., 1., 0.])));
+        let shoulder = s.add_joint(
+            "shoulder",
+            Some(root),
+            &Translator::new(&[1., 0., 0.]).mul_rotor(&Rotor::new(0.5, &[0., 0., 1.])),
+        );
+        s.add_joint(
+            "hand",
+            Some(shoulder),
+            &Motor::from(&Translator::new(&[2., 0., 0.])),
+        );
+        s.add_joint(
+            "head",
+            Some(root),
+            &Motor::from(&Translator::new(&[0., 1., 0.])),
+        );
+        s
+    }
+
+    fn rotation(a: f32) -> Motor {
+        Motor::from(&Rotor::new(a, &[0., 0., 1.]))
+    }
+
+    #[test]
+    fn world() {
+        let s = arm();
+        assert_eq!(s.find("hand"), Some(2));
+        assert_eq!(s.find("foot"), None);
+        let world = s.world();
+        let chain = batch::chain(&s.rest()[..3]);
+        for i in 0..3 {
+            assert_eq!(world[i], chain[i]);
+        }
+        assert_eq!(world[3], Motor::from(&Translator::new(&[0., 2., 0.])));
+        // The hand is 2 along the rotated x axis from the shoulder.
+        let hand = world[2].apply_to_point(&Point::new(&[0., 0., 0.]));
+        let dir = Rotor::new(0.5, &[0., 0., 1.])
+            .apply_to_point(&Point::inf(&[2., 0., 0.]))
+            .eucl();
+        assert_eq!(hand, Point::new(&[1. + dir[0], 1. + dir[1], 0.]));
+    }
+
+    #[test]
+    fn sampling() {
+        let s = arm();
+        let mut clip = AnimationClip::new(s.len());
+        clip.add_key(1, 1., &rotation(1.));
+        clip.add_key(1, 0., &rotation(0.));
+        let screw = Translator::new(&[0., 0., 2.]).mul_rotor(&Rotor::new(1., &[0., 0., 1.]));
+        clip.add_key(2, 0., &Motor::one());
+        clip.add_key(2, 2., &screw);
+        assert_eq!(clip.duration(), 2.);
+
+        let pose = clip.sample(0.5, &s.rest());
+        assert_eq!(pose[0], s.rest()[0]);
+        assert_eq!(pose[1], rotation(0.5));
+        assert_eq!(
+            pose[2],
+            Translator::new(&[0., 0., 0.5]).mul_rotor(&Rotor::new(0.25, &[0., 0., 1.]))
+        );
+        assert_eq!(clip.sample(-1., &s.rest())[1], rotation(0.));
+        assert_eq!(clip.sample(3., &s.rest())[2], screw);
+        // Screws around an axis off the origin.
+        let off = Motor::from(&Translator::new(&[1., 2., 0.]));
+        let moved = off.mul(&screw).mul(&off.reverse());
+        assert_eq!(moved.ln().exp(), moved);
+        assert_eq!(
+            interpolate(&Motor::one(), &moved, 0.25),
+            off.mul(&pose[2]).mul(&off.reverse())
+        );
+        // The other half of the double cover takes the same path.
+        assert_eq!(
+            interpolate(&rotation(0.), &rotation(1.).neg(), 0.5),
+            rotation(0.5)
+        );
+    }
+
+    #[test]
+    fn blending() {
+        let s = arm();
+        let (mut a, mut b) = (AnimationClip::new(s.len()), AnimationClip::new(s.len()));
+        a.add_key(1, 0., &rotation(0.));
+        b.add_key(1, 0., &rotation(1.));
+        let fade = |t| a.crossfade(&b, t, 1., 2., &s.rest())[1];
+        assert_eq!(fade(0.5), rotation(0.));
+        assert_eq!(fade(2.), rotation(0.5));
+        assert_eq!(fade(4.), rotation(1.));
+        let cut = |t| a.crossfade(&b, t, 1., 0., &s.rest())[1];
+        assert_eq!(cut(0.5), rotation(0.));
+        assert_eq!(cut(1.), rotation(1.));
+
+        // Relative to a reference rotated by 0.5, b adds another 0.5.
+        let mut reference = s.rest();
+        reference[1] = rotation(0.5);
+        let deltas = b.difference(0., &reference);
+        let base = a.sample(0., &s.rest());
+        assert_eq!(add(&base, &deltas, 0.)[1], rotation(0.));
+        assert_eq!(add(&base, &deltas, 1.)[1], rotation(0.5));
+        assert_eq!(add(&base, &deltas, 2.)[1], rotation(1.));
+        assert_eq!(add(&reference, &deltas, 1.)[1], rotation(1.));
+        assert_eq!(add(&base, &deltas, 1.)[2], base[2]);
+    }
+}
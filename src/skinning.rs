@@ -0,0 +1,186 @@
+//! Skinning with motors, the PGA version of dual quaternion skinning.
+//!
+//! Every vertex is moved by a blend of the motors of the joints it is bound
+//! to. The blend is the weighted sum of the motors made rigid again, which
+//! unlike blending matrices keeps volume at twisting joints. A motor and
+//! its negation are the same motion, so every motor is first flipped into
+//! the half of the pivot, the one with the largest weight.
+
+use super::{Motor, Point};
+
+/// How many joints a vertex can be bound to.
+pub const MAX_INFLUENCES: usize = 4;
+
+/// Joints and weights of a vertex. Unused slots have weight 0.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Weights {
+    pub joints: [usize; MAX_INFLUENCES],
+    pub weights: [f32; MAX_INFLUENCES],
+}
+
+impl Weights {
+    /// Bound to a single joint.
+    pub fn single(joint: usize) -> Self {
+        Self {
+            joints: [joint, 0, 0, 0],
+            weights: [1., 0., 0., 0.],
+        }
+    }
+}
+
+/// The motors that move the joints from the bind pose into the pose, i.e.,
+/// pose[i] * bind[i]^-1 for joint motors in world space.
+pub fn rig(bind: &[Motor], pose: &[Motor]) -> Vec<Motor> {
+    assert_eq!(bind.len(), pose.len());
+    bind.iter()
+        .zip(pose.iter())
+        .map(|(b, p)| p.mul(&b.reverse()))
+        .collect()
+}
+
+/// The blend of the motors, see the module documentation. The identity
+/// when the weights sum to zero, e.g., for Weights::default().
+pub fn blend(motors: &[Motor], weights: &[f32]) -> Motor {
+    assert_eq!(motors.len(), weights.len());
+    if weights.iter().sum::<f32>() == 0.0 {
+        return Motor::one();
+    }
+    let pivot = match weights.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)) {
+        Some((i, _)) => motors[i],
+        None => return Motor::one(),
+    };
+    let mut sum = Motor::zero();
+    for (m, w) in motors.iter().zip(weights.iter()) {
+        let w = if m.rotor_dot(&pivot) < 0.0 { -w } else { *w };
+        sum = sum.add(&m.mul_scalar(w));
+    }
+    sum.rigid_normalize()
+}
+
+/// The blended motor of a vertex.
+pub fn vertex_motor(joints: &[Motor], w: &Weights) -> Motor {
+    blend(&w.joints.map(|j| joints[j]), &w.weights)
+}
+
+/// Skinned positions and normals. There may be no normals, otherwise one
+/// per position.
+pub fn skin(
+    joints: &[Motor],
+    weights: &[Weights],
+    positions: &[[f32; 3]],
+    normals: &[[f32; 3]],
+) -> (Vec<[f32; 3]>, Vec<[f32; 3]>) {
+    let mut positions = positions.to_vec();
+    let mut normals = normals.to_vec();
+    skin_mut(joints, weights, &mut positions, &mut normals);
+    (positions, normals)
+}
+
+pub fn skin_mut(
+    joints: &[Motor],
+    weights: &[Weights],
+    positions: &mut [[f32; 3]],
+    normals: &mut [[f32; 3]],
+) {
+    assert_eq!(weights.len(), positions.len());
+    assert!(normals.is_empty() || normals.len() == positions.len());
+    for (i, (w, x)) in weights.iter().zip(positions.iter_mut()).enumerate() {
+        skin_vertex(joints, w, x, normals.get_mut(i));
+    }
+}
+
+/// Normals are directions, points at infinity, which only rotate.
+pub(crate) fn skin_vertex(
+    joints: &[Motor],
+    w: &Weights,
+    x: &mut [f32; 3],
+    n: Option<&mut [f32; 3]>,
+) {
+    let m = vertex_motor(joints, w);
+    *x = m.apply_to_point(&Point::new(x)).normalize().eucl();
+    if let Some(n) = n {
+        *n = m.apply_to_point(&Point::inf(n)).eucl();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::skinning::*;
+    use crate::*;
+
+    fn close(a: &[f32; 3], b: &[f32; 3]) -> bool {
+        a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 1e-4)
+    }
+
+    #[test]
+    fn blending() {
+        let m = Translator::new(&[1., 2., 3.]).mul_rotor(&Rotor::new(1., &[0., 1., 0.]));
+        assert_eq!(blend(&[m], &[1.]), m);
+        assert_eq!(
+            blend(&[m, m.neg()], &[0.3, 0.7]).make_scalar_positive(),
+            m.make_scalar_positive()
+        );
+        let a = Motor::from(&Rotor::new(0., &[0., 0., 1.]));
+        let b = Motor::from(&Rotor::new(1.2, &[0., 0., 1.]));
+        assert_eq!(
+            blend(&[a, b], &[0.5, 0.5]),
+            Motor::from(&Rotor::new(0.6, &[0., 0., 1.]))
+        );
+        assert_eq!(
+            blend(&[a, b.neg()], &[0.5, 0.5]).make_scalar_positive(),
+            Motor::from(&Rotor::new(0.6, &[0., 0., 1.]))
+        );
+        // Blending screws keeps the result rigid.
+        let c = Motor::from(&Translator::new(&[0., 0., 2.])).mul(&b);
+        let d = blend(&[a, c], &[0.3, 0.7]);
+        let e = d.e_bivector;
+        let v = d.v_bivector;
+        assert!((e[0] * v[0] + e[1] * v[1] + e[2] * v[2] - d.scalar * d.pseudo).abs() < 1e-5);
+        // No influences at all.
+        assert_eq!(vertex_motor(&[c], &Weights::default()), Motor::one());
+        assert_eq!(blend(&[], &[]), Motor::one());
+    }
+
+    #[test]
+    fn skinning() {
+        let bind = [Motor::one(), Motor::from(&Translator::new(&[0., 1., 0.]))];
+        let bend = Rotor::new(std::f32::consts::FRAC_PI_2, &[0., 0., 1.]);
+        let pose = [
+            Motor::one(),
+            Translator::new(&[0., 1., 0.]).mul_rotor(&bend),
+        ];
+        let joints = rig(&bind, &pose);
+        assert_eq!(rig(&bind, &bind)[1], Motor::one());
+        let weights = [
+            Weights::single(0),
+            Weights::single(1),
+            Weights {
+                joints: [0, 1, 0, 0],
+                weights: [0.5, 0.5, 0., 0.],
+            },
+        ];
+        let positions = [[0.5, 0.5, 0.], [0., 2., 0.], [0., 1., 0.]];
+        let normals = [[1., 0., 0.]; 3];
+        let (p, n) = skin(&joints, &weights, &positions, &normals);
+        assert!(close(&p[0], &[0.5, 0.5, 0.]));
+        assert!(close(&n[0], &[1., 0., 0.]));
+        // Rotated around the joint at (0, 1, 0).
+        let rotated = bend.apply_to_point(&Point::new(&[0., 1., 0.])).eucl();
+        assert!(close(&p[1], &[rotated[0], rotated[1] + 1., 0.]));
+        assert!(close(
+            &n[1],
+            &bend.apply_to_point(&Point::inf(&[1., 0., 0.])).eucl()
+        ));
+        // At the joint, halfway the normal is rotated by half the angle.
+        assert!(close(&p[2], &[0., 1., 0.]));
+        let half = Rotor::new(std::f32::consts::FRAC_PI_4, &[0., 0., 1.]);
+        assert!(close(
+            &n[2],
+            &half.apply_to_point(&Point::inf(&[1., 0., 0.])).eucl()
+        ));
+
+        let (p_, n_) = skin(&joints, &weights, &positions, &[]);
+        assert_eq!(p, p_);
+        assert!(n_.is_empty());
+    }
+}
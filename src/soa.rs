@@ -0,0 +1,530 @@
+//! Structure-of-arrays containers for large numbers of points, lines and
+//! motors, e.g., particles. Every basis element gets its own array. The
+//! kernels below load LANES elements at a time into small fixed-size arrays
+//! and run the scalar implementation on every lane, which the compiler
+//! vectorizes across lanes. The products are the generated ones, which have
+//! no branches.
+
+use super::{products, Line, Motor, Point};
+
+/// Points, one array per trivector component.
+#[derive(Debug, Clone, Default)]
+pub struct PointSoA {
+    pub e123: Vec<f32>,
+    pub e032: Vec<f32>,
+    pub e013: Vec<f32>,
+    pub e021: Vec<f32>,
+}
+
+/// Lines, one array per bivector component.
+#[derive(Debug, Clone, Default)]
+pub struct LineSoA {
+    pub e23: Vec<f32>,
+    pub e31: Vec<f32>,
+    pub e12: Vec<f32>,
+    pub e01: Vec<f32>,
+    pub e02: Vec<f32>,
+    pub e03: Vec<f32>,
+}
+
+/// Motors, one array per component.
+#[derive(Debug, Clone, Default)]
+pub struct MotorSoA {
+    pub scalar: Vec<f32>,
+    pub e23: Vec<f32>,
+    pub e31: Vec<f32>,
+    pub e12: Vec<f32>,
+    pub e01: Vec<f32>,
+    pub e02: Vec<f32>,
+    pub e03: Vec<f32>,
+    pub e0123: Vec<f32>,
+}
+
+impl PointSoA {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_capacity(n: usize) -> Self {
+        Self {
+            e123: Vec::with_capacity(n),
+            e032: Vec::with_capacity(n),
+            e013: Vec::with_capacity(n),
+            e021: Vec::with_capacity(n),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.e123.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.e123.is_empty()
+    }
+    pub fn push(&mut self, p: &Point) {
+        let t = p.trivector;
+        self.e123.push(t[0]);
+        self.e032.push(t[1]);
+        self.e013.push(t[2]);
+        self.e021.push(t[3]);
+    }
+    pub fn get(&self, i: usize) -> Point {
+        Point {
+            trivector: [self.e123[i], self.e032[i], self.e013[i], self.e021[i]],
+        }
+    }
+    pub fn set(&mut self, i: usize, p: &Point) {
+        let t = p.trivector;
+        self.e123[i] = t[0];
+        self.e032[i] = t[1];
+        self.e013[i] = t[2];
+        self.e021[i] = t[3];
+    }
+    pub fn iter(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.len()).map(move |i| self.get(i))
+    }
+
+    /// Moves every point with the same motor.
+    pub fn apply_motor(&mut self, m: &Motor) {
+        // The sandwich is linear in the point, see batch::MotorMatrix.
+        let m = m.matrix();
+        lanes_mut(self.slices_mut(), |t| {
+            m.apply_to_point(&Point { trivector: t }).trivector
+        });
+    }
+
+    /// Divides every point by its e123 component.
+    pub fn normalize(&mut self) {
+        lanes_mut(self.slices_mut(), |t| {
+            let f = 1. / t[0];
+            [1., t[1] * f, t[2] * f, t[3] * f]
+        });
+    }
+
+    fn slices(&self) -> [&[f32]; 4] {
+        [&self.e123, &self.e032, &self.e013, &self.e021]
+    }
+    fn slices_mut(&mut self) -> [&mut [f32]; 4] {
+        [
+            &mut self.e123,
+            &mut self.e032,
+            &mut self.e013,
+            &mut self.e021,
+        ]
+    }
+}
+
+impl LineSoA {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn len(&self) -> usize {
+        self.e23.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.e23.is_empty()
+    }
+    pub fn push(&mut self, l: &Line) {
+        let e = l.e_bivector;
+        let v = l.v_bivector;
+        self.e23.push(e[0]);
+        self.e31.push(e[1]);
+        self.e12.push(e[2]);
+        self.e01.push(v[0]);
+        self.e02.push(v[1]);
+        self.e03.push(v[2]);
+    }
+    pub fn get(&self, i: usize) -> Line {
+        Line {
+            e_bivector: [self.e23[i], self.e31[i], self.e12[i]],
+            v_bivector: [self.e01[i], self.e02[i], self.e03[i]],
+        }
+    }
+    pub fn set(&mut self, i: usize, l: &Line) {
+        let e = l.e_bivector;
+        let v = l.v_bivector;
+        self.e23[i] = e[0];
+        self.e31[i] = e[1];
+        self.e12[i] = e[2];
+        self.e01[i] = v[0];
+        self.e02[i] = v[1];
+        self.e03[i] = v[2];
+    }
+    pub fn iter(&self) -> impl Iterator<Item = Line> + '_ {
+        (0..self.len()).map(move |i| self.get(i))
+    }
+
+    /// Normalizes the euclidean part of every line.
+    pub fn normalize(&mut self) {
+        lanes_mut(self.slices_mut(), |t| {
+            let f = 1. / (t[0] * t[0] + t[1] * t[1] + t[2] * t[2]).sqrt();
+            [t[0] * f, t[1] * f, t[2] * f, t[3], t[4], t[5]]
+        });
+    }
+
+    /// Line::exp of every line. Both branches of Line::exp are computed on
+    /// every lane and selected, so that the loop has none.
+    pub fn exp(&self) -> MotorSoA {
+        let mut out = MotorSoA::zeros(self.len());
+        lanes(self.slices(), [], out.slices_mut(), |t, []| exp(&t));
+        out
+    }
+
+    /// The component arrays in the order of Line::tangent.
+    fn slices(&self) -> [&[f32]; 6] {
+        [
+            &self.e23, &self.e31, &self.e12, &self.e01, &self.e02, &self.e03,
+        ]
+    }
+    fn slices_mut(&mut self) -> [&mut [f32]; 6] {
+        [
+            &mut self.e23,
+            &mut self.e31,
+            &mut self.e12,
+            &mut self.e01,
+            &mut self.e02,
+            &mut self.e03,
+        ]
+    }
+}
+
+impl MotorSoA {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn zeros(n: usize) -> Self {
+        Self {
+            scalar: vec![0.; n],
+            e23: vec![0.; n],
+            e31: vec![0.; n],
+            e12: vec![0.; n],
+            e01: vec![0.; n],
+            e02: vec![0.; n],
+            e03: vec![0.; n],
+            e0123: vec![0.; n],
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.scalar.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.scalar.is_empty()
+    }
+    pub fn push(&mut self, m: &Motor) {
+        self.scalar.push(m.scalar);
+        self.e23.push(m.e_bivector[0]);
+        self.e31.push(m.e_bivector[1]);
+        self.e12.push(m.e_bivector[2]);
+        self.e01.push(m.v_bivector[0]);
+        self.e02.push(m.v_bivector[1]);
+        self.e03.push(m.v_bivector[2]);
+        self.e0123.push(m.pseudo);
+    }
+    pub fn get(&self, i: usize) -> Motor {
+        Motor {
+            scalar: self.scalar[i],
+            e_bivector: [self.e23[i], self.e31[i], self.e12[i]],
+            v_bivector: [self.e01[i], self.e02[i], self.e03[i]],
+            pseudo: self.e0123[i],
+        }
+    }
+    pub fn set(&mut self, i: usize, m: &Motor) {
+        self.scalar[i] = m.scalar;
+        self.e23[i] = m.e_bivector[0];
+        self.e31[i] = m.e_bivector[1];
+        self.e12[i] = m.e_bivector[2];
+        self.e01[i] = m.v_bivector[0];
+        self.e02[i] = m.v_bivector[1];
+        self.e03[i] = m.v_bivector[2];
+        self.e0123[i] = m.pseudo;
+    }
+    pub fn iter(&self) -> impl Iterator<Item = Motor> + '_ {
+        (0..self.len()).map(move |i| self.get(i))
+    }
+
+    /// Element wise self[i] * other[i].
+    pub fn mul(&self, other: &Self) -> Self {
+        assert_eq!(self.len(), other.len());
+        let mut out = Self::zeros(self.len());
+        lanes(self.slices(), other.slices(), out.slices_mut(), |a, b| {
+            from_motor(&products::geometric_motor_motor(
+                &to_motor(&a),
+                &to_motor(&b),
+            ))
+        });
+        out
+    }
+
+    /// self[i] * m for every motor, e.g., to add the same parent transform.
+    pub fn mul_motor(&self, m: &Motor) -> Self {
+        let mut out = Self::zeros(self.len());
+        lanes(self.slices(), [], out.slices_mut(), |a, []| {
+            from_motor(&products::geometric_motor_motor(&to_motor(&a), m))
+        });
+        out
+    }
+
+    /// Applies motor i to point i.
+    pub fn apply_to_points(&self, ps: &PointSoA) -> PointSoA {
+        assert_eq!(self.len(), ps.len());
+        let n = self.len();
+        let mut out = PointSoA {
+            e123: vec![0.; n],
+            e032: vec![0.; n],
+            e013: vec![0.; n],
+            e021: vec![0.; n],
+        };
+        lanes(self.slices(), ps.slices(), out.slices_mut(), |m, t| {
+            products::sandwich_motor_point(&to_motor(&m), &Point { trivector: t }).trivector
+        });
+        out
+    }
+
+    /// Motor::normalize of every motor.
+    pub fn normalize(&mut self) {
+        lanes_mut(self.slices_mut(), |m| {
+            let f = 1. / (m[0] * m[0] + m[1] * m[1] + m[2] * m[2] + m[3] * m[3]).sqrt();
+            m.map(|x| x * f)
+        });
+    }
+
+    /// The component arrays in the order of from_motor.
+    fn slices(&self) -> [&[f32]; 8] {
+        [
+            &self.scalar,
+            &self.e23,
+            &self.e31,
+            &self.e12,
+            &self.e01,
+            &self.e02,
+            &self.e03,
+            &self.e0123,
+        ]
+    }
+    fn slices_mut(&mut self) -> [&mut [f32]; 8] {
+        [
+            &mut self.scalar,
+            &mut self.e23,
+            &mut self.e31,
+            &mut self.e12,
+            &mut self.e01,
+            &mut self.e02,
+            &mut self.e03,
+            &mut self.e0123,
+        ]
+    }
+}
+
+fn from_motor(m: &Motor) -> [f32; 8] {
+    let (e, v) = (m.e_bivector, m.v_bivector);
+    [m.scalar, e[0], e[1], e[2], v[0], v[1], v[2], m.pseudo]
+}
+
+#[inline(always)]
+fn to_motor(m: &[f32; 8]) -> Motor {
+    Motor {
+        scalar: m[0],
+        e_bivector: [m[1], m[2], m[3]],
+        v_bivector: [m[4], m[5], m[6]],
+        pseudo: m[7],
+    }
+}
+
+/// Line::exp on a tangent [e, v].
+#[inline(always)]
+fn exp(t: &[f32; 6]) -> [f32; 8] {
+    let m = super::line::exp(&[t[0], t[1], t[2]], &[t[3], t[4], t[5]]);
+    let [[s, e0, e1, e2], [p, v0, v1, v2]] = m.into_klein();
+    [s, e0, e1, e2, v0, v1, v2, p]
+}
+
+const LANES: usize = 8;
+
+/// Runs f on element i of the arrays a and b and writes the result to
+/// element i of the arrays out, for the first out[0].len() elements.
+#[inline(always)]
+fn lanes<const A: usize, const B: usize, const O: usize>(
+    a: [&[f32]; A],
+    b: [&[f32]; B],
+    mut out: [&mut [f32]; O],
+    f: impl Fn([f32; A], [f32; B]) -> [f32; O],
+) {
+    let n = out[0].len();
+    let mut start = 0;
+    while start < n {
+        let len = LANES.min(n - start);
+        let x = load(&a, start, len);
+        let y = load(&b, start, len);
+        let mut z = [[0.0; LANES]; O];
+        for l in 0..LANES {
+            let r = f(
+                std::array::from_fn(|c| x[c][l]),
+                std::array::from_fn(|c| y[c][l]),
+            );
+            for (z, r) in z.iter_mut().zip(r.iter()) {
+                z[l] = *r;
+            }
+        }
+        store(&z, &mut out, start, len);
+        start += len;
+    }
+}
+
+/// lanes with the input and output arrays being the same.
+#[inline(always)]
+fn lanes_mut<const A: usize>(mut a: [&mut [f32]; A], f: impl Fn([f32; A]) -> [f32; A]) {
+    let n = a[0].len();
+    let mut start = 0;
+    while start < n {
+        let len = LANES.min(n - start);
+        let x = load::<A>(&std::array::from_fn(|i| &*a[i]), start, len);
+        let mut z = [[0.0; LANES]; A];
+        for l in 0..LANES {
+            let r = f(std::array::from_fn(|c| x[c][l]));
+            for (z, r) in z.iter_mut().zip(r.iter()) {
+                z[l] = *r;
+            }
+        }
+        store(&z, &mut a, start, len);
+        start += len;
+    }
+}
+
+// Full blocks get a copy of fixed size, which compiles to a few moves
+// instead of a call to memcpy.
+#[inline(always)]
+fn load<const A: usize>(a: &[&[f32]; A], start: usize, len: usize) -> [[f32; LANES]; A] {
+    let mut x = [[0.0; LANES]; A];
+    for (x, a) in x.iter_mut().zip(a.iter()) {
+        if len == LANES {
+            x.copy_from_slice(&a[start..start + LANES]);
+        } else {
+            x[..len].copy_from_slice(&a[start..start + len]);
+        }
+    }
+    x
+}
+
+#[inline(always)]
+fn store<const A: usize>(
+    z: &[[f32; LANES]; A],
+    out: &mut [&mut [f32]; A],
+    start: usize,
+    len: usize,
+) {
+    for (z, o) in z.iter().zip(out.iter_mut()) {
+        if len == LANES {
+            o[start..start + LANES].copy_from_slice(z);
+        } else {
+            o[start..start + len].copy_from_slice(&z[..len]);
+        }
+    }
+}
+
+impl From<&[Point]> for PointSoA {
+    fn from(ps: &[Point]) -> Self {
+        let mut soa = Self::with_capacity(ps.len());
+        ps.iter().for_each(|p| soa.push(p));
+        soa
+    }
+}
+impl From<&PointSoA> for Vec<Point> {
+    fn from(soa: &PointSoA) -> Self {
+        soa.iter().collect()
+    }
+}
+
+impl From<&[Line]> for LineSoA {
+    fn from(ls: &[Line]) -> Self {
+        let mut soa = Self::new();
+        ls.iter().for_each(|l| soa.push(l));
+        soa
+    }
+}
+impl From<&LineSoA> for Vec<Line> {
+    fn from(soa: &LineSoA) -> Self {
+        soa.iter().collect()
+    }
+}
+
+impl From<&[Motor]> for MotorSoA {
+    fn from(ms: &[Motor]) -> Self {
+        let mut soa = Self::new();
+        ms.iter().for_each(|m| soa.push(m));
+        soa
+    }
+}
+impl From<&MotorSoA> for Vec<Motor> {
+    fn from(soa: &MotorSoA) -> Self {
+        soa.iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soa::*;
+    use crate::*;
+
+    #[test]
+    fn round_trip() {
+        let motors: Vec<Motor> = (0..10).map(|_| Motor::random()).collect();
+        let soa = MotorSoA::from(&motors[..]);
+        assert_eq!(soa.len(), 10);
+        assert_eq!(Vec::<Motor>::from(&soa), motors);
+
+        let points: Vec<Point> = (0..10).map(|_| Point::random()).collect();
+        let soa = PointSoA::from(&points[..]);
+        assert_eq!(Vec::<Point>::from(&soa), points);
+
+        let lines: Vec<Line> = (0..10).map(|_| Line::random()).collect();
+        let soa = LineSoA::from(&lines[..]);
+        for (l, l_) in lines.iter().zip(soa.iter()) {
+            assert_eq!(l.tangent(), l_.tangent());
+        }
+    }
+
+    #[test]
+    fn kernels() {
+        let lines: Vec<Line> = (0..50).map(|_| Line::random()).collect();
+        let points: Vec<Point> = (0..50).map(|_| Point::random()).collect();
+        let lsoa = LineSoA::from(&lines[..]);
+        let mut msoa = lsoa.exp();
+        msoa.normalize();
+        let composed = msoa.mul(&msoa);
+        let moved = composed.apply_to_points(&PointSoA::from(&points[..]));
+        for i in 0..50 {
+            let m = lines[i].exp().normalize();
+            assert_eq!(msoa.get(i), m);
+            let mm = products::geometric_motor_motor(&m, &m);
+            assert_eq!(composed.get(i), mm);
+            assert_eq!(
+                moved.get(i),
+                products::sandwich_motor_point(&mm, &points[i])
+            );
+        }
+
+        let m = Motor::random().normalize();
+        let mut psoa = PointSoA::from(&points[..]);
+        psoa.apply_motor(&m);
+        psoa.normalize();
+        for (p, p_) in points.iter().zip(psoa.iter()) {
+            assert_eq!(m.apply_to_point(p).normalize(), p_);
+        }
+    }
+
+    #[test]
+    fn exp_near_zero() {
+        let lines: Vec<Line> = (0..50)
+            .map(|i| Line::random().mul_scalar(0.001 * i as f32))
+            .collect();
+        let msoa = LineSoA::from(&lines[..]).exp();
+        for (l, m) in lines.iter().zip(msoa.iter()) {
+            assert_eq!(l.exp(), m);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn lengths() {
+        let a = MotorSoA::from(&[Motor::random(), Motor::random()][..]);
+        let b = MotorSoA::from(&[Motor::random()][..]);
+        a.mul(&b);
+    }
+}
@@ -0,0 +1,139 @@
+//! A small block-sparse linear solver for the normal equations
+//! that show up when optimizing over many motors. Every block is
+//! 6x6, one row/column of blocks per motor.
+
+use std::collections::BTreeMap;
+
+pub type Block = na::Matrix6<f32>;
+pub type BlockVector = na::Vector6<f32>;
+
+/// Symmetric block-sparse matrix. Only the blocks that were
+/// added are stored, missing blocks are zero.
+#[derive(Debug, Clone)]
+pub struct BlockSparse {
+    n: usize,
+    blocks: BTreeMap<(usize, usize), Block>,
+}
+
+impl BlockSparse {
+    /// An n by n (in blocks) zero matrix.
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            blocks: BTreeMap::new(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn nonzero_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn add(&mut self, row: usize, col: usize, b: &Block) {
+        *self.blocks.entry((row, col)).or_insert_with(Block::zeros) += b;
+    }
+
+    pub fn add_diagonal(&mut self, lambda: f32) {
+        for i in 0..self.n {
+            self.add(i, i, &(Block::identity() * lambda));
+        }
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Block {
+        self.blocks
+            .get(&(row, col))
+            .copied()
+            .unwrap_or_else(Block::zeros)
+    }
+
+    pub fn mul_vector(&self, x: &[BlockVector]) -> Vec<BlockVector> {
+        let mut y = vec![BlockVector::zeros(); self.n];
+        for ((r, c), b) in self.blocks.iter() {
+            y[*r] += b * x[*c];
+        }
+        y
+    }
+
+    /// Solves self * x = b with conjugate gradients, preconditioned with the
+    /// inverse of the diagonal blocks. The matrix should be symmetric
+    /// positive definite. Returns the solution and the number of iterations.
+    pub fn solve(
+        &self,
+        b: &[BlockVector],
+        max_iterations: usize,
+        tolerance: f32,
+    ) -> (Vec<BlockVector>, usize) {
+        let precond: Vec<Block> = (0..self.n)
+            .map(|i| self.get(i, i).try_inverse().unwrap_or_else(Block::identity))
+            .collect();
+        let apply_precond = |r: &[BlockVector]| -> Vec<BlockVector> {
+            r.iter().zip(precond.iter()).map(|(r, p)| p * r).collect()
+        };
+
+        let mut x = vec![BlockVector::zeros(); self.n];
+        let mut r = b.to_vec();
+        let mut z = apply_precond(&r);
+        let mut p = z.clone();
+        let mut rz = dot(&r, &z);
+        let b_norm = dot(b, b).sqrt();
+        if b_norm == 0.0 {
+            return (x, 0);
+        }
+
+        for it in 0..max_iterations {
+            let ap = self.mul_vector(&p);
+            let pap = dot(&p, &ap);
+            if pap <= 0.0 {
+                return (x, it);
+            }
+            let alpha = rz / pap;
+            for i in 0..self.n {
+                x[i] += p[i] * alpha;
+                r[i] -= ap[i] * alpha;
+            }
+            if dot(&r, &r).sqrt() < tolerance * b_norm {
+                return (x, it + 1);
+            }
+            z = apply_precond(&r);
+            let rz_new = dot(&r, &z);
+            let beta = rz_new / rz;
+            rz = rz_new;
+            for i in 0..self.n {
+                p[i] = z[i] + p[i] * beta;
+            }
+        }
+        (x, max_iterations)
+    }
+}
+
+fn dot(a: &[BlockVector], b: &[BlockVector]) -> f32 {
+    a.iter().zip(b.iter()).map(|(a, b)| a.dot(b)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_chain() {
+        // Tridiagonal (in blocks) system, like a chain of motors.
+        let n = 50;
+        let mut a = BlockSparse::new(n);
+        for i in 0..n {
+            a.add(i, i, &(Block::identity() * 4.0));
+            if i + 1 < n {
+                a.add(i, i + 1, &(Block::identity() * -1.0));
+                a.add(i + 1, i, &(Block::identity() * -1.0));
+            }
+        }
+        let x_true: Vec<BlockVector> = (0..n).map(|_| BlockVector::new_random()).collect();
+        let b = a.mul_vector(&x_true);
+        let (x, _) = a.solve(&b, 200, 1e-6);
+        for (x, x_true) in x.iter().zip(x_true.iter()) {
+            assert!((x - x_true).norm() < 1e-3);
+        }
+    }
+}
@@ -32,34 +32,11 @@ impl Translator {
     }
 
     pub fn apply_to_point(&self, p: &super::Point) -> super::Point {
-        let v = self.v_bivector;
-        let s = self.scalar;
-        let p = p.trivector;
-        super::Point {
-            trivector: [
-                p[0] * s * s,
-                s * (-2. * p[0] * v[0] + p[1] * s),
-                s * (-2. * p[0] * v[1] + p[2] * s),
-                s * (-2. * p[0] * v[2] + p[3] * s),
-            ],
-        }
+        super::products::sandwich_translator_point(self, p)
     }
 
     pub fn mul_rotor(&self, r: &super::Rotor) -> super::Motor {
-        let v = self.v_bivector;
-        let e = r.e_bivector;
-        let ts = self.scalar;
-        let rs = r.scalar;
-        super::Motor {
-            scalar: rs * ts,
-            pseudo: e[0] * v[0] + e[1] * v[1] + e[2] * v[2],
-            e_bivector: [e[0] * ts, e[1] * ts, e[2] * ts],
-            v_bivector: [
-                e[1] * v[2] - e[2] * v[1] + rs * v[0],
-                -e[0] * v[2] + e[2] * v[0] + rs * v[1],
-                e[0] * v[1] - e[1] * v[0] + rs * v[2],
-            ],
-        }
+        super::products::geometric_translator_rotor(self, r)
     }
 
     pub fn add_scalar(&self, s: f32) -> Self {
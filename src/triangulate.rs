@@ -0,0 +1,197 @@
+//! Triangulation of points and lines seen by several cameras. The
+//! observations are given per camera, pixels[i] or lines[i] in cameras[i].
+
+use super::camera::Camera;
+use super::d2::Line2;
+use super::{meet, Line, Plane, Point};
+
+/// Rays and planes whose angle has a squared sine below this are parallel.
+pub const PARALLEL: f32 = 1e-6;
+
+/// The point closest to all back projected rays in the least squares
+/// sense, for two views the midpoint of their closest approach. None for
+/// fewer than two views or parallel rays.
+pub fn midpoint(cameras: &[Camera], pixels: &[[f32; 2]]) -> Option<Point> {
+    let (a, b) = rays(cameras, pixels)?;
+    let x = a.try_inverse()? * b;
+    Some(Point::new(&x.into()))
+}
+
+/// The direct linear transform. Each view asks the point to project onto
+/// its image point, two linear equations in the homogeneous point, and
+/// the least squares solution is the smallest eigenvector of A^T A.
+pub fn linear(cameras: &[Camera], pixels: &[[f32; 2]]) -> Option<Point> {
+    rays(cameras, pixels)?;
+    let mut ata = na::Matrix4::zeros();
+    for (c, pixel) in cameras.iter().zip(pixels.iter()) {
+        let [x, y] = c.image_point(pixel);
+        let e = c.pose.reverse().matrix().eucl;
+        let row = |i: usize| na::Vector4::from(e[i]);
+        for r in [row(2) * x - row(0), row(2) * y - row(1)].iter() {
+            ata += r * r.transpose();
+        }
+    }
+    let eig = na::SymmetricEigen::new(ata);
+    let v = eig.eigenvectors.column(eig.eigenvalues.imin());
+    Some(Point::new(&[v[0] / v[3], v[1] / v[3], v[2] / v[3]]))
+}
+
+/// The root mean square distance in pixels between the projections of
+/// the point and the observations. None if it is behind a camera.
+pub fn reprojection_error(cameras: &[Camera], pixels: &[[f32; 2]], p: &Point) -> Option<f32> {
+    let mut sum = 0.0;
+    for (c, pixel) in cameras.iter().zip(pixels.iter()) {
+        let [u, v] = c.project(p)?;
+        sum += (u - pixel[0]).powi(2) + (v - pixel[1]).powi(2);
+    }
+    Some((sum / cameras.len() as f32).sqrt())
+}
+
+/// The meet of the planes back projected from the image lines. With more
+/// than two views, the meet of the two planes spanning the pencil closest
+/// to all of them. None for fewer than two views or when the planes are
+/// the same, e.g., for a line in the plane of the camera centers.
+pub fn line(cameras: &[Camera], lines: &[Line2]) -> Option<Line> {
+    assert_eq!(cameras.len(), lines.len());
+    let planes: Vec<Plane> = cameras
+        .iter()
+        .zip(lines.iter())
+        .map(|(c, l)| c.back_project_line(l))
+        .collect();
+    let (a, b) = match planes.len() {
+        0 | 1 => return None,
+        2 => (planes[0].normalize(), planes[1].normalize()),
+        _ => {
+            let m = planes
+                .iter()
+                .map(|p| na::Vector4::from(p.vector) * na::Vector4::from(p.vector).transpose())
+                .sum::<na::Matrix4<f32>>();
+            let eig = na::SymmetricEigen::new(m);
+            let mut order = [0, 1, 2, 3];
+            order.sort_by(|i, j| eig.eigenvalues[*j].total_cmp(&eig.eigenvalues[*i]));
+            let plane = |i: usize| {
+                let v = eig.eigenvectors.column(order[i]);
+                Plane {
+                    vector: [v[0], v[1], v[2], v[3]],
+                }
+                .normalize()
+            };
+            (plane(0), plane(1))
+        }
+    };
+    let l = meet::planes(&a, &b);
+    if l.norm().powi(2) < PARALLEL {
+        return None;
+    }
+    Some(l.div_scalar(l.norm()))
+}
+
+/// The normal equations of midpoint, sum (1 - d d^T) and sum (1 - d d^T) c
+/// for the rays c + t d. None when they are singular.
+fn rays(cameras: &[Camera], pixels: &[[f32; 2]]) -> Option<(na::Matrix3<f32>, na::Vec3)> {
+    assert_eq!(cameras.len(), pixels.len());
+    if cameras.len() < 2 {
+        return None;
+    }
+    let mut a = na::Matrix3::zeros();
+    let mut b = na::Vec3::zeros();
+    for (c, pixel) in cameras.iter().zip(pixels.iter()) {
+        let d = na::Vec3::from(c.back_project(pixel).e_bivector).normalize();
+        let m = na::Matrix3::identity() - d * d.transpose();
+        a += m;
+        b += m * na::Vec3::from(c.center().normalize().eucl());
+    }
+    // The smallest eigenvalue is about the squared sine of the angle
+    // between the rays, 0 when they are parallel.
+    if a.symmetric_eigenvalues().min() < PARALLEL * cameras.len() as f32 {
+        return None;
+    }
+    Some((a, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::camera::Camera;
+    use crate::triangulate::*;
+    use crate::*;
+
+    /// Cameras on a circle of radius 10 around the y axis, looking at
+    /// the origin.
+    fn cameras() -> Vec<Camera> {
+        [-0.6, 0.1, 0.9]
+            .iter()
+            .map(|a| {
+                let pose =
+                    Rotor::new(*a, &[0., 1., 0.]).mul_translator(&Translator::new(&[0., 0., -10.]));
+                Camera::new(&pose, &[800., 800.], &[320., 240.])
+            })
+            .collect()
+    }
+
+    fn close(a: &Point, b: &Point) -> bool {
+        a.is_similar_to(1e-2, b)
+    }
+
+    #[test]
+    fn points() {
+        let cameras = cameras();
+        let p = Point::new(&[0.5, -1., 2.]);
+        let pixels: Vec<[f32; 2]> = cameras.iter().map(|c| c.project(&p).unwrap()).collect();
+        for n in 2..=3 {
+            let (c, x) = (&cameras[..n], &pixels[..n]);
+            let m = midpoint(c, x).unwrap();
+            let l = linear(c, x).unwrap();
+            assert!(close(&m, &p), "{:?}", m);
+            assert!(close(&l, &p), "{:?}", l);
+            assert!(reprojection_error(c, x, &l).unwrap() < 0.1);
+        }
+        let noisy: Vec<[f32; 2]> = pixels.iter().map(|x| [x[0] + 0.5, x[1] - 0.5]).collect();
+        let l = linear(&cameras, &noisy).unwrap();
+        let error = reprojection_error(&cameras, &noisy, &l).unwrap();
+        assert!(0.0 < error && error < 1.0);
+        assert!(midpoint(&cameras[..1], &pixels[..1]).is_none());
+    }
+
+    #[test]
+    fn parallel() {
+        let cameras: Vec<Camera> = [-1., 1.]
+            .iter()
+            .map(|x| {
+                Camera::new(
+                    &Motor::from(&Translator::new(&[*x, 0., 0.])),
+                    &[800., 800.],
+                    &[320., 240.],
+                )
+            })
+            .collect();
+        let pixels = [[320., 240.], [320., 240.]];
+        assert!(midpoint(&cameras, &pixels).is_none());
+        assert!(linear(&cameras, &pixels).is_none());
+        assert!(reprojection_error(&cameras, &pixels, &Point::new(&[0., 0., -1.])).is_none());
+    }
+
+    #[test]
+    fn lines() {
+        let cameras = cameras();
+        let (a, b) = (Point::new(&[1., -1., 2.]), Point::new(&[-2., 0.5, -1.]));
+        let l = join::points(&a, &b);
+        let images: Vec<_> = cameras
+            .iter()
+            .map(|c| c.project_line(&l).unwrap())
+            .collect();
+        for n in 2..=3 {
+            let t = line(&cameras[..n], &images[..n]).unwrap();
+            // On the line, both points have no distance to any plane through it.
+            for p in [&a, &b].iter() {
+                assert!(join::line_to_point(&t, p).norm() < 1e-3);
+            }
+        }
+        // The x axis lies in the plane of the camera centers.
+        let x = Line::new(&[0., 0., 0.], &[1., 0., 0.]);
+        let images: Vec<_> = cameras
+            .iter()
+            .map(|c| c.project_line(&x).unwrap())
+            .collect();
+        assert!(line(&cameras[..2], &images[..2]).is_none());
+    }
+}
@@ -0,0 +1,545 @@
+//! URDF robot descriptions, a tree of links connected by joints.
+//!
+//! The origin of a joint is the motor from its parent link to its child
+//! link at q = 0, T(xyz) * Rz(yaw) * Ry(pitch) * Rx(roll). The axis is the
+//! line through the joint origin in the parent link, so that moving the
+//! joint is exp(-q/2 axis) for revolute joints and a translation by q
+//! along it for prismatic ones, applied after the origin.
+//!
+//! Only links, joints and their origins, axes and limits are read. The
+//! XML is parsed just enough for that, without DTDs or namespaces.
+
+use super::{Line, Motor, ParseError, Rotor, Translator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JointType {
+    Revolute,
+    Continuous,
+    Prismatic,
+    Fixed,
+}
+
+/// Radians for revolute and meters for prismatic joints.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    pub lower: f32,
+    pub upper: f32,
+    pub effort: f32,
+    pub velocity: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Joint {
+    pub name: String,
+    pub kind: JointType,
+    /// Indices into Robot::links.
+    pub parent: usize,
+    pub child: usize,
+    pub origin: Motor,
+    /// Normalized, in the parent link.
+    pub axis: Line,
+    pub limits: Option<Limits>,
+}
+
+impl Joint {
+    /// The motor from the parent to the child link with the joint at q.
+    /// Positions outside the limits are clamped.
+    pub fn motor(&self, q: f32) -> Motor {
+        let q = match self.limits {
+            Some(l) if self.kind != JointType::Continuous => q.max(l.lower).min(l.upper),
+            _ => q,
+        };
+        let motion = match self.kind {
+            JointType::Revolute | JointType::Continuous => self.axis.mul_scalar(-0.5 * q).exp(),
+            JointType::Prismatic => {
+                let d = self.axis.e_bivector;
+                Motor::from(&Translator::new(&[q * d[0], q * d[1], q * d[2]]))
+            }
+            JointType::Fixed => Motor::one(),
+        };
+        motion.mul(&self.origin)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Robot {
+    pub name: String,
+    pub links: Vec<String>,
+    /// Sorted from the root, parents before children.
+    pub joints: Vec<Joint>,
+}
+
+impl Robot {
+    pub fn parse(src: &str) -> Result<Self, ParseError> {
+        let root = Xml { src, pos: 0 }.document()?;
+        if root.name != "robot" {
+            return root.error(format!("expected robot, found {}", root.name));
+        }
+        let links: Vec<String> = root
+            .children("link")
+            .map(|l| l.attribute("name").map(str::to_string))
+            .collect::<Result<_, _>>()?;
+        let link = |e: &Element, tag: &str| {
+            let c = e.child(tag)?;
+            let name = c.attribute("link")?;
+            match links.iter().position(|l| l == name) {
+                Some(i) => Ok(i),
+                None => c.error(format!("unknown link {}", name)),
+            }
+        };
+        let mut joints = Vec::new();
+        for e in root.children("joint") {
+            let kind = match e.attribute("type")? {
+                "revolute" => JointType::Revolute,
+                "continuous" => JointType::Continuous,
+                "prismatic" => JointType::Prismatic,
+                "fixed" => JointType::Fixed,
+                t => return e.error(format!("unsupported joint type {}", t)),
+            };
+            let (xyz, rpy) = match e.children("origin").next() {
+                Some(o) => (o.vector("xyz")?, o.vector("rpy")?),
+                None => ([0.0; 3], [0.0; 3]),
+            };
+            let origin = Motor::from(&Translator::new(&xyz))
+                .mul(&Motor::from(&Rotor::new(rpy[2], &[0., 0., 1.])))
+                .mul(&Motor::from(&Rotor::new(rpy[1], &[0., 1., 0.])))
+                .mul(&Motor::from(&Rotor::new(rpy[0], &[1., 0., 0.])));
+            let axis = match e.children("axis").next() {
+                Some(a) => a.vector("xyz")?,
+                None => [1., 0., 0.],
+            };
+            let dir = na::Vec3::from(axis);
+            if dir.norm() < 1e-6 {
+                return e.error("zero axis".to_string());
+            }
+            let dir = origin
+                .apply_to_point(&super::Point::inf(&dir.normalize().into()))
+                .eucl();
+            let limits = match e.children("limit").next() {
+                Some(l) => Some(Limits {
+                    lower: l.number("lower", 0.0)?,
+                    upper: l.number("upper", 0.0)?,
+                    effort: l.number("effort", 0.0)?,
+                    velocity: l.number("velocity", 0.0)?,
+                }),
+                None if kind == JointType::Revolute || kind == JointType::Prismatic => {
+                    return e.error("missing limit".to_string())
+                }
+                None => None,
+            };
+            joints.push((
+                e,
+                Joint {
+                    name: e.attribute("name")?.to_string(),
+                    kind,
+                    parent: link(e, "parent")?,
+                    child: link(e, "child")?,
+                    origin,
+                    axis: Line::new(&xyz, &dir),
+                    limits,
+                },
+            ));
+        }
+
+        // Sorted breadth first from the one link that is no child.
+        let mut parent = vec![None; links.len()];
+        for (e, j) in joints.iter() {
+            if parent[j.child].replace(j.parent).is_some() {
+                return e.error(format!("link {} has two parents", links[j.child]));
+            }
+        }
+        let roots: Vec<usize> = (0..links.len()).filter(|l| parent[*l].is_none()).collect();
+        if roots.len() != 1 {
+            return root.error(format!("expected one root link, found {}", roots.len()));
+        }
+        let mut order = roots;
+        let mut sorted = Vec::with_capacity(joints.len());
+        let mut i = 0;
+        while i < order.len() {
+            let link = order[i];
+            for (_, j) in joints.iter().filter(|(_, j)| j.parent == link) {
+                order.push(j.child);
+                sorted.push(j.clone());
+            }
+            i += 1;
+        }
+        if sorted.len() != joints.len() {
+            return root.error("the joints form a cycle".to_string());
+        }
+        Ok(Self {
+            name: root.attribute("name")?.to_string(),
+            links,
+            joints: sorted,
+        })
+    }
+
+    pub fn link(&self, name: &str) -> Option<usize> {
+        self.links.iter().position(|l| l == name)
+    }
+    pub fn joint(&self, name: &str) -> Option<usize> {
+        self.joints.iter().position(|j| j.name == name)
+    }
+
+    /// The link without a parent.
+    pub fn root(&self) -> usize {
+        (0..self.links.len())
+            .find(|l| self.joints.iter().all(|j| j.child != *l))
+            .unwrap()
+    }
+
+    /// The motors of all links in the root link for the joint positions,
+    /// one per joint. Fixed joints ignore theirs.
+    pub fn forward(&self, q: &[f32]) -> Vec<Motor> {
+        assert_eq!(q.len(), self.joints.len());
+        let mut links = vec![Motor::one(); self.links.len()];
+        for (j, q) in self.joints.iter().zip(q.iter()) {
+            links[j.child] = links[j.parent].mul(&j.motor(*q));
+        }
+        links
+    }
+
+    /// The joint axes in the root link for the joint positions.
+    pub fn axes(&self, q: &[f32]) -> Vec<Line> {
+        let links = self.forward(q);
+        self.joints
+            .iter()
+            .map(|j| links[j.parent].apply_to(&j.axis))
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+struct Element<'a> {
+    name: &'a str,
+    attributes: Vec<(&'a str, String)>,
+    children: Vec<Element<'a>>,
+    line: usize,
+}
+
+impl<'a> Element<'a> {
+    fn error<T>(&self, message: String) -> Result<T, ParseError> {
+        Err(ParseError {
+            line: self.line,
+            message,
+        })
+    }
+
+    fn children<'b>(&'b self, name: &'b str) -> impl Iterator<Item = &'b Element<'a>> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    fn child(&self, name: &str) -> Result<&Element<'a>, ParseError> {
+        match self.children.iter().find(|c| c.name == name) {
+            Some(c) => Ok(c),
+            None => self.error(format!("{} without {}", self.name, name)),
+        }
+    }
+
+    fn attribute(&self, name: &str) -> Result<&str, ParseError> {
+        match self.attributes.iter().find(|a| a.0 == name) {
+            Some(a) => Ok(&a.1),
+            None => self.error(format!("{} without {}", self.name, name)),
+        }
+    }
+
+    /// The default when it is missing.
+    fn number(&self, name: &str, default: f32) -> Result<f32, ParseError> {
+        if self.attributes.iter().all(|a| a.0 != name) {
+            return Ok(default);
+        }
+        let a = self.attribute(name)?;
+        match a.trim().parse() {
+            Ok(x) => Ok(x),
+            Err(_) => self.error(format!("{} is not a number: {}", name, a)),
+        }
+    }
+
+    /// Three numbers, zero when missing.
+    fn vector(&self, name: &str) -> Result<[f32; 3], ParseError> {
+        if self.attributes.iter().all(|a| a.0 != name) {
+            return Ok([0.0; 3]);
+        }
+        let a = self.attribute(name)?;
+        let v: Vec<f32> = match a.split_whitespace().map(str::parse).collect() {
+            Ok(v) => v,
+            Err(_) => return self.error(format!("{} is not a vector: {}", name, a)),
+        };
+        match v[..] {
+            [x, y, z] => Ok([x, y, z]),
+            _ => self.error(format!("{} has {} components", name, v.len())),
+        }
+    }
+}
+
+struct Xml<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Xml<'a> {
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn line(&self) -> usize {
+        self.src[..self.pos].matches('\n').count() + 1
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, ParseError> {
+        Err(ParseError {
+            line: self.line(),
+            message,
+        })
+    }
+
+    /// Skips past the end, or fails at the end of the file.
+    fn skip_past(&mut self, end: &str) -> Result<(), ParseError> {
+        match self.rest().find(end) {
+            Some(i) => {
+                self.pos += i + end.len();
+                Ok(())
+            }
+            None => self.error(format!("missing {}", end)),
+        }
+    }
+
+    /// Skips text, comments, declarations and processing instructions up
+    /// to the next tag.
+    fn skip(&mut self) -> Result<(), ParseError> {
+        loop {
+            match self.rest().find('<') {
+                Some(i) => self.pos += i,
+                None => {
+                    self.pos = self.src.len();
+                    return Ok(());
+                }
+            }
+            if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn document(&mut self) -> Result<Element<'a>, ParseError> {
+        self.skip()?;
+        if self.pos == self.src.len() {
+            return self.error("no element".to_string());
+        }
+        let e = self.element()?;
+        self.skip()?;
+        if self.pos != self.src.len() {
+            return self.error("more than one root element".to_string());
+        }
+        Ok(e)
+    }
+
+    fn name(&mut self) -> &'a str {
+        let rest = self.rest();
+        let n = rest
+            .find(|c: char| c.is_whitespace() || c == '/' || c == '>' || c == '=')
+            .unwrap_or(rest.len());
+        self.pos += n;
+        &rest[..n]
+    }
+
+    fn whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// At the <.
+    fn element(&mut self) -> Result<Element<'a>, ParseError> {
+        let line = self.line();
+        self.pos += 1;
+        let name = self.name();
+        if name.is_empty() {
+            return self.error("expected a tag name".to_string());
+        }
+        let mut e = Element {
+            name,
+            attributes: Vec::new(),
+            children: Vec::new(),
+            line,
+        };
+        loop {
+            self.whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok(e);
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let key = self.name();
+            self.whitespace();
+            if key.is_empty() || !self.rest().starts_with('=') {
+                return self.error(format!("expected an attribute in {}", name));
+            }
+            self.pos += 1;
+            self.whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(q) if q == '"' || q == '\'' => q,
+                _ => return self.error(format!("unquoted attribute {}", key)),
+            };
+            self.pos += 1;
+            let value = match self.rest().find(quote) {
+                Some(i) => &self.rest()[..i],
+                None => return self.error(format!("unterminated attribute {}", key)),
+            };
+            self.pos += value.len() + 1;
+            e.attributes.push((key, unescape(value)));
+        }
+        loop {
+            self.skip()?;
+            if self.pos == self.src.len() {
+                return self.error(format!("unclosed {}", name));
+            }
+            if self.rest().starts_with("</") {
+                self.pos += 2;
+                let end = self.name();
+                if end != name {
+                    return self.error(format!("expected </{}>, found </{}>", name, end));
+                }
+                self.whitespace();
+                self.skip_past(">")?;
+                return Ok(e);
+            }
+            e.children.push(self.element()?);
+        }
+    }
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::urdf::*;
+    use crate::*;
+
+    const ARM: &str = r#"<?xml version="1.0"?>
+<!-- A planar arm on a rail, with a gripper. -->
+<robot name="arm">
+  <link name="base"/>
+  <link name="upper"><visual><geometry><box size="1 1 1"/></geometry></visual></link>
+  <link name="slider"/>
+  <link name="lower"/>
+  <link name="wrist"/>
+  <link name="tool"/>
+  <joint name="elbow" type="revolute">
+    <parent link="upper"/>
+    <child link="lower"/>
+    <origin xyz="0 0 1" rpy="0 0 0"/>
+    <axis xyz="0 1 0"/>
+    <limit lower="-2" upper="2" effort="10" velocity="2"/>
+  </joint>
+  <joint name="rail" type="prismatic">
+    <parent link="base"/>
+    <child link="slider"/>
+    <axis xyz="2 0 0"/>
+    <limit lower="0" upper="0.5" effort="100" velocity="0.1"/>
+  </joint>
+  <joint name="shoulder" type="continuous">
+    <parent link="slider"/>
+    <child link="upper"/>
+    <origin xyz="0 0 0.5" rpy="0 0 1.5707963"/>
+    <axis xyz="0 0 1"/>
+  </joint>
+  <joint name="wrist" type="revolute">
+    <parent link="lower"/>
+    <child link="wrist"/>
+    <origin xyz="0 0 1" rpy="1.5707963 0 0"/>
+    <axis xyz="0 0 1"/>
+    <limit lower="-3" upper="3" effort="1" velocity="1"/>
+  </joint>
+  <joint name="gripper" type="fixed">
+    <parent link="wrist"/>
+    <child link="tool"/>
+    <origin xyz="0 0 0.2"/>
+  </joint>
+</robot>
+"#;
+
+    fn position(m: &Motor) -> Point {
+        m.apply_to_point(&Point::new(&[0., 0., 0.]))
+    }
+
+    #[test]
+    fn parse() {
+        let r = Robot::parse(ARM).unwrap();
+        assert_eq!(r.name, "arm");
+        assert_eq!(r.links.len(), 6);
+        assert_eq!(r.root(), r.link("base").unwrap());
+        let names: Vec<&str> = r.joints.iter().map(|j| j.name.as_str()).collect();
+        assert_eq!(names, ["rail", "shoulder", "elbow", "wrist", "gripper"]);
+        let rail = &r.joints[0];
+        assert_eq!(rail.kind, JointType::Prismatic);
+        assert_eq!(rail.limits.unwrap().upper, 0.5);
+        assert_eq!(rail.axis, Line::new(&[0., 0., 0.], &[1., 0., 0.]));
+        assert!(r.joints[1].limits.is_none());
+        // The wrist axis is z turned by the roll of its origin, -y.
+        let wrist = &r.joints[r.joint("wrist").unwrap()];
+        assert_eq!(wrist.axis, Line::new(&[0., 0., 1.], &[0., -1., 0.]));
+    }
+
+    #[test]
+    fn forward() {
+        let r = Robot::parse(ARM).unwrap();
+        let tool = r.link("tool").unwrap();
+        let links = r.forward(&[0.; 5]);
+        assert_eq!(position(&links[tool]), Point::new(&[0.2, 0., 2.5]));
+
+        // The rail moves along x, clamped to 0.5, and the elbow bends the
+        // lower arm around y, which the shoulder turned to -x.
+        let half = std::f32::consts::FRAC_PI_2;
+        let links = r.forward(&[2., 0., half, 0., 0.]);
+        let lower = r.link("lower").unwrap();
+        assert_eq!(position(&links[lower]), Point::new(&[0.5, 0., 1.5]));
+        let wrist = r.link("wrist").unwrap();
+        assert_eq!(position(&links[wrist]), Point::new(&[0.5, 1., 1.5]));
+
+        // Turning a joint is turning the links after it around its axis.
+        let q = [0.2, 0.7, -0.4, 1.3, 0.];
+        let axes = r.axes(&q);
+        let mut moved = q;
+        moved[3] += 0.5;
+        let turn = axes[3].mul_scalar(-0.25).exp();
+        assert_eq!(
+            position(&r.forward(&moved)[tool]),
+            turn.apply_to_point(&position(&r.forward(&q)[tool]))
+        );
+        // Continuous joints go all the way around.
+        let mut around = q;
+        around[1] += 2. * std::f32::consts::PI;
+        assert_eq!(
+            position(&r.forward(&around)[tool]),
+            position(&r.forward(&q)[tool])
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let e = Robot::parse(&ARM.replace("continuous", "planar")).unwrap_err();
+        assert_eq!(e.line, 23);
+        assert_eq!(e.message, "unsupported joint type planar");
+        let e = Robot::parse(&ARM.replace("<child link=\"upper\"/>", "<child link=\"base\"/>"))
+            .unwrap_err();
+        assert_eq!(e.message, "the joints form a cycle");
+        assert!(Robot::parse(&ARM.replace("</robot>", "")).is_err());
+        assert!(Robot::parse(&ARM.replace("<limit lower=\"0\"", "<lmt lower=\"0\"")).is_err());
+        assert!(Robot::parse("<robot name=\"r\"><link name=\"a\"/></robot>").is_ok());
+    }
+}